@@ -1,36 +1,190 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{ArgGroup, Args, CommandFactory, ErrorKind, Parser, Subcommand};
+use lib::config::handler::{SettingsHandler, SpellUsecase};
+use lib::config::registry::ConfigurationError;
+use lib::core::entity::area_of_effect::{AreaOfEffect, AreaShape, CoverageRule};
+use lib::core::entity::caster::{proficiency_bonus_for_level, Ability, CasterProfile};
+use lib::core::entity::export::ExportFormat;
+use lib::core::entity::spell::{Spell, SpellLevel, SpellLevelError, SpellLevelRange};
+use lib::core::entity::spell_annotation::SpellAnnotationUpdate;
+use lib::core::entity::spell_card::CardFormat;
+use lib::core::entity::spell_deck::SpellWeighting;
+use lib::core::entity::spell_slots::{ClassLevel, Rest};
+use lib::core::usecase::area_of_effect::{AreaOfEffectError, AreaOfEffectInterface};
+use lib::core::usecase::cast::{CastError, CastInterface};
+use lib::core::usecase::caster::{CasterError, CasterInterface};
 use lib::core::usecase::dice::DiceInterface;
-use lib::core::usecase::spell::{SpellError, SpellInterface};
+use lib::core::usecase::export::{ExportError, ExportInterface};
+use lib::core::usecase::reference::{
+    ConditionsDataSourceError, ReferenceError, ReferenceInterface,
+};
+use lib::core::usecase::spell::{SpellError, SpellFilter, SpellInterface, SpellsDataSourceError};
+use lib::core::usecase::spell_annotation::{
+    SpellAnnotationError, SpellAnnotationFilter, SpellAnnotationInterface,
+};
+use lib::core::usecase::spell_card::SpellCardInterface;
+use lib::core::usecase::spell_draw::{SpellDrawError, SpellDrawInterface};
+use lib::core::usecase::spell_import::{SpellImportError, SpellImportInterface};
+use lib::core::usecase::spell_slots::{SpellSlotsError, SpellSlotsInterface};
+use lib::core::usecase::spellbook::{SpellbookError, SpellbookInterface};
+use lib::datasources::common::local_file::LocalFileError;
+use lib::datasources::common::remote_datasource::APIError;
 
-use crate::entity::{dice_set::CliDisplayDiceSet, spell::CliDisplaySpell};
+use crate::entity::{
+    area_of_effect::CliDisplayAoeCoverage, caster::CliDisplayCaster, dice_set::CliDisplayDiceSet,
+    reference::CliDisplayReference, spell::CliDisplaySpell, spell_cast::CliDisplaySpellCast,
+    spell_comparison::CliDisplaySpellComparison, spell_slots::CliDisplaySpellSlots,
+    spellbook::CliDisplaySpellbook,
+};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum CliError {
     Spell(SpellError),
+    Cast(CastError),
+    Spellbook(SpellbookError),
+    SpellSlots(SpellSlotsError),
+    Export(ExportError),
+    Io(std::io::Error),
+    Draw(SpellDrawError),
+    Annotation(SpellAnnotationError),
+    Reference(ReferenceError),
+    AreaOfEffect(AreaOfEffectError),
+    Caster(CasterError),
+    Import(SpellImportError),
+    Configuration(ConfigurationError),
+    LocalFile(LocalFileError),
     // UnknownSubCommand(String),
     // Clap(clap::Error),
     // Dice(DiceError),
 }
 
+/// Names the failed usecase and its error, configuration errors are already readable
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CliError::Spell(err) => write!(f, "Spell({:?})", err),
+            CliError::Cast(err) => write!(f, "Cast({:?})", err),
+            CliError::Spellbook(err) => write!(f, "Spellbook({:?})", err),
+            CliError::SpellSlots(err) => write!(f, "SpellSlots({:?})", err),
+            CliError::Export(err) => write!(f, "Export({:?})", err),
+            CliError::Io(err) => write!(f, "Io({:?})", err),
+            CliError::Draw(err) => write!(f, "Draw({:?})", err),
+            CliError::Annotation(err) => write!(f, "Annotation({:?})", err),
+            CliError::Reference(err) => write!(f, "Reference({:?})", err),
+            CliError::AreaOfEffect(err) => write!(f, "AreaOfEffect({:?})", err),
+            CliError::Caster(err) => write!(f, "Caster({:?})", err),
+            CliError::Import(err) => write!(f, "Import({:?})", err),
+            CliError::Configuration(err) => write!(f, "{}", err),
+            CliError::LocalFile(err) => write!(f, "LocalFile({:?})", err),
+        }
+    }
+}
+
+impl CliError {
+    /// Error of the API behind a failed command, its GraphQL errors are worth printing
+    pub fn api_error(&self) -> Option<&APIError> {
+        let datasource_error = match self {
+            CliError::Spell(SpellError::DataSourceError(err)) => err,
+            CliError::Import(SpellImportError::DataSourceError(err)) => err,
+            CliError::Reference(ReferenceError::DataSourceError(
+                ConditionsDataSourceError::GraphQLError(err),
+            )) => return Some(err),
+            _ => return None,
+        };
+        match datasource_error {
+            SpellsDataSourceError::GraphQLError(err) => Some(err),
+            SpellsDataSourceError::RestError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
 /// CLI for fetching spells and rolling dice.
 #[derive(Parser, Debug)]
 #[clap(version)]
 pub struct Arguments {
     #[clap(subcommand)]
     pub cmd: SubCommand,
+    #[clap(
+        long,
+        global = true,
+        takes_value(false),
+        help = "Only use spells persisted by earlier runs, never the network"
+    )]
+    pub offline: bool,
+}
+
+impl Arguments {
+    /// Datasource picked with `dnd data import --from`
+    pub fn import_from(&self) -> Option<String> {
+        match &self.cmd {
+            SubCommand::Data(DataArgs {
+                action: DataAction::Import(args),
+            }) => args.from.clone(),
+            _ => None,
+        }
+    }
+
+    /// Rejects combinations clap can't tell apart by the argument names, like an exact level
+    /// range
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        match &self.cmd {
+            SubCommand::Spell(SpellArgs {
+                level: Some(LevelArg::Range(range)),
+                exact_level: true,
+                ..
+            }) => Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "The argument '--exact-level' cannot be used with the level range '{}'",
+                    range
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Subcommand, Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum SubCommand {
     /// Enter Spells API
     Spell(SpellArgs),
     /// Enter Dice API
     Dice(DiceArgs),
+    /// Manage known and prepared spells of characters
+    Spellbook(SpellbookArgs),
+    /// Calculate and track spell slots of characters
+    Slots(SlotsArgs),
+    /// Store the spellcasting ability, proficiency and item bonuses of characters
+    Caster(CasterArgs),
+    /// Manage the local spell database
+    Data(DataArgs),
+}
+
+/// Value of `--level`, either a single level or an inclusive range
+#[derive(Clone, Debug)]
+pub enum LevelArg {
+    Level(SpellLevel),
+    Range(SpellLevelRange),
+}
+
+fn parse_level_arg(value: &str) -> Result<LevelArg, SpellLevelError> {
+    match value.contains("..") {
+        true => Ok(LevelArg::Range(value.parse::<SpellLevelRange>()?)),
+        false => Ok(LevelArg::Level(value.parse::<SpellLevel>()?)),
+    }
 }
 
 #[derive(Args, Clone, Debug)]
+#[clap(args_conflicts_with_subcommands = true)]
+#[clap(group(ArgGroup::new("output").args(&["export", "cards"])))]
 /// Get random spell unless name is specified
 pub struct SpellArgs {
+    #[clap(subcommand)]
+    pub action: Option<SpellAction>,
     #[clap(short, long, takes_value(false), help = "Get random spell")]
     pub random: bool,
     #[clap(short, long, help = "Get spell by name")]
@@ -38,9 +192,17 @@ pub struct SpellArgs {
     #[clap(
         short,
         long,
-        help = "Level of spell (by default this is maximum level, get exact with -e)"
+        value_parser = parse_level_arg,
+        help = "Level of spell (0-9) or inclusive range (e.g. 1..3); a single level is the maximum level, get exact with -e"
+    )]
+    pub level: Option<LevelArg>,
+    #[clap(
+        long,
+        takes_value(false),
+        conflicts_with = "level",
+        help = "Get cantrips only"
     )]
-    pub level: Option<f64>,
+    pub cantrips: bool,
     #[clap(
         short,
         long,
@@ -48,8 +210,290 @@ pub struct SpellArgs {
         help = "Comma-separated list of classes"
     )]
     pub classes: Vec<String>,
-    #[clap(short, long, takes_value(false), help = "Get spells for exact <LEVEL>")]
+    #[clap(
+        long = "subclass",
+        value_delimiter = ',',
+        value_name = "SUBCLASSES",
+        help = "Comma-separated list of subclasses, adds their always-prepared spells (e.g. life, devotion, land)"
+    )]
+    pub subclasses: Vec<String>,
+    #[clap(
+        short,
+        long = "tag",
+        value_delimiter = ',',
+        value_name = "TAGS",
+        help = "Comma-separated list of tags the spells must all have"
+    )]
+    pub tags: Vec<String>,
+    #[clap(long, takes_value(false), help = "Get favourite spells only")]
+    pub favourite: bool,
+    #[clap(
+        long,
+        takes_value(false),
+        conflicts_with = "output",
+        help = "Print the conditions and spells mentioned in the description beneath the spell"
+    )]
+    pub expand: bool,
+    #[clap(
+        long,
+        value_name = "CHARACTER",
+        conflicts_with = "output",
+        help = "Show the save DC and spell attack bonus of a character set with `dnd caster set`"
+    )]
+    pub caster: Option<String>,
+    #[clap(
+        short,
+        long,
+        takes_value(false),
+        help = "Get spells for exact <LEVEL>, not with a level range"
+    )]
     pub exact_level: bool,
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "random",
+        help = "Number of distinct random spells to draw"
+    )]
+    pub count: Option<u16>,
+    #[clap(
+        long,
+        requires = "random",
+        help = "Draw from a named deck, spells drawn before are not drawn again"
+    )]
+    pub deck: Option<String>,
+    #[clap(
+        long,
+        takes_value(false),
+        requires = "deck",
+        help = "Put all spells back into the deck before drawing"
+    )]
+    pub reset_deck: bool,
+    #[clap(
+        long,
+        value_parser,
+        requires = "random",
+        value_name = "WEIGHTS",
+        help = "Weight random draws, e.g. level:1=5,2=3,3=1 or school:evocation=2 (unlisted weigh 1)"
+    )]
+    pub weight: Option<SpellWeighting>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "FORMAT",
+        help = "Export the spells as markdown, html, csv or json"
+    )]
+    pub export: Option<ExportFormat>,
+    #[clap(
+        long,
+        value_parser,
+        value_name = "FORMAT",
+        help = "Render the spells as printable cards in svg or html"
+    )]
+    pub cards: Option<CardFormat>,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        requires = "output",
+        value_name = "PATH",
+        help = "File to write the export or cards to (defaults to stdout)"
+    )]
+    pub out: Option<PathBuf>,
+}
+
+impl SpellArgs {
+    /// Whether the random spell is drawn with a count, deck, weighting or annotation filter
+    fn is_draw(&self) -> bool {
+        self.count.is_some()
+            || self.deck.is_some()
+            || self.weight.is_some()
+            || !self.annotation_filter().is_empty()
+    }
+
+    fn level_range(&self) -> Option<SpellLevelRange> {
+        if self.cantrips {
+            return Some(SpellLevelRange::cantrips());
+        }
+        match self.level.clone()? {
+            LevelArg::Level(level) if self.exact_level => Some(SpellLevelRange::exact(level)),
+            LevelArg::Level(level) => Some(SpellLevelRange::up_to(level)),
+            LevelArg::Range(range) => Some(range),
+        }
+    }
+
+    pub fn filter(&self) -> SpellFilter {
+        SpellFilter {
+            level: self.level_range(),
+            classes: self.classes.to_vec(),
+            subclasses: self.subclasses.to_vec(),
+            name: None,
+        }
+    }
+
+    pub fn annotation_filter(&self) -> SpellAnnotationFilter {
+        SpellAnnotationFilter {
+            tags: self.tags.to_vec(),
+            favourite: self.favourite,
+        }
+    }
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum SpellAction {
+    /// Roll a spell's damage or healing for a slot level (or character level for cantrips)
+    Cast(CastArgs),
+    /// Compare spells side by side, including average damage at each slot level
+    Compare(CompareArgs),
+    /// Attach notes, tags or the favourite flag to a spell
+    Annotate(AnnotateArgs),
+    /// Show the squares and creatures covered by a spell's area of effect on a map
+    Aoe(AoeArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CastArgs {
+    #[clap(help = "Name of the spell to cast")]
+    pub name: String,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        help = "Spell slot level (defaults to the spell's level), cantrips use --caster-level"
+    )]
+    pub slot: Option<SpellLevel>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Character level, selects the damage tier of cantrips (defaults to 1)"
+    )]
+    pub caster_level: Option<u8>,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value_t = 0,
+        allow_hyphen_values = true,
+        help = "Spellcasting ability modifier, replaces MOD in the dice expression"
+    )]
+    pub modifier: i64,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct AnnotateArgs {
+    #[clap(help = "Name of the spell to annotate")]
+    pub name: String,
+    #[clap(
+        long = "note",
+        value_name = "NOTE",
+        help = "Add a note, can be repeated"
+    )]
+    pub notes: Vec<String>,
+    #[clap(long, takes_value(false), help = "Remove the existing notes")]
+    pub clear_notes: bool,
+    #[clap(
+        short,
+        long = "tag",
+        value_delimiter = ',',
+        value_name = "TAGS",
+        help = "Comma-separated list of tags to add"
+    )]
+    pub tags: Vec<String>,
+    #[clap(
+        long = "untag",
+        value_delimiter = ',',
+        value_name = "TAGS",
+        help = "Comma-separated list of tags to remove"
+    )]
+    pub untags: Vec<String>,
+    #[clap(long, takes_value(false), help = "Mark the spell as favourite")]
+    pub favourite: bool,
+    #[clap(
+        long,
+        takes_value(false),
+        conflicts_with = "favourite",
+        help = "Remove the favourite mark"
+    )]
+    pub unfavourite: bool,
+}
+
+impl AnnotateArgs {
+    fn update(&self) -> SpellAnnotationUpdate {
+        let favourite = match (self.favourite, self.unfavourite) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        SpellAnnotationUpdate {
+            notes: self.notes.to_vec(),
+            clear_notes: self.clear_notes,
+            tags: self.tags.to_vec(),
+            untags: self.untags.to_vec(),
+            favourite,
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct AoeArgs {
+    #[clap(
+        required_unless_present = "shape",
+        help = "Name of the spell whose area of effect is placed"
+    )]
+    pub name: Option<String>,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        value_name = "PATH",
+        help = "YAML or JSON map with the origin, target and tokens"
+    )]
+    pub map: PathBuf,
+    #[clap(
+        long,
+        value_parser,
+        conflicts_with = "name",
+        requires = "size",
+        help = "Area shape instead of a spell's: cone, cube, cylinder, line or sphere"
+    )]
+    pub shape: Option<AreaShape>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Area size in feet, overrides the spell's size"
+    )]
+    pub size: Option<u32>,
+    #[clap(
+        long,
+        value_parser,
+        default_value = "half",
+        help = "Squares count as covered when the area covers at least half of them (half) or any part (touch)"
+    )]
+    pub rule: CoverageRule,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CompareArgs {
+    #[clap(
+        required = true,
+        min_values = 2,
+        help = "Names of the spells to compare"
+    )]
+    pub names: Vec<String>,
+    #[clap(
+        long,
+        value_parser,
+        help = "Character level, selects the damage tier of cantrips (defaults to 1)"
+    )]
+    pub caster_level: Option<u8>,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value_t = 0,
+        allow_hyphen_values = true,
+        help = "Spellcasting ability modifier, replaces MOD in the dice expression"
+    )]
+    pub modifier: i64,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -62,77 +506,753 @@ pub struct DiceArgs {
     pub dice_sets: Vec<String>,
 }
 
-pub struct MainCli<S, D>
-where
-    S: SpellInterface,
-    D: DiceInterface,
-{
-    random_spell_usecase: S,
-    dice_roll_usecase: D,
+#[derive(Args, Clone, Debug)]
+pub struct SpellbookArgs {
+    #[clap(subcommand)]
+    pub action: SpellbookAction,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum SpellbookAction {
+    /// Create a spellbook for a character
+    New(NewSpellbookArgs),
+    /// Add known spells to a character's spellbook
+    Add(SpellbookSpellsArgs),
+    /// Remove known spells from a character's spellbook
+    Remove(SpellbookSpellsArgs),
+    /// Prepare known spells (or unprepare them with --undo)
+    Prepare(PrepareSpellsArgs),
+    /// List a character's spellbook, or all characters
+    List(ListSpellbookArgs),
+    /// Render a character's spells as printable cards
+    Cards(SpellbookCardsArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct NewSpellbookArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(short, long, help = "Spellcasting class of the character")]
+    pub class: String,
+    #[clap(
+        short = 'l',
+        long,
+        value_parser,
+        help = "Highest spell level the character can cast"
+    )]
+    pub max_level: SpellLevel,
+    #[clap(
+        short = 'p',
+        long,
+        value_parser,
+        help = "Maximum number of prepared spells"
+    )]
+    pub max_prepared: Option<usize>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SpellbookSpellsArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(required = true, help = "Names of the spells")]
+    pub spells: Vec<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct PrepareSpellsArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(required = true, help = "Names of the spells")]
+    pub spells: Vec<String>,
+    #[clap(short, long, takes_value(false), help = "Unprepare the spells instead")]
+    pub undo: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ListSpellbookArgs {
+    #[clap(help = "Name of the character (lists all characters if omitted)")]
+    pub character: Option<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SpellbookCardsArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = "html",
+        help = "Card format, svg or html"
+    )]
+    pub format: CardFormat,
+    #[clap(short, long, takes_value(false), help = "Only render prepared spells")]
+    pub prepared: bool,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        value_name = "PATH",
+        help = "File to write the cards to (defaults to stdout)"
+    )]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SlotsArgs {
+    #[clap(subcommand)]
+    pub action: SlotsAction,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum SlotsAction {
+    /// Calculate spell slots for class levels without storing them
+    Calc(CalcSlotsArgs),
+    /// Set the class levels of a character, restoring all slots
+    Set(SetSlotsArgs),
+    /// Show remaining spell slots of a character
+    Show(CharacterSlotsArgs),
+    /// Expend a spell slot
+    Use(UseSlotArgs),
+    /// Recover slots with a short rest (pact slots) or long rest (all slots)
+    Rest(RestArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CalcSlotsArgs {
+    #[clap(
+        required = true,
+        value_parser,
+        help = "Class levels, e.g. wizard:5 warlock:2 (third casters as eldritch-knight:7 or arcane-trickster:7)"
+    )]
+    pub class_levels: Vec<ClassLevel>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SetSlotsArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(
+        required = true,
+        value_parser,
+        help = "Class levels, e.g. wizard:5 warlock:2 (third casters as eldritch-knight:7 or arcane-trickster:7)"
+    )]
+    pub class_levels: Vec<ClassLevel>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CharacterSlotsArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct UseSlotArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(value_parser, help = "Level of the slot to expend")]
+    pub level: SpellLevel,
+    #[clap(
+        short,
+        long,
+        takes_value(false),
+        help = "Expend a Pact Magic slot, <LEVEL> must be the level of the pact slots"
+    )]
+    pub pact: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct RestArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(
+        short,
+        long,
+        takes_value(false),
+        help = "Take a long rest instead of a short rest"
+    )]
+    pub long: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CasterArgs {
+    #[clap(subcommand)]
+    pub action: CasterAction,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum CasterAction {
+    /// Set the spellcasting numbers of a character
+    Set(SetCasterArgs),
+    /// Show the spell save DC and spell attack bonus of a character
+    Show(CharacterCasterArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+#[clap(group(ArgGroup::new("proficiency_source").required(true).args(&["proficiency", "level"])))]
+pub struct SetCasterArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        help = "Spellcasting ability, e.g. int for wizards or cha for sorcerers"
+    )]
+    pub ability: Ability,
+    #[clap(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=30),
+        help = "Score of the spellcasting ability"
+    )]
+    pub score: u8,
+    #[clap(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(2..=6),
+        help = "Proficiency bonus"
+    )]
+    pub proficiency: Option<u8>,
+    #[clap(
+        short,
+        long,
+        value_parser = clap::value_parser!(u8).range(1..=20),
+        help = "Character level, sets the proficiency bonus"
+    )]
+    pub level: Option<u8>,
+    #[clap(
+        long,
+        default_value_t = 0,
+        allow_hyphen_values = true,
+        help = "Bonus of magic items to the spell save DC"
+    )]
+    pub dc_bonus: i16,
+    #[clap(
+        long,
+        default_value_t = 0,
+        allow_hyphen_values = true,
+        help = "Bonus of magic items to spell attack rolls"
+    )]
+    pub attack_bonus: i16,
+}
+
+impl SetCasterArgs {
+    pub fn profile(&self) -> CasterProfile {
+        CasterProfile {
+            character: self.character.clone(),
+            ability: self.ability,
+            ability_score: self.score,
+            proficiency_bonus: match self.level {
+                Some(level) => proficiency_bonus_for_level(level),
+                None => self.proficiency.unwrap_or_default(),
+            },
+            save_dc_bonus: self.dc_bonus,
+            attack_bonus: self.attack_bonus,
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CharacterCasterArgs {
+    #[clap(help = "Name of the character")]
+    pub character: String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DataArgs {
+    #[clap(subcommand)]
+    pub action: DataAction,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum DataAction {
+    /// Replace the spells of the SQLite database with the spells of a datasource
+    Import(ImportDataArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct ImportDataArgs {
+    #[clap(
+        long,
+        help = "Datasource to import from like graphql, rest or file, the configured datasource by default"
+    )]
+    pub from: Option<String>,
+}
+
+/// Runs a subcommand, setting up only the usecases it needs
+pub struct MainCli {
+    settings_handler: SettingsHandler,
+    /// Set up on first use and refreshed once the command is done
+    spell_usecase: Option<SpellUsecase>,
     args: Arguments,
 }
 
-impl<S, D> MainCli<S, D>
-where
-    S: SpellInterface,
-    D: DiceInterface,
-{
-    pub fn new(random_spell_usecase: S, dice_roll_usecase: D) -> Self {
+impl MainCli {
+    pub fn new(args: Arguments, settings_handler: SettingsHandler) -> Self {
         MainCli {
-            random_spell_usecase,
-            dice_roll_usecase,
-            args: Arguments::parse(),
+            settings_handler,
+            spell_usecase: None,
+            args,
         }
     }
+
+    fn spell_usecase(&mut self) -> Result<&mut SpellUsecase, CliError> {
+        if self.spell_usecase.is_none() {
+            let spell_usecase = self
+                .settings_handler
+                .setup_spell_usecase()
+                .map_err(CliError::Configuration)?;
+            self.spell_usecase = Some(spell_usecase);
+        }
+        Ok(self.spell_usecase.as_mut().unwrap())
+    }
+
     pub async fn run(&mut self) -> Result<(), CliError> {
-        match self.args.cmd.clone() {
+        let result = match self.args.cmd.clone() {
             SubCommand::Spell(args) => self.handle_spell_cmd(&args).await,
             SubCommand::Dice(args) => self.handle_dice_cmd(&args),
+            SubCommand::Spellbook(args) => self.handle_spellbook_cmd(&args).await,
+            SubCommand::Slots(args) => self.handle_slots_cmd(&args),
+            SubCommand::Caster(args) => self.handle_caster_cmd(&args),
+            SubCommand::Data(args) => self.handle_data_cmd(&args).await,
+        };
+        self.revalidate().await;
+        result
+    }
+
+    /// Refreshes persisted spells that were served stale, once the command has printed them
+    async fn revalidate(&mut self) {
+        if let Some(spell_usecase) = &mut self.spell_usecase {
+            if let Err(err) = spell_usecase.revalidate().await {
+                log::warn!("Could not refresh the persisted spells: {:?}", err);
+            }
         }
     }
 
     pub async fn handle_spell_cmd(&mut self, args: &SpellArgs) -> Result<(), CliError> {
-        match args.name.clone() {
-            Some(name) => {
+        match &args.action {
+            Some(SpellAction::Cast(cast_args)) => return self.handle_cast_cmd(cast_args).await,
+            Some(SpellAction::Compare(compare_args)) => {
+                return self.handle_compare_cmd(compare_args).await
+            }
+            Some(SpellAction::Annotate(annotate_args)) => {
+                return self.handle_annotate_cmd(annotate_args).await
+            }
+            Some(SpellAction::Aoe(aoe_args)) => return self.handle_aoe_cmd(aoe_args).await,
+            None => {}
+        }
+        let spell_annotation_usecase = self
+            .settings_handler
+            .setup_spell_annotation_usecase()
+            .map_err(CliError::LocalFile)?;
+        let caster = match &args.caster {
+            Some(character) => Some(
+                self.settings_handler
+                    .setup_caster_usecase()
+                    .map_err(CliError::LocalFile)?
+                    .get_caster(character)
+                    .map_err(CliError::Caster)?,
+            ),
+            None => None,
+        };
+        let spells = match args.name.clone() {
+            Some(name) => vec![self
+                .spell_usecase()?
+                .get_spell_by_name(name)
+                .await
+                .map_err(CliError::Spell)?],
+            None => {
+                if args.random && args.is_draw() {
+                    self.handle_draw(args).await?
+                } else if args.random {
+                    vec![self
+                        .spell_usecase()?
+                        .get_random_spell(args.filter())
+                        .await
+                        .map_err(CliError::Spell)?]
+                } else {
+                    let spells = self
+                        .spell_usecase()?
+                        .get_all_spells_with_filters(args.filter())
+                        .await
+                        .map_err(CliError::Spell)?;
+                    spell_annotation_usecase
+                        .filter_spells(spells, &args.annotation_filter())
+                        .map_err(CliError::Annotation)?
+                }
+            }
+        };
+        match (args.export, args.cards) {
+            (Some(format), _) => self.handle_export(&spells, args, format)?,
+            (None, Some(format)) => self.handle_cards(&spells, format, &args.out)?,
+            (None, None) => {
+                let annotations = spell_annotation_usecase
+                    .get_annotations()
+                    .map_err(CliError::Annotation)?;
+                let mut reference_usecase = match args.expand {
+                    true => Some(
+                        self.settings_handler
+                            .setup_reference_usecase()
+                            .map_err(CliError::Configuration)?,
+                    ),
+                    false => None,
+                };
+                let known_spells = match args.expand {
+                    true => self
+                        .spell_usecase()?
+                        .get_all_spells_with_filters(SpellFilter::default())
+                        .await
+                        .map_err(CliError::Spell)?,
+                    false => vec![],
+                };
+                for spell in spells {
+                    let always_prepared =
+                        spell.matching_subclasses(&args.classes, &args.subclasses);
+                    let annotation = annotations.iter().find(|a| a.index == spell.index);
+                    println!(
+                        "{}",
+                        CliDisplaySpell::new(&spell)
+                            .always_prepared(always_prepared)
+                            .annotation(annotation)
+                            .caster(caster.as_ref())
+                    );
+                    let references = match reference_usecase.as_mut() {
+                        Some(usecase) => usecase.find_references(&spell, &known_spells).await,
+                        None => Ok(vec![]),
+                    };
+                    match references {
+                        Ok(references) => {
+                            for reference in &references {
+                                println!("{}", CliDisplayReference::new(reference));
+                            }
+                        }
+                        Err(ReferenceError::DataSourceError(
+                            ConditionsDataSourceError::NotCached,
+                        )) => {
+                            eprintln!(
+                                "Not expanding spells offline, run --expand once online to persist the conditions"
+                            );
+                            reference_usecase = None;
+                        }
+                        Err(err) => return Err(CliError::Reference(err)),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_draw(&mut self, args: &SpellArgs) -> Result<Vec<Spell>, CliError> {
+        let spell_annotation_usecase = self
+            .settings_handler
+            .setup_spell_annotation_usecase()
+            .map_err(CliError::LocalFile)?;
+        let mut spell_draw_usecase = self
+            .settings_handler
+            .setup_spell_draw_usecase()
+            .map_err(CliError::LocalFile)?;
+        let spells = self
+            .spell_usecase()?
+            .get_all_spells_with_filters(args.filter())
+            .await
+            .map_err(CliError::Spell)?;
+        let spells = spell_annotation_usecase
+            .filter_spells(spells, &args.annotation_filter())
+            .map_err(CliError::Annotation)?;
+        if let (Some(deck), true) = (&args.deck, args.reset_deck) {
+            match spell_draw_usecase.reset_deck(deck) {
+                Ok(_) | Err(SpellDrawError::UnknownDeck(_)) => {}
+                Err(err) => return Err(CliError::Draw(err)),
+            }
+        }
+        spell_draw_usecase
+            .draw(
+                &spells,
+                args.count.unwrap_or(1) as usize,
+                args.deck.as_deref(),
+                &args.weight.clone().unwrap_or_default(),
+            )
+            .map_err(CliError::Draw)
+    }
+
+    fn handle_cards(
+        &self,
+        spells: &[Spell],
+        format: CardFormat,
+        out: &Option<PathBuf>,
+    ) -> Result<(), CliError> {
+        let spell_card_usecase = self.settings_handler.setup_spell_card_usecase();
+        match out {
+            Some(path) => {
+                spell_card_usecase
+                    .render_to_file(spells, format, path)
+                    .map_err(CliError::Io)?;
+                println!(
+                    "Rendered cards for {} spells to {}",
+                    spells.len(),
+                    path.display()
+                );
+            }
+            None => print!("{}", spell_card_usecase.render(spells, format)),
+        }
+        Ok(())
+    }
+
+    fn handle_export(
+        &self,
+        spells: &[Spell],
+        args: &SpellArgs,
+        format: ExportFormat,
+    ) -> Result<(), CliError> {
+        let export_usecase = self.settings_handler.setup_export_usecase();
+        match &args.out {
+            Some(path) => {
+                export_usecase
+                    .export_to_file(spells, &args.filter(), format, path)
+                    .map_err(CliError::Export)?;
+                println!("Exported {} spells to {}", spells.len(), path.display());
+            }
+            None => {
+                let content = export_usecase
+                    .export(spells, &args.filter(), format)
+                    .map_err(CliError::Export)?;
+                print!("{}", content);
+            }
+        }
+        Ok(())
+    }
+    pub async fn handle_cast_cmd(&mut self, args: &CastArgs) -> Result<(), CliError> {
+        let cast_usecase = self.settings_handler.setup_cast_usecase();
+        let spell = self
+            .spell_usecase()?
+            .get_spell_by_name(args.name.clone())
+            .await
+            .map_err(CliError::Spell)?;
+        let spell_cast = cast_usecase
+            .cast(&spell, args.slot, args.caster_level, args.modifier)
+            .map_err(CliError::Cast)?;
+        println!("{}", CliDisplaySpellCast::new(&spell_cast));
+        Ok(())
+    }
+
+    pub async fn handle_compare_cmd(&mut self, args: &CompareArgs) -> Result<(), CliError> {
+        let cast_usecase = self.settings_handler.setup_cast_usecase();
+        let mut spells = Vec::new();
+        for name in &args.names {
+            let spell = self
+                .spell_usecase()?
+                .get_spell_by_name(name.clone())
+                .await
+                .map_err(CliError::Spell)?;
+            spells.push(spell);
+        }
+        let comparison = cast_usecase
+            .compare(&spells, args.caster_level, args.modifier)
+            .map_err(CliError::Cast)?;
+        println!("{}", CliDisplaySpellComparison::new(&comparison));
+        Ok(())
+    }
+
+    pub async fn handle_annotate_cmd(&mut self, args: &AnnotateArgs) -> Result<(), CliError> {
+        let mut spell_annotation_usecase = self
+            .settings_handler
+            .setup_spell_annotation_usecase()
+            .map_err(CliError::LocalFile)?;
+        let spell = self
+            .spell_usecase()?
+            .get_spell_by_name(args.name.clone())
+            .await
+            .map_err(CliError::Spell)?;
+        let annotation = spell_annotation_usecase
+            .annotate(&spell, &args.update())
+            .map_err(CliError::Annotation)?;
+        println!(
+            "{}",
+            CliDisplaySpell::new(&spell).annotation(Some(&annotation))
+        );
+        Ok(())
+    }
+
+    pub async fn handle_aoe_cmd(&mut self, args: &AoeArgs) -> Result<(), CliError> {
+        let area_of_effect_usecase = self.settings_handler.setup_area_of_effect_usecase();
+        let area = match (args.shape, &args.name) {
+            (Some(shape), _) => AreaOfEffect {
+                shape,
+                size: args.size.unwrap_or_default(),
+            },
+            (None, Some(name)) => {
                 let spell = self
-                    .random_spell_usecase
-                    .get_spell_by_name(name)
+                    .spell_usecase()?
+                    .get_spell_by_name(name.clone())
                     .await
                     .map_err(CliError::Spell)?;
-                println!("{}", CliDisplaySpell::new(&spell));
+                match spell.area_of_effect {
+                    Some(area) => AreaOfEffect {
+                        size: args.size.unwrap_or(area.size),
+                        ..area
+                    },
+                    None => {
+                        return Err(CliError::AreaOfEffect(AreaOfEffectError::NoAreaOfEffect(
+                            spell.name,
+                        )))
+                    }
+                }
             }
-            None => {
-                if args.random {
+            (None, None) => unreachable!("clap requires a spell name or a shape"),
+        };
+        let map = area_of_effect_usecase
+            .load_map(&args.map)
+            .map_err(CliError::AreaOfEffect)?;
+        let coverage = area_of_effect_usecase
+            .cover(&area, &map, args.rule)
+            .map_err(CliError::AreaOfEffect)?;
+        println!("{}", CliDisplayAoeCoverage::new(&coverage));
+        Ok(())
+    }
+
+    pub async fn handle_spellbook_cmd(&mut self, args: &SpellbookArgs) -> Result<(), CliError> {
+        let mut spellbook_usecase = self
+            .settings_handler
+            .setup_spellbook_usecase()
+            .map_err(CliError::LocalFile)?;
+        let spellbook = match &args.action {
+            SpellbookAction::New(args) => spellbook_usecase
+                .create_spellbook(
+                    args.character.clone(),
+                    args.class.clone(),
+                    args.max_level,
+                    args.max_prepared,
+                )
+                .map_err(CliError::Spellbook)?,
+            SpellbookAction::Add(args) => {
+                let mut spells = Vec::new();
+                for name in &args.spells {
                     let spell = self
-                        .random_spell_usecase
-                        .get_random_spell(args.level, args.classes.to_vec(), args.exact_level)
+                        .spell_usecase()?
+                        .get_spell_by_name(name.clone())
                         .await
                         .map_err(CliError::Spell)?;
-                    println!("{}", CliDisplaySpell::new(&spell));
-                } else {
-                    let spells = self
-                        .random_spell_usecase
-                        .get_all_spells_with_filters(
-                            args.level,
-                            args.classes.to_vec(),
-                            args.exact_level,
-                        )
+                    spells.push(spell);
+                }
+                spellbook_usecase
+                    .add_spells(&args.character, &spells)
+                    .map_err(CliError::Spellbook)?
+            }
+            SpellbookAction::Remove(args) => spellbook_usecase
+                .remove_spells(&args.character, &args.spells)
+                .map_err(CliError::Spellbook)?,
+            SpellbookAction::Prepare(args) => spellbook_usecase
+                .prepare_spells(&args.character, &args.spells, !args.undo)
+                .map_err(CliError::Spellbook)?,
+            SpellbookAction::List(ListSpellbookArgs {
+                character: Some(character),
+            }) => spellbook_usecase
+                .get_spellbook(character)
+                .map_err(CliError::Spellbook)?,
+            SpellbookAction::List(ListSpellbookArgs { character: None }) => {
+                let spellbooks = spellbook_usecase
+                    .get_all_spellbooks()
+                    .map_err(CliError::Spellbook)?;
+                for spellbook in spellbooks {
+                    println!("{}", CliDisplaySpellbook::new(&spellbook));
+                }
+                return Ok(());
+            }
+            SpellbookAction::Cards(args) => {
+                let spellbook = spellbook_usecase
+                    .get_spellbook(&args.character)
+                    .map_err(CliError::Spellbook)?;
+                let mut spells = Vec::new();
+                let entries = spellbook
+                    .known
+                    .iter()
+                    .filter(|entry| !args.prepared || entry.prepared || entry.level.is_cantrip());
+                for entry in entries {
+                    let spell = self
+                        .spell_usecase()?
+                        .get_spell_by_name(entry.name.clone())
                         .await
                         .map_err(CliError::Spell)?;
-                    for spell in spells {
-                        println!("{}", CliDisplaySpell::new(&spell));
-                    }
+                    spells.push(spell);
                 }
+                return self.handle_cards(&spells, args.format, &args.out);
+            }
+        };
+        println!("{}", CliDisplaySpellbook::new(&spellbook));
+        Ok(())
+    }
+
+    pub fn handle_slots_cmd(&mut self, args: &SlotsArgs) -> Result<(), CliError> {
+        let mut spell_slots_usecase = self
+            .settings_handler
+            .setup_spell_slots_usecase()
+            .map_err(CliError::LocalFile)?;
+        let caster_slots = match &args.action {
+            SlotsAction::Calc(args) => {
+                let slots = spell_slots_usecase
+                    .calculate_slots(&args.class_levels)
+                    .map_err(CliError::SpellSlots)?;
+                println!("{}", CliDisplaySpellSlots::new(&slots, None));
+                return Ok(());
+            }
+            SlotsAction::Set(args) => spell_slots_usecase
+                .set_class_levels(args.character.clone(), args.class_levels.to_vec()),
+            SlotsAction::Show(args) => spell_slots_usecase.get_caster_slots(&args.character),
+            SlotsAction::Use(args) => {
+                spell_slots_usecase.expend_slot(&args.character, args.level, args.pact)
+            }
+            SlotsAction::Rest(args) => {
+                let rest = match args.long {
+                    true => Rest::Long,
+                    false => Rest::Short,
+                };
+                spell_slots_usecase.rest(&args.character, rest)
             }
         }
+        .map_err(CliError::SpellSlots)?;
+        let slots = spell_slots_usecase
+            .calculate_slots(&caster_slots.class_levels)
+            .map_err(CliError::SpellSlots)?;
+        println!("{}", CliDisplaySpellSlots::new(&slots, Some(&caster_slots)));
+        Ok(())
+    }
+
+    pub fn handle_caster_cmd(&mut self, args: &CasterArgs) -> Result<(), CliError> {
+        let mut caster_usecase = self
+            .settings_handler
+            .setup_caster_usecase()
+            .map_err(CliError::LocalFile)?;
+        let profile = match &args.action {
+            CasterAction::Set(args) => caster_usecase.set_caster(args.profile()),
+            CasterAction::Show(args) => caster_usecase.get_caster(&args.character),
+        }
+        .map_err(CliError::Caster)?;
+        println!("{}", CliDisplayCaster::new(&profile));
+        Ok(())
+    }
 
+    pub async fn handle_data_cmd(&mut self, args: &DataArgs) -> Result<(), CliError> {
+        let spell_import_usecase = self
+            .settings_handler
+            .setup_spell_import_usecase()
+            .map_err(CliError::Configuration)?;
+        match &args.action {
+            DataAction::Import(_) => {
+                let count = spell_import_usecase
+                    .import_spells()
+                    .await
+                    .map_err(CliError::Import)?;
+                println!("Imported {} spells", count);
+            }
+        }
         Ok(())
     }
+
     pub fn handle_dice_cmd(&mut self, args: &DiceArgs) -> Result<(), CliError> {
-        let dice_set = self
-            .dice_roll_usecase
-            .roll(args.dice_sets.to_vec())
-            .unwrap();
+        let dice_roll_usecase = self.settings_handler.setup_dice_usecase();
+        let dice_set = dice_roll_usecase.roll(args.dice_sets.to_vec()).unwrap();
         println!("{}", CliDisplayDiceSet::new(&dice_set));
         Ok(())
     }
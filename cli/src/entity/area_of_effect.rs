@@ -0,0 +1,88 @@
+use lib::core::entity::area_of_effect::AoeCoverage;
+use std::fmt::{Display, Error, Formatter};
+
+const TOKEN_LABELS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Covered tokens and a map of the covered squares, `*` marks covered squares and letters mark
+/// tokens as listed in the legend
+pub struct CliDisplayAoeCoverage<'a>(&'a AoeCoverage);
+
+impl CliDisplayAoeCoverage<'_> {
+    pub fn new(coverage: &AoeCoverage) -> CliDisplayAoeCoverage<'_> {
+        CliDisplayAoeCoverage(coverage)
+    }
+
+    fn label(i: usize) -> char {
+        TOKEN_LABELS.chars().nth(i).unwrap_or('?')
+    }
+
+    /// Inclusive bounds of the covered squares and tokens with a margin of one square
+    fn bounds(&self) -> ((i32, i32), (i32, i32)) {
+        let token_squares = self.0.tokens.iter().flat_map(|(token, _)| {
+            let size = token.size.max(1);
+            [
+                (token.at[0], token.at[1]),
+                (token.at[0] + size - 1, token.at[1] + size - 1),
+            ]
+        });
+        let squares = self
+            .0
+            .squares
+            .iter()
+            .copied()
+            .chain(token_squares)
+            .collect::<Vec<(i32, i32)>>();
+        let min_x = squares.iter().map(|s| s.0).min().unwrap_or(0) - 1;
+        let max_x = squares.iter().map(|s| s.0).max().unwrap_or(0) + 1;
+        let min_y = squares.iter().map(|s| s.1).min().unwrap_or(0) - 1;
+        let max_y = squares.iter().map(|s| s.1).max().unwrap_or(0) + 1;
+        ((min_x, min_y), (max_x, max_y))
+    }
+}
+
+impl<'a> Display for CliDisplayAoeCoverage<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "Area:")?;
+        writeln!(f, "\t{} ({} rule)", self.0.area, self.0.rule)?;
+        writeln!(f, "Covered squares:")?;
+        writeln!(f, "\t{}", self.0.squares.len())?;
+        let affected = self
+            .0
+            .affected()
+            .iter()
+            .map(|token| token.name.clone())
+            .collect::<Vec<String>>();
+        writeln!(f, "Affected:")?;
+        match affected.is_empty() {
+            true => writeln!(f, "\tnone")?,
+            false => writeln!(f, "\t{}", affected.join(", "))?,
+        }
+
+        writeln!(f, "Map:")?;
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+        for y in min_y..=max_y {
+            let row = (min_x..=max_x)
+                .map(|x| {
+                    let token = self
+                        .0
+                        .tokens
+                        .iter()
+                        .position(|(token, _)| token.occupies((x, y)));
+                    match (token, self.0.is_covered((x, y))) {
+                        (Some(i), _) => Self::label(i),
+                        (None, true) => '*',
+                        (None, false) => '.',
+                    }
+                })
+                .map(String::from)
+                .collect::<Vec<String>>();
+            writeln!(f, "\t{}", row.join(" "))?;
+        }
+        for (i, (token, hit)) in self.0.tokens.iter().enumerate() {
+            let status = if *hit { "hit" } else { "missed" };
+            writeln!(f, "\t{} {} ({})", Self::label(i), token.name, status)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use lib::core::entity::caster::CasterProfile;
+use std::fmt::{Display, Error, Formatter};
+
+pub struct CliDisplayCaster<'a>(&'a CasterProfile);
+
+impl<'a> CliDisplayCaster<'a> {
+    pub fn new(profile: &'a CasterProfile) -> Self {
+        CliDisplayCaster(profile)
+    }
+}
+
+/// Item bonus suffix, e.g. ` (+1 from items)`, empty without a bonus
+fn item_bonus(bonus: i16) -> String {
+    match bonus {
+        0 => String::new(),
+        bonus => format!(" ({:+} from items)", bonus),
+    }
+}
+
+impl<'a> Display for CliDisplayCaster<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let profile = self.0;
+        writeln!(f, "-----{}----", "-".repeat(profile.character.len()))?;
+        writeln!(f, "-----{}----", profile.character)?;
+        writeln!(f, "Spellcasting ability:")?;
+        writeln!(
+            f,
+            "\t{} {} ({:+})",
+            profile.ability,
+            profile.ability_score,
+            profile.modifier()
+        )?;
+        writeln!(f, "Proficiency bonus:")?;
+        writeln!(f, "\t{:+}", profile.proficiency_bonus)?;
+        writeln!(f, "Spell save DC:")?;
+        writeln!(
+            f,
+            "\t{}{}",
+            profile.spell_save_dc(),
+            item_bonus(profile.save_dc_bonus)
+        )?;
+        writeln!(f, "Spell attack bonus:")?;
+        writeln!(
+            f,
+            "\t{:+}{}",
+            profile.spell_attack_bonus(),
+            item_bonus(profile.attack_bonus)
+        )?;
+        writeln!(f, "-----{}----", "-".repeat(profile.character.len()))?;
+        Ok(())
+    }
+}
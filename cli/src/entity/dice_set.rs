@@ -4,11 +4,17 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 
 pub struct CliDisplayDice<'a>(&'a Dice);
 
+impl CliDisplayDice<'_> {
+    pub fn new(dice: &Dice) -> CliDisplayDice<'_> {
+        CliDisplayDice(dice)
+    }
+}
+
 impl<'a> Display for CliDisplayDice<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "{}d{}", self.0.dice_count, self.0.face)?;
-        if self.0.modifier.is_some() {
-            write!(f, "+{}", self.0.modifier.unwrap())?;
+        if let Some(modifier) = self.0.modifier {
+            write!(f, "+{}", modifier)?;
         }
         Ok(())
     }
@@ -17,7 +23,7 @@ impl<'a> Display for CliDisplayDice<'a> {
 pub struct CliDisplayDiceSet<'a>(&'a DiceSet);
 
 impl CliDisplayDiceSet<'_> {
-    pub fn new(dice_set: &DiceSet) -> CliDisplayDiceSet {
+    pub fn new(dice_set: &DiceSet) -> CliDisplayDiceSet<'_> {
         CliDisplayDiceSet(dice_set)
     }
 }
@@ -0,0 +1,20 @@
+use crate::entity::rich_text::CliDisplayRichText;
+use lib::core::entity::reference::Reference;
+use std::fmt::{Display, Error, Formatter};
+
+pub struct CliDisplayReference<'a>(&'a Reference);
+
+impl CliDisplayReference<'_> {
+    pub fn new(reference: &Reference) -> CliDisplayReference<'_> {
+        CliDisplayReference(reference)
+    }
+}
+
+impl<'a> Display for CliDisplayReference<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "{} ({}):", self.0.name, self.0.kind)?;
+        write!(f, "{}", CliDisplayRichText::new(&self.0.desc))?;
+
+        Ok(())
+    }
+}
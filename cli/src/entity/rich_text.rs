@@ -0,0 +1,265 @@
+use lib::core::entity::rich_text::{parse_description, Block, Emphasis, Span};
+use std::env;
+use std::fmt::{Display, Error, Formatter};
+use std::io::IsTerminal;
+
+const DEFAULT_WIDTH: usize = 80;
+/// Width taken by the leading tab of description lines
+const INDENT_WIDTH: usize = 8;
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// Word of a wrapped line, `spaced` when whitespace separated it from the word before
+struct Word {
+    text: String,
+    emphasis: Emphasis,
+    spaced: bool,
+}
+
+/// Markdown spell description with tables, emphasis and word-wrapping for the terminal
+pub struct CliDisplayRichText {
+    blocks: Vec<Block>,
+    width: usize,
+    styled: bool,
+}
+
+impl CliDisplayRichText {
+    pub fn new(desc: &[String]) -> Self {
+        let stdout = std::io::stdout();
+        CliDisplayRichText {
+            blocks: parse_description(desc),
+            width: Self::terminal_width().saturating_sub(INDENT_WIDTH).max(20),
+            styled: stdout.is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+
+    fn terminal_width() -> usize {
+        match terminal_size::terminal_size() {
+            Some((terminal_size::Width(width), _)) => width as usize,
+            None => env::var("COLUMNS")
+                .ok()
+                .and_then(|columns| columns.parse::<usize>().ok())
+                .unwrap_or(DEFAULT_WIDTH),
+        }
+    }
+
+    fn style(&self, text: &str, emphasis: Emphasis) -> String {
+        if !self.styled || (!emphasis.bold && !emphasis.italic) {
+            return text.to_string();
+        }
+        let mut codes = Vec::new();
+        if emphasis.bold {
+            codes.push("1");
+        }
+        if emphasis.italic {
+            codes.push("3");
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+
+    /// Greedily wraps the words of `spans` into lines of at most `width` visible characters
+    ///
+    /// Words not preceded by whitespace, like punctuation after emphasis, stay on the line of
+    /// the word before them.
+    fn wrap_spans(spans: &[Span], width: usize) -> Vec<Vec<Word>> {
+        let mut lines = vec![];
+        let mut line: Vec<Word> = vec![];
+        let mut line_width = 0;
+        let mut after_space = false;
+        for span in spans {
+            for (i, word) in span.text.split_whitespace().enumerate() {
+                let spaced = i > 0 || after_space || span.text.starts_with(char::is_whitespace);
+                let word_width = word.chars().count();
+                if spaced && line_width > 0 {
+                    match line_width + 1 + word_width > width {
+                        true => {
+                            lines.push(std::mem::take(&mut line));
+                            line_width = 0;
+                        }
+                        false => line_width += 1,
+                    }
+                }
+                line_width += word_width;
+                line.push(Word {
+                    text: word.to_string(),
+                    emphasis: span.emphasis,
+                    spaced,
+                });
+            }
+            after_space = match span.text.trim().is_empty() {
+                true => after_space || !span.text.is_empty(),
+                false => span.text.ends_with(char::is_whitespace),
+            };
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Wraps plain text, splitting words longer than `width`
+    fn wrap_text(text: &str, width: usize) -> Vec<String> {
+        let words = text
+            .split_whitespace()
+            .flat_map(|word| {
+                let chars = word.chars().collect::<Vec<char>>();
+                chars
+                    .chunks(width.max(1))
+                    .map(|chunk| chunk.iter().collect::<String>())
+                    .collect::<Vec<String>>()
+            })
+            .map(|word| Span {
+                text: format!(" {}", word),
+                emphasis: Emphasis::default(),
+            })
+            .collect::<Vec<Span>>();
+        let lines = Self::wrap_spans(&words, width)
+            .into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .map(|word| word.text)
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>();
+        match lines.is_empty() {
+            true => vec![String::new()],
+            false => lines,
+        }
+    }
+
+    /// Joins the words of a line, styling runs of the same emphasis and keeping the spaces
+    /// between runs unstyled
+    fn render_line(&self, line: &[Word]) -> String {
+        let mut rendered = String::new();
+        let mut run = String::new();
+        let mut run_emphasis = None;
+        for (i, word) in line.iter().enumerate() {
+            let separator = if word.spaced && i > 0 { " " } else { "" };
+            match run_emphasis {
+                Some(emphasis) if emphasis == word.emphasis => run.push_str(separator),
+                Some(emphasis) => {
+                    rendered.push_str(&self.style(&std::mem::take(&mut run), emphasis));
+                    rendered.push_str(separator);
+                }
+                None => {}
+            }
+            run.push_str(&word.text);
+            run_emphasis = Some(word.emphasis);
+        }
+        if let Some(emphasis) = run_emphasis {
+            rendered.push_str(&self.style(&run, emphasis));
+        }
+        rendered
+    }
+
+    fn column_widths(&self, header: &Option<Vec<String>>, rows: &[Vec<String>]) -> Vec<usize> {
+        let columns = header
+            .iter()
+            .chain(rows)
+            .map(|row| row.len())
+            .max()
+            .unwrap_or(0);
+        let mut widths = (0..columns)
+            .map(|i| {
+                header
+                    .iter()
+                    .chain(rows)
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            })
+            .collect::<Vec<usize>>();
+        // Borders and padding take 3 characters per column plus the closing border
+        let available = self.width.saturating_sub(3 * columns + 1);
+        while widths.iter().sum::<usize>() > available {
+            match widths.iter_mut().filter(|w| **w > MIN_COLUMN_WIDTH).max() {
+                Some(widest) => *widest -= 1,
+                None => break,
+            }
+        }
+        widths
+    }
+
+    fn write_row(
+        &self,
+        f: &mut Formatter<'_>,
+        widths: &[usize],
+        row: &[String],
+        bold: bool,
+    ) -> Result<(), Error> {
+        let cells = widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| Self::wrap_text(row.get(i).map_or("", |c| c), *width))
+            .collect::<Vec<Vec<String>>>();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+        for line in 0..height {
+            let parts = cells
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| {
+                    let text = cell.get(line).map_or("", |t| t);
+                    let padding = " ".repeat(width - text.chars().count());
+                    let emphasis = Emphasis {
+                        bold,
+                        italic: false,
+                    };
+                    format!(" {}{} ", self.style(text, emphasis), padding)
+                })
+                .collect::<Vec<String>>();
+            writeln!(f, "\t│{}│", parts.join("│"))?;
+        }
+        Ok(())
+    }
+
+    fn write_table(
+        &self,
+        f: &mut Formatter<'_>,
+        header: &Option<Vec<String>>,
+        rows: &[Vec<String>],
+    ) -> Result<(), Error> {
+        let widths = self.column_widths(header, rows);
+        let border = |left: &str, middle: &str, right: &str| {
+            let lines = widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<String>>();
+            format!("{}{}{}", left, lines.join(middle), right)
+        };
+        writeln!(f, "\t{}", border("┌", "┬", "┐"))?;
+        if let Some(header) = header {
+            self.write_row(f, &widths, header, true)?;
+            writeln!(f, "\t{}", border("├", "┼", "┤"))?;
+        }
+        for row in rows {
+            self.write_row(f, &widths, row, false)?;
+        }
+        writeln!(f, "\t{}", border("└", "┴", "┘"))
+    }
+}
+
+impl Display for CliDisplayRichText {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for block in &self.blocks {
+            match block {
+                Block::Paragraph(spans) => {
+                    for line in Self::wrap_spans(spans, self.width) {
+                        writeln!(f, "\t{}", self.render_line(&line))?;
+                    }
+                }
+                Block::ListItem(spans) => {
+                    let lines = Self::wrap_spans(spans, self.width.saturating_sub(2));
+                    for (i, line) in lines.iter().enumerate() {
+                        let bullet = if i == 0 { "• " } else { "  " };
+                        writeln!(f, "\t{}{}", bullet, self.render_line(line))?;
+                    }
+                }
+                Block::Table { header, rows } => self.write_table(f, header, rows)?,
+            }
+        }
+
+        Ok(())
+    }
+}
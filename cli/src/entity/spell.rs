@@ -1,30 +1,128 @@
-use lib::core::entity::spell::Spell;
+use crate::entity::rich_text::CliDisplayRichText;
+use lib::core::entity::caster::CasterProfile;
+use lib::core::entity::spell::{Spell, SpellSave};
+use lib::core::entity::spell_annotation::SpellAnnotation;
 use std::fmt::{Display, Error, Formatter};
 
-pub struct CliDisplaySpell<'a>(&'a Spell);
+/// A spell with the subclasses that always have it prepared, the user's annotation and the
+/// save DC and to-hit of a caster
+pub struct CliDisplaySpell<'a> {
+    spell: &'a Spell,
+    always_prepared: Vec<String>,
+    annotation: Option<&'a SpellAnnotation>,
+    caster: Option<&'a CasterProfile>,
+}
+
+impl<'a> CliDisplaySpell<'a> {
+    pub fn new(spell: &'a Spell) -> CliDisplaySpell<'a> {
+        CliDisplaySpell {
+            spell,
+            always_prepared: vec![],
+            annotation: None,
+            caster: None,
+        }
+    }
+
+    /// Marks the spell as always prepared for the given matching subclasses
+    pub fn always_prepared(mut self, subclasses: Vec<String>) -> Self {
+        self.always_prepared = subclasses;
+        self
+    }
+
+    pub fn annotation(mut self, annotation: Option<&'a SpellAnnotation>) -> Self {
+        self.annotation = annotation;
+        self
+    }
+
+    /// Shows the caster's save DC and spell attack bonus for the spell
+    pub fn caster(mut self, caster: Option<&'a CasterProfile>) -> Self {
+        self.caster = caster;
+        self
+    }
+}
 
-impl CliDisplaySpell<'_> {
-    pub fn new(spell: &Spell) -> CliDisplaySpell {
-        CliDisplaySpell(spell)
+fn save_on_success(save: &SpellSave) -> String {
+    match save.success.as_deref() {
+        Some("half") => " (half on a success)".to_string(),
+        Some("none") => " (no effect on a success)".to_string(),
+        _ => String::new(),
     }
 }
 
 impl<'a> Display for CliDisplaySpell<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        writeln!(f, "-----{}----", "-".repeat(self.0.name.len()))?;
-        writeln!(f, "-----{}----", self.0.name)?;
+        writeln!(f, "-----{}----", "-".repeat(self.spell.name.len()))?;
+        writeln!(f, "-----{}----", self.spell.name)?;
         writeln!(f, "Level:")?;
-        writeln!(f, "\t{}", self.0.level)?;
-        if !self.0.classes.is_empty() {
+        match self.spell.level.is_cantrip() {
+            true => writeln!(f, "\t{} (cantrip)", self.spell.level)?,
+            false => writeln!(f, "\t{}", self.spell.level)?,
+        }
+        if !self.spell.classes.is_empty() {
             writeln!(f, "Classes:")?;
-            writeln!(f, "\t{}", self.0.classes.join(", "))?;
+            writeln!(f, "\t{}", self.spell.classes.join(", "))?;
+        }
+        if !self.always_prepared.is_empty() {
+            writeln!(f, "Always prepared:")?;
+            writeln!(f, "\t{}", self.always_prepared.join(", "))?;
+        }
+        if let Some(school) = &self.spell.school {
+            writeln!(f, "School:")?;
+            writeln!(f, "\t{}", school)?;
+        }
+        if let Some(range) = &self.spell.range {
+            writeln!(f, "Range:")?;
+            writeln!(f, "\t{}", range)?;
+        }
+        if !self.spell.components.is_empty() {
+            writeln!(f, "Components:")?;
+            writeln!(f, "\t{}", self.spell.components.join(", "))?;
+        }
+        if let Some(duration) = &self.spell.duration {
+            writeln!(f, "Duration:")?;
+            writeln!(f, "\t{}", duration)?;
+        }
+        if let Some(area) = &self.spell.area_of_effect {
+            writeln!(f, "Area:")?;
+            writeln!(f, "\t{}", area)?;
+        }
+        let stats = self.caster.map(|caster| caster.stats_for(self.spell));
+        if let Some(save) = &self.spell.save {
+            writeln!(f, "Saving throw:")?;
+            match stats.and_then(|stats| stats.save_dc) {
+                Some(dc) => writeln!(f, "\tDC {} {}{}", dc, save.ability, save_on_success(save))?,
+                None => writeln!(f, "\t{}{}", save.ability, save_on_success(save))?,
+            }
+        }
+        if let Some(attack_type) = &self.spell.attack_type {
+            writeln!(f, "Spell attack:")?;
+            match stats.and_then(|stats| stats.attack_bonus) {
+                Some(bonus) => writeln!(f, "\t{:+} to hit ({})", bonus, attack_type)?,
+                None => writeln!(f, "\t{}", attack_type)?,
+            }
+        }
+        writeln!(f, "Source:")?;
+        writeln!(f, "\t{}", self.spell.source)?;
+        if let Some(annotation) = self.annotation {
+            if annotation.favourite {
+                writeln!(f, "Favourite:")?;
+                writeln!(f, "\tyes")?;
+            }
+            if !annotation.tags.is_empty() {
+                writeln!(f, "Tags:")?;
+                writeln!(f, "\t{}", annotation.tags.join(", "))?;
+            }
+            if !annotation.notes.is_empty() {
+                writeln!(f, "Notes:")?;
+                writeln!(f, "\t{}", annotation.notes.join("\n\t"))?;
+            }
         }
-        if !self.0.desc.is_empty() {
+        if !self.spell.desc.is_empty() {
             writeln!(f, "Description:")?;
-            writeln!(f, "\t{}", self.0.desc.join("\n\t"))?;
+            write!(f, "{}", CliDisplayRichText::new(&self.spell.desc))?;
         }
-        writeln!(f, "-----{}----", "-".repeat(self.0.name.len()))?;
-        writeln!(f, "-----{}----", "-".repeat(self.0.name.len()))?;
+        writeln!(f, "-----{}----", "-".repeat(self.spell.name.len()))?;
+        writeln!(f, "-----{}----", "-".repeat(self.spell.name.len()))?;
 
         Ok(())
     }
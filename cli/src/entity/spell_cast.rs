@@ -0,0 +1,43 @@
+use lib::core::entity::spell_cast::{CastEffect, SpellCast};
+use std::fmt::{Display, Error, Formatter};
+
+use crate::entity::dice_set::CliDisplayDice;
+
+pub struct CliDisplaySpellCast<'a>(&'a SpellCast);
+
+impl CliDisplaySpellCast<'_> {
+    pub fn new(spell_cast: &SpellCast) -> CliDisplaySpellCast<'_> {
+        CliDisplaySpellCast(spell_cast)
+    }
+}
+
+impl<'a> Display for CliDisplaySpellCast<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "-----{}----", "-".repeat(self.0.spell_name.len()))?;
+        writeln!(f, "-----{}----", self.0.spell_name)?;
+        if let Some(slot) = self.0.slot {
+            writeln!(f, "Slot Level:")?;
+            writeln!(f, "\t{}", slot)?;
+        }
+        if let Some(caster_level) = self.0.caster_level {
+            writeln!(f, "Caster Level:")?;
+            writeln!(f, "\t{}", caster_level)?;
+        }
+        match &self.0.effect {
+            CastEffect::Damage(Some(damage_type)) => writeln!(f, "Damage ({}):", damage_type)?,
+            CastEffect::Damage(None) => writeln!(f, "Damage:")?,
+            CastEffect::Healing => writeln!(f, "Healing:")?,
+        }
+        writeln!(f, "\t{}", self.0.expression)?;
+        writeln!(f, "Total: \t\t{}", self.0.total())?;
+        for (dice, result) in &self.0.dice_set.dice_map {
+            writeln!(f, "|---{}: \t{}", CliDisplayDice::new(dice), result)?;
+        }
+        if self.0.bonus != 0 {
+            writeln!(f, "|---bonus: \t{}", self.0.bonus)?;
+        }
+        writeln!(f, "-----{}----", "-".repeat(self.0.spell_name.len()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use lib::core::entity::spell::{SpellLevel, MAX_SPELL_LEVEL};
+use lib::core::entity::spell_cast::CastEffect;
+use lib::core::entity::spell_comparison::{SpellComparison, SpellComparisonColumn};
+use lib::core::usecase::cast::{resolve_cantrip, resolve_slot};
+use std::fmt::{Display, Error, Formatter};
+
+pub struct CliDisplaySpellComparison<'a>(&'a SpellComparison);
+
+impl CliDisplaySpellComparison<'_> {
+    pub fn new(comparison: &SpellComparison) -> CliDisplaySpellComparison<'_> {
+        CliDisplaySpellComparison(comparison)
+    }
+
+    fn or_dash(value: Option<String>) -> String {
+        match value {
+            Some(value) if !value.is_empty() => value,
+            _ => "-".to_string(),
+        }
+    }
+
+    fn average(value: Option<&f64>) -> String {
+        Self::or_dash(value.map(|average| format!("{:.1}", average)))
+    }
+
+    fn level(column: &SpellComparisonColumn) -> String {
+        match column.spell.level.is_cantrip() {
+            true => "0 (cantrip)".to_string(),
+            false => column.spell.level.to_string(),
+        }
+    }
+
+    /// Damage or healing at the spell's level, or at the compared caster level for cantrips,
+    /// looked up like the averages
+    fn damage(&self, column: &SpellComparisonColumn) -> String {
+        let spell = &column.spell;
+        let resolved = match spell.level.is_cantrip() {
+            true => resolve_cantrip(spell, Some(self.0.caster_level))
+                .map(|(effect, _, expression)| (effect, expression)),
+            false => resolve_slot(spell, None).map(|(effect, _, expression)| (effect, expression)),
+        };
+        match resolved {
+            Ok((CastEffect::Damage(Some(damage_type)), expression)) => {
+                format!("{} {}", expression, damage_type)
+            }
+            Ok((CastEffect::Damage(None), expression)) => expression,
+            Ok((CastEffect::Healing, expression)) => format!("{} healing", expression),
+            Err(_) => Self::or_dash(
+                spell
+                    .damage
+                    .as_ref()
+                    .and_then(|damage| damage.damage_type.clone()),
+            ),
+        }
+    }
+
+    fn rows(&self) -> Vec<(String, Vec<String>)> {
+        let columns = &self.0.columns;
+        let row = |label: &str, cell: &dyn Fn(&SpellComparisonColumn) -> String| {
+            let cells = columns
+                .iter()
+                .map(|column| Self::or_dash(Some(cell(column))))
+                .collect::<Vec<String>>();
+            (label.to_string(), cells)
+        };
+        let mut rows = vec![
+            row("Level", &Self::level),
+            row("School", &|c| Self::or_dash(c.spell.school.clone())),
+            row("Range", &|c| Self::or_dash(c.spell.range.clone())),
+            row("Components", &|c| c.spell.components.join(", ")),
+            row("Duration", &|c| Self::or_dash(c.spell.duration.clone())),
+            row("Damage", &|c| self.damage(c)),
+        ];
+        if self.0.has_cantrips() {
+            let label = format!("Avg (caster level {})", self.0.caster_level);
+            rows.push(row(&label, &|c| Self::average(c.cantrip_average.as_ref())));
+        }
+        for slot in 1..=MAX_SPELL_LEVEL {
+            let level = SpellLevel::new(slot).unwrap_or_default();
+            let label = format!("Avg slot {}", slot);
+            rows.push(row(&label, &|c| {
+                Self::average(c.average_at_slot_level.get(&level))
+            }));
+        }
+        rows
+    }
+}
+
+impl<'a> Display for CliDisplaySpellComparison<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let rows = self.rows();
+        let names = self
+            .0
+            .columns
+            .iter()
+            .map(|column| column.spell.name.clone())
+            .collect::<Vec<String>>();
+        let label_width = rows
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let widths = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                rows.iter()
+                    .map(|(_, cells)| cells[i].chars().count())
+                    .chain([name.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<usize>>();
+        let write_row = |f: &mut Formatter<'_>, label: &str, cells: &[String]| {
+            write!(f, "{:<width$}", label, width = label_width)?;
+            for (cell, width) in cells.iter().zip(&widths) {
+                write!(f, " | {:<width$}", cell, width = width)?;
+            }
+            writeln!(f)
+        };
+        write_row(f, "", &names)?;
+        let separator = widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<String>>();
+        writeln!(f, "{}-+{}", "-".repeat(label_width), separator.join("+"))?;
+        for (label, cells) in &rows {
+            write_row(f, label, cells)?;
+        }
+        if self.0.modifier != 0 {
+            writeln!(f, "(averages include a modifier of {})", self.0.modifier)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use lib::core::entity::spell::SpellLevel;
+use lib::core::entity::spell_slots::{CasterSlots, SpellSlots};
+use std::fmt::{Display, Error, Formatter};
+
+pub struct CliDisplaySpellSlots<'a>(&'a SpellSlots, Option<&'a CasterSlots>);
+
+impl<'a> CliDisplaySpellSlots<'a> {
+    pub fn new(slots: &'a SpellSlots, caster_slots: Option<&'a CasterSlots>) -> Self {
+        CliDisplaySpellSlots(slots, caster_slots)
+    }
+
+    fn remaining(&self, maximum: u8, expended: u8) -> String {
+        match self.1 {
+            Some(_) => format!("{}/{}", maximum.saturating_sub(expended), maximum),
+            None => maximum.to_string(),
+        }
+    }
+}
+
+impl<'a> Display for CliDisplaySpellSlots<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        if let Some(caster_slots) = self.1 {
+            writeln!(f, "-----{}----", "-".repeat(caster_slots.character.len()))?;
+            writeln!(f, "-----{}----", caster_slots.character)?;
+            writeln!(f, "Classes:")?;
+            let class_levels = caster_slots
+                .class_levels
+                .iter()
+                .map(|class_level| class_level.to_string())
+                .collect::<Vec<String>>();
+            writeln!(f, "\t{}", class_levels.join(", "))?;
+        }
+        if self.0.is_empty() {
+            writeln!(f, "No spell slots")?;
+        }
+        if self.0.slots.iter().any(|slots| *slots > 0) {
+            writeln!(f, "Spell Slots:")?;
+            for (index, maximum) in self.0.slots.iter().enumerate() {
+                if *maximum == 0 {
+                    continue;
+                }
+                let level = SpellLevel::new(index as u8 + 1).unwrap_or_default();
+                let expended = self.1.map_or(0, |c| c.expended_at_level(level));
+                writeln!(
+                    f,
+                    "\tLevel {}: {}",
+                    level,
+                    self.remaining(*maximum, expended)
+                )?;
+            }
+        }
+        if let Some(pact) = self.0.pact {
+            writeln!(f, "Pact Slots (level {}):", pact.level)?;
+            let expended = self.1.map_or(0, |c| c.pact_expended);
+            writeln!(f, "\t{}", self.remaining(pact.count, expended))?;
+        }
+        if let Some(caster_slots) = self.1 {
+            writeln!(f, "-----{}----", "-".repeat(caster_slots.character.len()))?;
+        }
+
+        Ok(())
+    }
+}
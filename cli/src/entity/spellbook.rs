@@ -0,0 +1,39 @@
+use lib::core::entity::spellbook::Spellbook;
+use std::fmt::{Display, Error, Formatter};
+
+pub struct CliDisplaySpellbook<'a>(&'a Spellbook);
+
+impl CliDisplaySpellbook<'_> {
+    pub fn new(spellbook: &Spellbook) -> CliDisplaySpellbook<'_> {
+        CliDisplaySpellbook(spellbook)
+    }
+}
+
+impl<'a> Display for CliDisplaySpellbook<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "-----{}----", "-".repeat(self.0.character.len()))?;
+        writeln!(f, "-----{}----", self.0.character)?;
+        writeln!(f, "Class:")?;
+        writeln!(f, "\t{}", self.0.class)?;
+        writeln!(f, "Max Spell Level:")?;
+        writeln!(f, "\t{}", self.0.max_spell_level)?;
+        writeln!(f, "Prepared:")?;
+        match self.0.max_prepared {
+            Some(max_prepared) => writeln!(f, "\t{}/{}", self.0.prepared_count(), max_prepared)?,
+            None => writeln!(f, "\t{}", self.0.prepared_count())?,
+        }
+        if !self.0.known.is_empty() {
+            writeln!(f, "Known Spells:")?;
+            for entry in &self.0.known {
+                let marker = match entry.prepared || entry.level.is_cantrip() {
+                    true => "[P]",
+                    false => "[ ]",
+                };
+                writeln!(f, "\t{} {} ({})", marker, entry.name, entry.level)?;
+            }
+        }
+        writeln!(f, "-----{}----", "-".repeat(self.0.character.len()))?;
+
+        Ok(())
+    }
+}
@@ -1,12 +1,22 @@
 mod cli;
-use cli::MainCli;
+use clap::Parser;
+use cli::{Arguments, CliError, MainCli};
 use lib::config::{handler::SettingsHandler, settings::Settings};
 
 use std::env;
+use std::process;
 
 mod entity {
+    pub mod area_of_effect;
+    pub mod caster;
     pub mod dice_set;
+    pub mod reference;
+    pub mod rich_text;
     pub mod spell;
+    pub mod spell_cast;
+    pub mod spell_comparison;
+    pub mod spell_slots;
+    pub mod spellbook;
 }
 
 #[tokio::main]
@@ -14,10 +24,33 @@ async fn main() {
     env_logger::init();
 
     let folder_path = env::var("DND_SETTINGS_DIR").unwrap_or_else(|_| ".config".into());
-    let settings_handler = SettingsHandler::new(Settings::new(&folder_path).unwrap());
-    let spell_usecase = settings_handler.setup_spell_usecase();
-    let dice_usecase = settings_handler.setup_dice_usecase();
+    let args = Arguments::parse();
+    if let Err(err) = args.validate() {
+        err.exit();
+    }
+    let mut settings = match Settings::new(&folder_path) {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("Invalid settings in '{}': {}", folder_path, err);
+            process::exit(2);
+        }
+    };
+    settings.spell_cache_settings.offline |= args.offline;
+    if let Some(from) = args.import_from() {
+        settings.spell_database_settings.import_from = Some(from);
+    }
 
-    let mut cli = MainCli::new(spell_usecase, dice_usecase);
-    cli.run().await.unwrap();
+    let mut cli = MainCli::new(args, SettingsHandler::new(settings));
+    if let Err(err) = cli.run().await {
+        match &err {
+            CliError::Configuration(_) => eprintln!("{}", err),
+            err => {
+                if let Some(api_error) = err.api_error() {
+                    eprintln!("{}", api_error);
+                }
+                eprintln!("Error: {}", err);
+            }
+        }
+        process::exit(1);
+    }
 }
@@ -1,6 +1,9 @@
 use assert_cmd::prelude::*;
+use lib::datasources::common::mock_server::MockServer;
 use predicates::prelude::*;
+use std::fs;
 use std::process::Command;
+use tempfile::TempDir;
 
 pub fn get_cli_command(folder_path: Option<&str>, env: Option<&str>) -> Command {
     let mut cmd = Command::cargo_bin("cli").unwrap();
@@ -9,16 +12,42 @@ pub fn get_cli_command(folder_path: Option<&str>, env: Option<&str>) -> Command
 
     cmd.env("DND_SETTINGS_DIR", folder_path.unwrap_or(default_path));
 
-    match env {
-        Some(s) => {
-            cmd.env("ENV", s);
-        }
-        None => {}
-    };
+    if let Some(s) = env {
+        cmd.env("ENV", s);
+    }
 
     cmd
 }
 
+/// Settings folder with the default settings and `overrides` as the `test` environment,
+/// `{dir}` in `overrides` is replaced by the folder path
+pub fn settings_dir(overrides: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_str().unwrap().to_string();
+    fs::copy("../.config/default.yml", dir.path().join("default.yml")).unwrap();
+    fs::write(
+        dir.path().join("test.yml"),
+        format!("debug: true\n{}", overrides.replace("{dir}", &path)),
+    )
+    .unwrap();
+    dir
+}
+
+/// Local GraphQL API answering from the recorded responses in `tests/fixtures/graphql`
+pub fn mock_api() -> MockServer {
+    MockServer::graphql_fixtures("tests/fixtures/graphql")
+}
+
+/// Settings folder like `settings_dir` querying `server` without persisting spells
+pub fn mock_settings_dir(server: &MockServer, overrides: &str) -> TempDir {
+    settings_dir(&format!(
+        "spell_settings:\n  spell_api:\n    url: {}/graphql\n    retries: 0\n\
+         spell_cache_settings:\n  enabled: false\n{}",
+        server.url(),
+        overrides
+    ))
+}
+
 #[derive(Debug)]
 pub enum CommandAssertionFailure {
     FailDidNotFail,
@@ -0,0 +1,12 @@
+//! CLI tests, built as a single binary sharing the `common` helpers
+
+mod common;
+
+mod test_caster_api;
+mod test_data_api;
+mod test_dice_api;
+mod test_file_api;
+mod test_offline_api;
+mod test_slots_api;
+mod test_spell_api;
+mod test_spellbook_api;
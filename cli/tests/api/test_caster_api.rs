@@ -0,0 +1,50 @@
+use crate::common;
+
+const CASTER_SETTINGS: &str = "caster_settings:\n  path: {dir}/casters.yml\n";
+
+#[test]
+fn test_set_and_show_caster() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(CASTER_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("caster").args([
+        "set",
+        "Elminster",
+        "--ability",
+        "int",
+        "--score",
+        "17",
+        "--level",
+        "5",
+        "--dc-bonus",
+        "1",
+    ]);
+    common::assert_success_contains(cmd, r"Intelligence 17 \(\+3\)\nProficiency bonus:\n\t\+3")
+        .unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("caster").args(["show", "elminster"]);
+    common::assert_success_contains(
+        cmd,
+        r"Spell save DC:\n\t15 \(\+1 from items\)\nSpell attack bonus:\n\t\+6\n",
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_set_caster_without_proficiency_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(CASTER_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("caster")
+        .args(["set", "Mialee", "--ability", "int", "--score", "16"]);
+    common::assert_failure_contains(cmd, "--proficiency").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("caster").args(["show", "Mialee"]);
+    common::assert_failure_contains(cmd, "UnknownCharacter").unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::common;
+
+const SQLITE_SETTINGS: &str = "spell_settings:
+  spell_datasource:
+    remote_type: sqlite
+    path: tests/fixtures/5e-database
+spell_cache_settings:
+  enabled: false
+spell_database_settings:
+  path: {dir}/spells.db
+  import_from: file
+";
+
+#[test]
+fn test_import_and_query_sqlite_datasource() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SQLITE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("data").arg("import");
+    common::assert_success_contains(cmd, "Imported 5 spells").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-c", "wizard", "-l", "3", "-e"]);
+    common::assert_success_contains(cmd, "-----Fireball----").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "cure wounds"]);
+    common::assert_success_contains(cmd, r"Classes:\n\tBard, Cleric").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_import_from_sqlite_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SQLITE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("data").args(["import", "--from", "sqlite"]);
+    common::assert_failure_contains(cmd, "cannot import from itself").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("data").args(["import", "--from", "csv"]);
+    common::assert_failure_contains(cmd, "'csv' is not a spell datasource").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_import_without_source_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let settings = SQLITE_SETTINGS.replace("  import_from: file\n", "");
+    let dir = common::settings_dir(&settings);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("data").arg("import");
+    common::assert_failure_contains(cmd, "cannot import from itself").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_import_skips_persisted_spells() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::settings_dir(&format!(
+        "spell_settings:\n  spell_api:\n    url: {}/graphql\n\
+         spell_cache_settings:\n  enabled: true\n  path: {{dir}}/spells.json\n  max_age: 1000000000000\n\
+         spell_database_settings:\n  path: {{dir}}/spells.db\n",
+        server.url()
+    ));
+    std::fs::write(
+        dir.path().join("spells.json"),
+        format!(
+            r#"{{"metadata": {{"fetched_at": 0, "source": "{}/graphql", "schema_version": 1}},
+               "spells": [{{"index": "fireball", "name": "Fireball", "level": 3}}]}}"#,
+            server.url()
+        ),
+    )?;
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("data").arg("import");
+    common::assert_success_contains(cmd, "Imported 7 spells").unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use crate::common;
+
+const FILE_SETTINGS: &str = "spell_settings:
+  spell_datasource:
+    remote_type: file
+    path: tests/fixtures/5e-database
+spell_cache_settings:
+  enabled: false
+caster_settings:
+  path: {dir}/casters.yml
+";
+
+#[test]
+fn test_spells_from_file_datasource() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(FILE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell")
+        .args(["-c", "cleric", "--subclass", "life", "-l", "1"]);
+    common::assert_success_contains(cmd, r"-----Bless----(.|\n)*Always prepared:\n\tLife").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-r", "-l", "0", "-e"]);
+    common::assert_success_contains(cmd, "-----Fire Bolt----").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_expand_without_conditions_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(FILE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "fireball", "--expand"]);
+    common::assert_failure_contains(cmd, "--expand needs the graphql datasource").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_caster_save_dc_and_attack_from_file_datasource() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(FILE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("caster")
+        .args(["set", "Elminster", "-a", "int", "-s", "18", "-p", "3"]);
+    common::assert_success_contains(cmd, "Spell save DC:\n\t15\n").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell")
+        .args(["-n", "fireball", "--caster", "Elminster"]);
+    common::assert_success_contains(
+        cmd,
+        r"Saving throw:\n\tDC 15 Dexterity \(half on a success\)",
+    )
+    .unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell")
+        .args(["-n", "fire bolt", "--caster", "Elminster"]);
+    common::assert_success_contains(cmd, r"Spell attack:\n\t\+7 to hit \(ranged\)").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_file_datasource_without_path_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir("spell_settings:\n  spell_datasource:\n    remote_type: file\n");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "fireball"]);
+    common::assert_failure_contains(cmd, "Missing setting spell_settings.spell_datasource.path")
+        .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_unknown_datasource_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir("spell_settings:\n  spell_datasource:\n    remote_type: csv\n");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "fireball"]);
+    common::assert_failure_contains(
+        cmd,
+        "'csv' is not a spell datasource, expected one of file, graphql, rest, sqlite",
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_dice_with_unknown_datasource() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir("spell_settings:\n  spell_datasource:\n    remote_type: csv\n");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("dice").arg("1d6");
+    common::assert_success_contains(cmd, "1d6").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_punctuation_after_emphasis() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(FILE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.env("COLUMNS", "200")
+        .arg("spell")
+        .args(["-n", "cure wounds"]);
+    common::assert_success_contains(cmd, r"your spellcasting ability modifier\. This spell")
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::common;
+
+const CACHE_SETTINGS: &str =
+    "spell_cache_settings:\n  enabled: true\n  path: {dir}/spells.json\n  max_age: 86400\n";
+
+const CACHED_SPELLS: &str = r#"{
+  "metadata": {
+    "fetched_at": 0,
+    "source": "https://www.dnd5eapi.co/graphql",
+    "schema_version": 1
+  },
+  "spells": [
+    {
+      "index": "fireball",
+      "name": "Fireball",
+      "level": 3,
+      "classes": ["Sorcerer", "Wizard"],
+      "save": {"ability": "dexterity", "success": "half"}
+    }
+  ]
+}"#;
+
+#[test]
+fn test_offline_spell_from_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(CACHE_SETTINGS);
+    std::fs::write(dir.path().join("spells.json"), CACHED_SPELLS)?;
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["--offline", "spell", "-n", "fireball"]);
+    common::assert_success_contains(cmd, r"-----Fireball----\nLevel:\n\t3").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["spell", "-c", "wizard", "--offline"]);
+    common::assert_success_contains(cmd, r"Saving throw:\n\tDexterity \(half on a success\)")
+        .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_without_cache_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(CACHE_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["--offline", "spell", "-r"]);
+    common::assert_failure_contains(cmd, "NotCached").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_expand_without_persisted_conditions() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(CACHE_SETTINGS);
+    std::fs::write(dir.path().join("spells.json"), CACHED_SPELLS)?;
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["--offline", "spell", "-n", "fireball", "--expand"]);
+    common::assert_success_contains(cmd, r"-----Fireball----").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_offline_expand_from_persisted_conditions() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::settings_dir(&format!(
+        "spell_settings:\n  spell_api:\n    url: {}/graphql\n    retries: 0\n{}",
+        server.url(),
+        CACHE_SETTINGS
+    ));
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["spell", "-n", "fear", "--expand"]);
+    common::assert_success_contains(cmd, r"Frightened \(condition\)").unwrap();
+    assert!(dir.path().join("conditions.json").is_file());
+    let requests = server.requests();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.args(["--offline", "spell", "-n", "fear", "--expand"]);
+    common::assert_success_contains(cmd, r"-----Fear----(.|\n)*Frightened \(condition\)").unwrap();
+    assert_eq!(server.requests(), requests);
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::common;
+
+const SLOTS_SETTINGS: &str = "spell_slots_settings:\n  path: {dir}/slots.yml\n";
+
+#[test]
+fn test_calc_multiclass_slots() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("slots")
+        .args(["calc", "cleric:3", "paladin:4", "warlock:3"]);
+    common::assert_success_contains(cmd, r"Level 3: 2\nPact Slots \(level 2\):\n\t2").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_calc_with_invalid_class_level_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("slots").args(["calc", "wizard:25"]);
+    common::assert_failure_contains(cmd, "not a class level").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_use_and_rest_slots() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SLOTS_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["set", "Fizban", "wizard:1"]);
+    common::assert_success_contains(cmd, r"Level 1: 2/2").unwrap();
+
+    for remaining in ["1/2", "0/2"] {
+        let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+        cmd.arg("slots").args(["use", "Fizban", "1"]);
+        common::assert_success_contains(cmd, remaining).unwrap();
+    }
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["use", "Fizban", "1"]);
+    common::assert_failure_contains(cmd, "NoSlotRemaining").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["rest", "Fizban", "--long"]);
+    common::assert_success_contains(cmd, r"Level 1: 2/2").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_use_pact_slot_of_other_level_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SLOTS_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["set", "Fizban", "warlock:3"]);
+    common::assert_success_contains(cmd, r"Pact Slots \(level 2\)").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["use", "Fizban", "1", "--pact"]);
+    common::assert_failure_contains(cmd, "NotPactSlotLevel").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("slots").args(["use", "Fizban", "2", "--pact"]);
+    common::assert_success_contains(cmd, r"Pact Slots \(level 2\)").unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,394 @@
+use crate::common;
+
+#[test]
+fn test_single_random_spell() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+
+    cmd.arg("spell").args(["-r"]);
+    common::assert_success_contains(cmd, "Classes:\n").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_by_class() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+
+    cmd.arg("spell").args(["-r", "-c", "bard"]);
+    common::assert_success_contains(cmd, "Bard").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_by_level() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+
+    cmd.arg("spell").args(["-r", "-l", "2"]);
+    let pattern = r"Level:\s+[0-2]\b";
+    common::assert_success_contains(cmd, pattern).unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_by_exact_level() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+
+    cmd.arg("spell").args(["-r", "-l", "2", "-e"]);
+    common::assert_success_contains(cmd, "Level:\n\t2").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_bad_args() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["not", "a", "set of", "commands"]);
+    common::assert_failure_contains(cmd, "USAGE").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_fractional_level_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-r", "-l", "2.5"]);
+    common::assert_failure_contains(cmd, "'2.5' is not a spell level").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_reversed_level_range_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-r", "-l", "3..1"]);
+    common::assert_failure_contains(cmd, "not a valid level range").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_cantrips_and_level_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-r", "--cantrips", "-l", "2"]);
+    common::assert_failure_contains(cmd, "cannot be used with").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_exact_level_range_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-l", "1..3", "-e"]);
+    common::assert_failure_contains(cmd, "'--exact-level' cannot be used with the level range")
+        .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_cast_with_invalid_slot_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["cast", "fireball", "--slot", "10"]);
+    common::assert_failure_contains(cmd, "out of range").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_cast_with_negative_modifier() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell")
+        .args(["cast", "cure wounds", "--slot", "2", "-m", "-1"]);
+    common::assert_success_contains(cmd, r"\|---bonus: \t-1").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_cast_cantrip_with_slot_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["cast", "fire bolt", "--slot", "1"]);
+    common::assert_failure_contains(cmd, "SlotForCantrip").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_export_with_unknown_format_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-c", "wizard", "--export", "pdf"]);
+    common::assert_failure_contains(cmd, "'pdf' is not an export format").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_out_without_export_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["-c", "wizard", "--out", "wizard.md"]);
+    common::assert_failure_contains(cmd, "--export <FORMAT>").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_export_with_cards_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["-c", "wizard", "--export", "json", "--cards", "svg"]);
+    common::assert_failure_contains(cmd, "cannot be used with").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_compare_cantrip_at_caster_level() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell")
+        .args(["compare", "fire bolt", "fireball", "--caster-level", "5"]);
+    common::assert_success_contains(cmd, r"Damage +\| 2d10 Fire +\| 8d6 Fire").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_compare_single_spell_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["compare", "fireball"]);
+    common::assert_failure_contains(cmd, "requires at least 2 values").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_count_without_random_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["--count", "3", "--deck", "scrolls"]);
+    common::assert_failure_contains(cmd, "--random").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_with_invalid_weight_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell").args(["-r", "--weight", "level:1=-1"]);
+    common::assert_failure_contains(cmd, "'1=-1' is not a weight").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_by_subclass() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+
+    cmd.arg("spell")
+        .args(["-c", "cleric", "--subclass", "life", "-l", "1"]);
+    common::assert_success_contains(cmd, "Always prepared:\n\tLife").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_annotate_favourite_and_unfavourite_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["annotate", "fireball", "--favourite", "--unfavourite"]);
+    common::assert_failure_contains(cmd, "cannot be used with").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_annotate_and_filter_by_tag() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(
+        &server,
+        "spell_annotation_settings:\n  path: {dir}/annotations.yml\n",
+    );
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args([
+        "annotate",
+        "fireball",
+        "--tag",
+        "aoe,fire",
+        "--favourite",
+        "--note",
+        "Mind the party",
+    ]);
+    common::assert_success_contains(cmd, r"Tags:\n\taoe, fire").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["--tag", "aoe", "--favourite"]);
+    common::assert_success_contains(cmd, r"Notes:\n\tMind the party").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_expand() -> Result<(), Box<dyn std::error::Error>> {
+    let server = common::mock_api();
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "fear", "--expand"]);
+    common::assert_success_contains(
+        cmd,
+        r"-----Fear----(.|\n)*Frightened \(condition\):\n\t• A frightened creature has disadvantage",
+    )
+    .unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_expand_with_export_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["-n", "fear", "--expand", "--export", "json"]);
+    common::assert_failure_contains(cmd, "cannot be used with").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_caster_with_cards_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["-n", "fireball", "--caster", "Elminster", "--cards", "svg"]);
+    common::assert_failure_contains(cmd, "cannot be used with").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_expand_with_cards_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = common::get_cli_command(None, None);
+
+    cmd.arg("spell")
+        .args(["-n", "fireball", "--expand", "--cards", "html"]);
+    common::assert_failure_contains(cmd, "'--expand' cannot be used with").unwrap();
+
+    Ok(())
+}
+
+const AOE_MAP: &str = "origin: Wizard
+target: Goblin
+tokens:
+  - name: Wizard
+    at: [0, 5]
+  - name: Goblin
+    at: [3, 5]
+  - name: Orc
+    at: [3, 6]
+  - name: Ogre
+    at: [3, 7]
+";
+
+#[test]
+fn test_spell_aoe_cone_on_map() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let map = dir.path().join("map.yml");
+    std::fs::write(&map, AOE_MAP)?;
+
+    let mut cmd = common::get_cli_command(None, None);
+    cmd.arg("spell")
+        .args(["aoe", "--shape", "cone", "--size", "15", "--map"])
+        .arg(&map);
+    common::assert_success_contains(cmd, r"Affected:\n\tGoblin, Orc\n").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_aoe_line_without_target_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let map = dir.path().join("map.yml");
+    std::fs::write(&map, "origin: [0, 0]\n")?;
+
+    let mut cmd = common::get_cli_command(None, None);
+    cmd.arg("spell")
+        .args(["aoe", "--shape", "line", "--size", "100", "-m"])
+        .arg(&map);
+    common::assert_failure_contains(cmd, "MissingTarget").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_from_replayed_fixtures() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = std::fs::canonicalize("tests/fixtures/graphql")?;
+    let dir = common::settings_dir(&format!(
+        "spell_settings:\n  spell_api:\n    url: http://127.0.0.1:9/graphql\n    \
+         fixture_mode: replay\n    fixture_path: {}\n\
+         spell_cache_settings:\n  enabled: false\n",
+        fixtures.display()
+    ));
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-n", "shatter"]);
+    common::assert_success_contains(cmd, r"-----Shatter----\nLevel:\n\t2").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spell_prints_every_graphql_error() -> Result<(), Box<dyn std::error::Error>> {
+    let fixtures = tempfile::tempdir()?;
+    std::fs::write(
+        fixtures.path().join("SpellsQuery.json"),
+        r#"{"data": null, "errors": [{"message": "Spells unavailable"}, {"message": "Try later"}]}"#,
+    )?;
+    let server =
+        lib::datasources::common::mock_server::MockServer::graphql_fixtures(fixtures.path());
+    let dir = common::mock_settings_dir(&server, "");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spell").args(["-r"]);
+    common::assert_failure_contains(cmd, r"(?s)Spells unavailable.*Try later").unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::common;
+
+const SPELLBOOK_SETTINGS: &str = "spellbook_settings:\n  path: {dir}/spellbooks.yml\n";
+
+#[test]
+fn test_new_and_list_spellbook() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SPELLBOOK_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["new", "Elminster", "-c", "wizard", "-l", "3", "-p", "8"]);
+    common::assert_success_contains(cmd, "Elminster").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook").args(["list", "elminster"]);
+    common::assert_success_contains(cmd, r"Prepared:\n\t0/8").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_new_duplicate_spellbook_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SPELLBOOK_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["new", "Elminster", "-c", "wizard", "-l", "3"]);
+    common::assert_success_contains(cmd, "Elminster").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["new", "Elminster", "-c", "wizard", "-l", "3"]);
+    common::assert_failure_contains(cmd, "CharacterExists").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_prepare_unknown_spell_fails() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SPELLBOOK_SETTINGS);
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["new", "Elminster", "-c", "wizard", "-l", "3"]);
+    common::assert_success_contains(cmd, "Elminster").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["prepare", "Elminster", "Shield"]);
+    common::assert_failure_contains(cmd, "SpellNotKnown").unwrap();
+
+    Ok(())
+}
+
+#[test]
+fn test_spellbook_cards_to_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = common::settings_dir(SPELLBOOK_SETTINGS);
+    let out = dir.path().join("cards.svg");
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["new", "Elminster", "-c", "wizard", "-l", "3"]);
+    common::assert_success_contains(cmd, "Elminster").unwrap();
+
+    let mut cmd = common::get_cli_command(dir.path().to_str(), Some("test"));
+    cmd.arg("spellbook")
+        .args(["cards", "Elminster", "--format", "svg", "--out"])
+        .arg(&out);
+    common::assert_success_contains(cmd, "Rendered cards for 0 spells").unwrap();
+    assert!(std::fs::read_to_string(&out)?.starts_with("<svg"));
+
+    Ok(())
+}
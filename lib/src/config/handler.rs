@@ -1,50 +1,379 @@
-use crate::core::usecase::{dice::DiceImplementation, spell::SpellImplementation};
-use crate::core::usecase::{dice::DiceInterface, spell::SpellInterface};
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::{
+    area_of_effect::AreaOfEffectImplementation, cast::CastImplementation,
+    caster::CasterImplementation, dice::DiceImplementation, export::ExportImplementation,
+    reference::ReferenceImplementation, spell::SpellImplementation,
+    spell_annotation::SpellAnnotationImplementation, spell_card::SpellCardImplementation,
+    spell_draw::SpellDrawImplementation, spell_slots::SpellSlotsImplementation,
+    spellbook::SpellbookImplementation,
+};
+use crate::core::usecase::{
+    area_of_effect::AreaOfEffectInterface, cast::CastInterface, caster::CasterInterface,
+    dice::DiceInterface, export::ExportInterface, reference::ConditionsDataSourceInterface,
+    reference::ReferenceInterface, spell::SpellsDataSourceInterface,
+    spell_annotation::SpellAnnotationInterface, spell_card::SpellCardInterface,
+    spell_draw::SpellDrawInterface, spell_slots::SpellSlotsInterface,
+    spellbook::SpellbookInterface,
+};
 use crate::datasources::{
-    common::remote_datasource::GraphQLAPI, spells::spells_datasource::SpellsGraphQLDataSource,
+    caster::caster_datasource::CasterFileDataSource,
+    common::cached_datasource::CachedDataSource,
+    common::local_file::{LocalFile, LocalFileError},
+    common::remote_datasource::{GraphQLAPI, RestAPI},
+    conditions::conditions_datasource::ConditionsGraphQLDataSource,
+    conditions::persistent_datasource::PersistentConditionsDataSource,
+    spell_annotation::spell_annotation_datasource::SpellAnnotationFileDataSource,
+    spell_deck::spell_deck_datasource::SpellDeckFileDataSource,
+    spell_slots::spell_slots_datasource::SpellSlotsFileDataSource,
+    spellbook::spellbook_datasource::SpellbookFileDataSource,
+    spells::file_datasource::SpellsFileDataSource,
+    spells::homebrew_datasource::HomebrewSpellsDataSource,
+    spells::persistent_datasource::PersistentSpellsDataSource,
+    spells::rest_datasource::SpellsRestDataSource,
+    spells::spells_datasource::SpellsGraphQLDataSource,
 };
+#[cfg(feature = "sqlite")]
+use crate::{
+    core::usecase::spell_import::{SpellImportImplementation, SpellImportInterface},
+    datasources::spells::sqlite_datasource::SpellsSqliteDataSource,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use crate::config::registry::{
+    ConditionsDataSource, ConfigurationError, DataSourceRegistry, SpellsDataSource,
+};
 use crate::config::settings::Settings;
 
 use log::{debug, error, info};
 
+/// Spells of the configured datasource, cached and merged with the homebrew spells
+pub type SpellUsecase =
+    SpellImplementation<HomebrewSpellsDataSource<CachedDataSource<SpellsDataSource, Vec<Spell>>>>;
+
 pub struct SettingsHandler {
     settings: Settings,
+    registry: DataSourceRegistry,
 }
 
 impl SettingsHandler {
     pub fn new(settings: Settings) -> Self {
-        Self { settings }
+        let mut registry = DataSourceRegistry::new();
+        Self::register_builtin_datasources(&mut registry);
+        Self { settings, registry }
     }
-    pub fn setup_spell_usecase(&self) -> impl SpellInterface {
-        match self
-            .settings
-            .spell_settings
-            .spell_datasource
-            .remote_type
-            .clone()
-        {
-            x if x == "graphql" => {
-                info!("Fetching GraphQL Datasource");
-                Self::setup_graphql_spell_usecase(&self.settings)
-            }
-            _ => {
-                error!("Unknown data source requested!");
-                panic!("Unknown type")
-            }
+
+    /// Makes `constructor` available as `spell_datasource.remote_type: <kind>`, replacing the
+    /// built in datasource of the same type
+    pub fn register_spell_datasource<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<SpellsDataSource, ConfigurationError> + Send + Sync + 'static,
+    {
+        self.registry.register(kind, constructor);
+    }
+
+    /// Like `register_spell_datasource` for a datasource fetching from `spell_api.url`, its
+    /// spells are persisted like the API's
+    pub fn register_remote_spell_datasource<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<SpellsDataSource, ConfigurationError> + Send + Sync + 'static,
+    {
+        self.registry.register_remote(kind, constructor);
+    }
+
+    /// Makes `constructor` the conditions `--expand` reads with `spell_datasource.remote_type:
+    /// <kind>`, only the GraphQL API has conditions built in
+    pub fn register_conditions_datasource<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<ConditionsDataSource, ConfigurationError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.registry.register_conditions(kind, constructor);
+    }
+
+    pub fn setup_spell_usecase(&self) -> Result<SpellUsecase, ConfigurationError> {
+        let remote_type = &self.settings.spell_settings.spell_datasource.remote_type;
+        let mut datasource = self.setup_spell_datasource(remote_type)?;
+        if self.registry.is_remote(remote_type) {
+            let url = self.settings.spell_settings.spell_api.url.clone();
+            datasource = Box::new(Self::setup_persistent_spells(
+                &self.settings,
+                datasource,
+                url,
+            ));
+        }
+        Ok(Self::setup_spell_usecase_for(&self.settings, datasource))
+    }
+
+    /// Imports spells from `spell_database_settings.import_from` into the SQLite database,
+    /// defaulting to the configured datasource. The spells are fetched afresh, bypassing the
+    /// persisted and cached ones.
+    #[cfg(feature = "sqlite")]
+    pub fn setup_spell_import_usecase(
+        &self,
+    ) -> Result<impl SpellImportInterface, ConfigurationError> {
+        let remote_type = &self.settings.spell_settings.spell_datasource.remote_type;
+        let source = match &self.settings.spell_database_settings.import_from {
+            Some(source) => source.clone(),
+            None => remote_type.clone(),
+        };
+        if source == "sqlite" {
+            return Err(ConfigurationError::InvalidSetting(
+                "spell_database_settings.import_from".to_string(),
+                "the SQLite database cannot import from itself, pick another datasource"
+                    .to_string(),
+            ));
         }
+        info!("Importing spells from the {} datasource", source);
+        Ok(SpellImportImplementation::new(
+            self.setup_spell_datasource(&source)?,
+            Self::setup_sqlite_spells(&self.settings),
+        ))
     }
 
     pub fn setup_dice_usecase(&self) -> impl DiceInterface {
         DiceImplementation::new()
     }
 
-    fn setup_graphql_spell_usecase(
+    pub fn setup_cast_usecase(&self) -> impl CastInterface {
+        CastImplementation::new(DiceImplementation::new())
+    }
+
+    pub fn setup_export_usecase(&self) -> impl ExportInterface {
+        ExportImplementation::new()
+    }
+
+    pub fn setup_spell_card_usecase(&self) -> impl SpellCardInterface {
+        SpellCardImplementation::new()
+    }
+
+    pub fn setup_area_of_effect_usecase(&self) -> impl AreaOfEffectInterface {
+        AreaOfEffectImplementation::new()
+    }
+
+    /// Conditions of the configured datasource, only the GraphQL API has any. Conditions of
+    /// remote datasources are persisted next to the spells.
+    pub fn setup_reference_usecase(&self) -> Result<impl ReferenceInterface, ConfigurationError> {
+        let remote_type = &self.settings.spell_settings.spell_datasource.remote_type;
+        let mut datasource = self
+            .registry
+            .build_conditions(remote_type, &self.settings)
+            .inspect_err(|err| error!("{}", err))?;
+        if self.registry.is_remote(remote_type) {
+            let url = self.settings.spell_settings.spell_api.url.clone();
+            datasource = Box::new(Self::setup_persistent_conditions(
+                &self.settings,
+                datasource,
+                url,
+            ));
+        }
+        Ok(ReferenceImplementation::new(datasource))
+    }
+
+    pub fn setup_spellbook_usecase(&self) -> Result<impl SpellbookInterface, LocalFileError> {
+        let path = &self.settings.spellbook_settings.path;
+        debug!("Setting up SpellbookFileDataSource at '{}'", path);
+        let datasource = SpellbookFileDataSource::new(LocalFile::new(path)?);
+        Ok(SpellbookImplementation::new(datasource))
+    }
+
+    pub fn setup_spell_slots_usecase(&self) -> Result<impl SpellSlotsInterface, LocalFileError> {
+        let path = &self.settings.spell_slots_settings.path;
+        debug!("Setting up SpellSlotsFileDataSource at '{}'", path);
+        let datasource = SpellSlotsFileDataSource::new(LocalFile::new(path)?);
+        Ok(SpellSlotsImplementation::new(datasource))
+    }
+
+    pub fn setup_spell_draw_usecase(&self) -> Result<impl SpellDrawInterface, LocalFileError> {
+        let path = &self.settings.spell_deck_settings.path;
+        debug!("Setting up SpellDeckFileDataSource at '{}'", path);
+        let datasource = SpellDeckFileDataSource::new(LocalFile::new(path)?);
+        Ok(SpellDrawImplementation::new(datasource))
+    }
+
+    pub fn setup_spell_annotation_usecase(
+        &self,
+    ) -> Result<impl SpellAnnotationInterface, LocalFileError> {
+        let path = &self.settings.spell_annotation_settings.path;
+        debug!("Setting up SpellAnnotationFileDataSource at '{}'", path);
+        let datasource = SpellAnnotationFileDataSource::new(LocalFile::new(path)?);
+        Ok(SpellAnnotationImplementation::new(datasource))
+    }
+
+    pub fn setup_caster_usecase(&self) -> Result<impl CasterInterface, LocalFileError> {
+        let path = &self.settings.caster_settings.path;
+        debug!("Setting up CasterFileDataSource at '{}'", path);
+        let datasource = CasterFileDataSource::new(LocalFile::new(path)?);
+        Ok(CasterImplementation::new(datasource))
+    }
+
+    fn setup_spell_datasource(
+        &self,
+        remote_type: &str,
+    ) -> Result<SpellsDataSource, ConfigurationError> {
+        self.registry
+            .build(remote_type, &self.settings)
+            .inspect_err(|err| error!("{}", err))
+    }
+
+    fn register_builtin_datasources(registry: &mut DataSourceRegistry) {
+        registry.register_remote("graphql", |settings| {
+            info!("Fetching GraphQL Datasource");
+            Ok(Box::new(Self::setup_graphql_spells(settings)))
+        });
+        registry.register_conditions("graphql", |settings| {
+            Ok(Box::new(Self::setup_graphql_conditions(settings)))
+        });
+        registry.register_remote("rest", |settings| {
+            info!("Fetching REST Datasource");
+            Ok(Box::new(Self::setup_rest_spells(settings)))
+        });
+        registry.register("file", |settings| {
+            info!("Reading File Datasource");
+            Ok(Box::new(Self::setup_file_spells(settings)?))
+        });
+        #[cfg(feature = "sqlite")]
+        registry.register("sqlite", |settings| {
+            info!("Reading SQLite Datasource");
+            Ok(Box::new(Self::setup_sqlite_spells(settings)))
+        });
+    }
+
+    fn setup_persistent_spells<T>(
         settings: &Settings,
-    ) -> SpellImplementation<SpellsGraphQLDataSource> {
+        datasource: T,
+        source: String,
+    ) -> PersistentSpellsDataSource<T>
+    where
+        T: SpellsDataSourceInterface,
+    {
+        let cache_settings = &settings.spell_cache_settings;
+        let file = match cache_settings.enabled {
+            true => match LocalFile::new(&cache_settings.path) {
+                Ok(file) => {
+                    debug!("Persisting spells to '{}'", cache_settings.path);
+                    Some(file)
+                }
+                Err(err) => {
+                    error!("Not persisting spells: {:?}", err);
+                    None
+                }
+            },
+            false => None,
+        };
+        if cache_settings.offline {
+            info!("Offline, reading persisted spells only");
+        }
+        PersistentSpellsDataSource::new(
+            datasource,
+            file,
+            source,
+            Duration::from_secs(cache_settings.max_age),
+            cache_settings.offline,
+        )
+    }
+
+    fn setup_persistent_conditions<T>(
+        settings: &Settings,
+        datasource: T,
+        source: String,
+    ) -> PersistentConditionsDataSource<T>
+    where
+        T: ConditionsDataSourceInterface,
+    {
+        let cache_settings = &settings.spell_cache_settings;
+        let path = Path::new(&cache_settings.path).with_file_name("conditions.json");
+        let file = match cache_settings.enabled {
+            true => match LocalFile::new(&path) {
+                Ok(file) => {
+                    debug!("Persisting conditions to '{}'", path.display());
+                    Some(file)
+                }
+                Err(err) => {
+                    error!("Not persisting conditions: {:?}", err);
+                    None
+                }
+            },
+            false => None,
+        };
+        PersistentConditionsDataSource::new(
+            datasource,
+            file,
+            source,
+            Duration::from_secs(cache_settings.max_age),
+            cache_settings.offline,
+        )
+    }
+
+    fn setup_graphql_spells(settings: &Settings) -> impl SpellsDataSourceInterface + Send + Sync {
         debug!("Setting up SpellsGraphQLDataSource");
-        let api = GraphQLAPI::new(settings.spell_settings.spell_api.url.clone());
-        let datasource = SpellsGraphQLDataSource::new(api);
-        SpellImplementation::<SpellsGraphQLDataSource>::new(datasource)
+        let spell_api = &settings.spell_settings.spell_api;
+        let api = GraphQLAPI::with_options(spell_api.url.clone(), spell_api.client_options());
+        SpellsGraphQLDataSource::new(api)
+    }
+
+    fn setup_graphql_conditions(
+        settings: &Settings,
+    ) -> impl ConditionsDataSourceInterface + Send + Sync {
+        debug!("Setting up ConditionsGraphQLDataSource");
+        let spell_api = &settings.spell_settings.spell_api;
+        let api = GraphQLAPI::with_options(spell_api.url.clone(), spell_api.client_options());
+        ConditionsGraphQLDataSource::new(api)
+    }
+
+    fn setup_rest_spells(settings: &Settings) -> impl SpellsDataSourceInterface + Send + Sync {
+        debug!("Setting up SpellsRestDataSource");
+        let spell_api = &settings.spell_settings.spell_api;
+        SpellsRestDataSource::new(
+            RestAPI::with_options(spell_api.url.clone(), spell_api.client_options()),
+            settings
+                .spell_settings
+                .spell_datasource
+                .max_concurrent_requests,
+        )
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn setup_sqlite_spells(settings: &Settings) -> SpellsSqliteDataSource {
+        let path = &settings.spell_database_settings.path;
+        debug!("Setting up SpellsSqliteDataSource at '{}'", path);
+        SpellsSqliteDataSource::new(path)
+    }
+
+    fn setup_file_spells(
+        settings: &Settings,
+    ) -> Result<impl SpellsDataSourceInterface + Send + Sync, ConfigurationError> {
+        let setting = "spell_settings.spell_datasource.path";
+        let path = match &settings.spell_settings.spell_datasource.path {
+            Some(path) => path,
+            None => return Err(ConfigurationError::MissingSetting(setting.to_string())),
+        };
+        debug!("Setting up SpellsFileDataSource in '{}'", path);
+        SpellsFileDataSource::from_folder(path).map_err(|err| {
+            ConfigurationError::InvalidSetting(setting.to_string(), format!("{:?}", err))
+        })
+    }
+
+    /// Caches the spells of `datasource` and merges the homebrew spells on top
+    fn setup_spell_usecase_for<T>(
+        settings: &Settings,
+        datasource: T,
+    ) -> SpellImplementation<HomebrewSpellsDataSource<CachedDataSource<T, Vec<Spell>>>>
+    where
+        T: SpellsDataSourceInterface,
+    {
+        let cache_time = settings.spell_settings.spell_datasource.cache_time;
+        debug!("Caching remote spells for {}ms", cache_time);
+        let datasource = CachedDataSource::new(datasource, cache_time);
+        let homebrew = settings
+            .spell_settings
+            .homebrew
+            .iter()
+            .map(PathBuf::from)
+            .collect::<Vec<PathBuf>>();
+        debug!("Merging {} homebrew spell files", homebrew.len());
+        SpellImplementation::new(HomebrewSpellsDataSource::new(datasource, homebrew))
     }
 }
@@ -1,2 +1,3 @@
 pub mod handler;
+pub mod registry;
 pub mod settings;
@@ -0,0 +1,248 @@
+use crate::config::settings::Settings;
+use crate::core::usecase::reference::ConditionsDataSourceInterface;
+use crate::core::usecase::spell::SpellsDataSourceInterface;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+pub type SpellsDataSource = Box<dyn SpellsDataSourceInterface + Send + Sync>;
+
+pub type ConditionsDataSource = Box<dyn ConditionsDataSourceInterface + Send + Sync>;
+
+/// Builds a spell datasource of one `spell_datasource.remote_type` from the settings
+pub type SpellsDataSourceConstructor =
+    Box<dyn Fn(&Settings) -> Result<SpellsDataSource, ConfigurationError> + Send + Sync>;
+
+/// Builds the conditions datasource next to the spell datasource of the same type
+pub type ConditionsDataSourceConstructor =
+    Box<dyn Fn(&Settings) -> Result<ConditionsDataSource, ConfigurationError> + Send + Sync>;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConfigurationError {
+    /// The requested type and the registered ones
+    UnknownDataSource(String, Vec<String>),
+    MissingSetting(String),
+    /// The setting and why its value is invalid
+    InvalidSetting(String, String),
+    /// A spell datasource type without conditions
+    NoConditions(String),
+}
+
+impl Display for ConfigurationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ConfigurationError::UnknownDataSource(kind, kinds) => write!(
+                f,
+                "'{}' is not a spell datasource, expected one of {}",
+                kind,
+                kinds.join(", ")
+            ),
+            ConfigurationError::MissingSetting(setting) => {
+                write!(f, "Missing setting {}", setting)
+            }
+            ConfigurationError::InvalidSetting(setting, reason) => {
+                write!(f, "Invalid setting {}: {}", setting, reason)
+            }
+            ConfigurationError::NoConditions(kind) => write!(
+                f,
+                "--expand needs the graphql datasource, the {} datasource has no conditions",
+                kind
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigurationError {}
+
+struct RegisteredDataSource {
+    constructor: SpellsDataSourceConstructor,
+    remote: bool,
+}
+
+/// Spell and conditions datasource constructors by `spell_datasource.remote_type`
+#[derive(Default)]
+pub struct DataSourceRegistry {
+    datasources: HashMap<String, RegisteredDataSource>,
+    conditions: HashMap<String, ConditionsDataSourceConstructor>,
+}
+
+impl DataSourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `constructor` for `kind`, replacing any constructor registered before
+    pub fn register<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<SpellsDataSource, ConfigurationError> + Send + Sync + 'static,
+    {
+        self.insert(kind, Box::new(constructor), false);
+    }
+
+    /// Registers a datasource fetching over the network, the spell usecase persists its spells
+    /// for offline use
+    pub fn register_remote<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<SpellsDataSource, ConfigurationError> + Send + Sync + 'static,
+    {
+        self.insert(kind, Box::new(constructor), true);
+    }
+
+    /// Registers the conditions of `kind`, types without conditions can't expand spells
+    pub fn register_conditions<F>(&mut self, kind: &str, constructor: F)
+    where
+        F: Fn(&Settings) -> Result<ConditionsDataSource, ConfigurationError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.conditions
+            .insert(kind.to_string(), Box::new(constructor));
+    }
+
+    fn insert(&mut self, kind: &str, constructor: SpellsDataSourceConstructor, remote: bool) {
+        self.datasources.insert(
+            kind.to_string(),
+            RegisteredDataSource {
+                constructor,
+                remote,
+            },
+        );
+    }
+
+    pub fn is_remote(&self, kind: &str) -> bool {
+        matches!(self.datasources.get(kind), Some(datasource) if datasource.remote)
+    }
+
+    /// Registered types in alphabetical order
+    pub fn kinds(&self) -> Vec<String> {
+        let mut kinds = self.datasources.keys().cloned().collect::<Vec<String>>();
+        kinds.sort();
+        kinds
+    }
+
+    pub fn build(
+        &self,
+        kind: &str,
+        settings: &Settings,
+    ) -> Result<SpellsDataSource, ConfigurationError> {
+        match self.datasources.get(kind) {
+            Some(datasource) => (datasource.constructor)(settings),
+            None => Err(ConfigurationError::UnknownDataSource(
+                kind.to_string(),
+                self.kinds(),
+            )),
+        }
+    }
+
+    pub fn build_conditions(
+        &self,
+        kind: &str,
+        settings: &Settings,
+    ) -> Result<ConditionsDataSource, ConfigurationError> {
+        match self.conditions.get(kind) {
+            Some(constructor) => constructor(settings),
+            None => Err(ConfigurationError::NoConditions(kind.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::core::entity::reference::{Reference, ReferenceKind};
+    use crate::core::entity::spell::Spell;
+    use crate::core::usecase::reference::ConditionsDataSourceError;
+    use crate::core::usecase::spell::SpellsDataSourceError;
+
+    struct MockSpellsDataSource;
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for MockSpellsDataSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            Ok(vec![Spell {
+                index: "eldritch-blast".to_string(),
+                ..Default::default()
+            }])
+        }
+    }
+
+    struct MockConditionsDataSource;
+
+    #[async_trait(?Send)]
+    impl ConditionsDataSourceInterface for MockConditionsDataSource {
+        async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+            Ok(vec![Reference {
+                kind: ReferenceKind::Condition,
+                index: "charmed".to_string(),
+                name: "Charmed".to_string(),
+                desc: vec![],
+            }])
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings::new(&"../.config".to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_builds_registered_datasource() {
+        let mut registry = DataSourceRegistry::new();
+        registry.register("mock", |_| Ok(Box::new(MockSpellsDataSource)));
+        registry.register_remote("remote-mock", |_| Ok(Box::new(MockSpellsDataSource)));
+        assert!(!registry.is_remote("mock"));
+        assert!(registry.is_remote("remote-mock"));
+        assert!(!registry.is_remote("graphql"));
+
+        let datasource = registry.build("mock", &settings()).unwrap();
+        let spells = block_on(datasource.get_all_spells()).unwrap();
+        assert_eq!(spells[0].index, "eldritch-blast");
+    }
+
+    #[test]
+    fn test_unknown_datasource_lists_registered_ones() {
+        let mut registry = DataSourceRegistry::new();
+        registry.register("mock", |_| Ok(Box::new(MockSpellsDataSource)));
+        registry.register("file", |_| {
+            Err(ConfigurationError::MissingSetting("path".to_string()))
+        });
+
+        let err = registry.build("graphql", &settings()).err().unwrap();
+        assert_eq!(
+            err,
+            ConfigurationError::UnknownDataSource(
+                "graphql".to_string(),
+                vec!["file".to_string(), "mock".to_string()]
+            )
+        );
+        assert_eq!(
+            err.to_string(),
+            "'graphql' is not a spell datasource, expected one of file, mock"
+        );
+    }
+
+    #[test]
+    fn test_conditions_only_for_registered_types() {
+        let mut registry = DataSourceRegistry::new();
+        registry.register("mock", |_| Ok(Box::new(MockSpellsDataSource)));
+        registry.register("file", |_| Ok(Box::new(MockSpellsDataSource)));
+        registry.register_conditions("mock", |_| Ok(Box::new(MockConditionsDataSource)));
+
+        let datasource = registry.build_conditions("mock", &settings()).unwrap();
+        let conditions = block_on(datasource.get_all_conditions()).unwrap();
+        assert_eq!(conditions[0].name, "Charmed");
+
+        let err = registry
+            .build_conditions("file", &settings())
+            .err()
+            .unwrap();
+        assert_eq!(err, ConfigurationError::NoConditions("file".to_string()));
+        assert_eq!(
+            err.to_string(),
+            "--expand needs the graphql datasource, the file datasource has no conditions"
+        );
+    }
+}
@@ -1,33 +1,212 @@
+use crate::datasources::common::fixtures::{FixtureMode, Fixtures};
+use crate::datasources::common::remote_datasource::ClientOptions;
 use config::{Config, ConfigError, File, FileFormat};
 use serde::Deserialize;
 
 use std::env;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
+#[serde(default)]
 pub struct SpellApi {
     pub url: String,
+    /// Milliseconds to wait for a connection
+    pub connect_timeout: u64,
+    /// Milliseconds a whole request may take
+    pub timeout: u64,
+    /// Retries after network errors, 429 and 5xx responses
+    pub retries: u32,
+    /// Milliseconds before the first retry, doubled for each further retry
+    pub retry_backoff: u64,
+    pub user_agent: String,
+    /// `record` captures GraphQL responses into `fixture_path`, `replay` answers from them
+    pub fixture_mode: Option<FixtureMode>,
+    pub fixture_path: String,
+}
+
+impl Default for SpellApi {
+    fn default() -> Self {
+        let options = ClientOptions::default();
+        Self {
+            url: "https://www.dnd5eapi.co/graphql".to_string(),
+            connect_timeout: options.connect_timeout.as_millis() as u64,
+            timeout: options.timeout.as_millis() as u64,
+            retries: options.retries,
+            retry_backoff: options.retry_backoff.as_millis() as u64,
+            user_agent: options.user_agent,
+            fixture_mode: None,
+            fixture_path: ".dnd/fixtures".to_string(),
+        }
+    }
+}
+
+impl SpellApi {
+    pub fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            connect_timeout: Duration::from_millis(self.connect_timeout),
+            timeout: Duration::from_millis(self.timeout),
+            retries: self.retries,
+            retry_backoff: Duration::from_millis(self.retry_backoff),
+            user_agent: self.user_agent.clone(),
+            fixtures: self
+                .fixture_mode
+                .map(|mode| Fixtures::new(mode, &self.fixture_path)),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 pub struct SpellDatasource {
+    /// `graphql` or `rest` for the API at `spell_api.url`, `file` for a local 5e-database folder
+    /// or `sqlite` for spells imported into `spell_database_settings.path`
     pub remote_type: String,
+    /// Folder with `5e-SRD-Spells.json` for the `file` datasource
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Milliseconds the remote spells are kept in memory, 0 disables caching
     pub cache_time: u64,
+    /// Spell detail requests in flight at once for the `rest` datasource
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+}
+
+fn default_max_concurrent_requests() -> usize {
+    8
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 pub struct SpellSettings {
     pub spell_api: SpellApi,
     pub spell_datasource: SpellDatasource,
+    /// Homebrew spell files (YAML or JSON) merged on top of the remote spells
+    #[serde(default)]
+    pub homebrew: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellCacheSettings {
+    /// Persist fetched spells to `path` for offline use
+    pub enabled: bool,
+    pub path: String,
+    /// Seconds before persisted spells are refetched when online
+    pub max_age: u64,
+    /// Only read persisted spells, never the network
+    #[serde(default)]
+    pub offline: bool,
+}
+
+impl Default for SpellCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: ".dnd/cache/spells.json".to_string(),
+            max_age: 86400,
+            offline: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellbookSettings {
+    pub path: String,
+}
+
+impl Default for SpellbookSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/spellbooks.yml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellSlotsSettings {
+    pub path: String,
+}
+
+impl Default for SpellSlotsSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/slots.yml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellDeckSettings {
+    pub path: String,
+}
+
+impl Default for SpellDeckSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/decks.yml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellAnnotationSettings {
+    pub path: String,
+}
+
+impl Default for SpellAnnotationSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/annotations.yml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CasterSettings {
+    pub path: String,
+}
+
+impl Default for CasterSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/casters.yml".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpellDatabaseSettings {
+    /// SQLite database read by the `sqlite` datasource
+    pub path: String,
+    /// Datasource `dnd data import` reads from, the configured datasource when unset
+    #[serde(default)]
+    pub import_from: Option<String>,
+}
+
+impl Default for SpellDatabaseSettings {
+    fn default() -> Self {
+        Self {
+            path: ".dnd/spells.db".to_string(),
+            import_from: None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
-#[allow(dead_code)]
 pub struct Settings {
     pub debug: bool,
     pub spell_settings: SpellSettings,
+    #[serde(default)]
+    pub spell_cache_settings: SpellCacheSettings,
+    #[serde(default)]
+    pub spellbook_settings: SpellbookSettings,
+    #[serde(default)]
+    pub spell_slots_settings: SpellSlotsSettings,
+    #[serde(default)]
+    pub spell_deck_settings: SpellDeckSettings,
+    #[serde(default)]
+    pub spell_annotation_settings: SpellAnnotationSettings,
+    #[serde(default)]
+    pub caster_settings: CasterSettings,
+    #[serde(default)]
+    pub spell_database_settings: SpellDatabaseSettings,
 }
 
 impl Settings {
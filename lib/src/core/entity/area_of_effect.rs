@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// Feet covered by one grid square
+pub const FEET_PER_SQUARE: f64 = 5.0;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AreaShapeError(String);
+
+impl Display for AreaShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "'{}' is not an area shape, expected cone, cube, cylinder, line or sphere",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for AreaShapeError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AreaShape {
+    Cone,
+    Cube,
+    Cylinder,
+    Line,
+    Sphere,
+}
+
+impl AreaShape {
+    /// Whether the shape extends from its point of origin towards a target
+    pub fn is_directed(&self) -> bool {
+        matches!(self, AreaShape::Cone | AreaShape::Cube | AreaShape::Line)
+    }
+}
+
+impl FromStr for AreaShape {
+    type Err = AreaShapeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "cone" => Ok(AreaShape::Cone),
+            "cube" => Ok(AreaShape::Cube),
+            "cylinder" => Ok(AreaShape::Cylinder),
+            "line" => Ok(AreaShape::Line),
+            "sphere" => Ok(AreaShape::Sphere),
+            _ => Err(AreaShapeError(s.to_string())),
+        }
+    }
+}
+
+impl Display for AreaShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            AreaShape::Cone => write!(f, "cone"),
+            AreaShape::Cube => write!(f, "cube"),
+            AreaShape::Cylinder => write!(f, "cylinder"),
+            AreaShape::Line => write!(f, "line"),
+            AreaShape::Sphere => write!(f, "sphere"),
+        }
+    }
+}
+
+/// Shape and size in feet of a spell's area, the size is the radius of spheres and cylinders,
+/// the length of cones and lines and the side of cubes
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AreaOfEffect {
+    #[serde(rename = "type")]
+    pub shape: AreaShape,
+    pub size: u32,
+}
+
+impl AreaOfEffect {
+    /// Whether `point` lies in the area placed at `origin`, pointing along the unit vector
+    /// `direction` for directed shapes (all in squares)
+    pub fn contains(&self, origin: (f64, f64), direction: (f64, f64), point: (f64, f64)) -> bool {
+        const EPSILON: f64 = 1e-9;
+        let size = self.size as f64 / FEET_PER_SQUARE;
+        let (dx, dy) = (point.0 - origin.0, point.1 - origin.1);
+        // Distance along and across the direction
+        let along = dx * direction.0 + dy * direction.1;
+        let across = (dy * direction.0 - dx * direction.1).abs();
+        match self.shape {
+            AreaShape::Sphere | AreaShape::Cylinder => (dx * dx + dy * dy).sqrt() <= size + EPSILON,
+            // The width of a cone at any point equals its distance from the origin
+            AreaShape::Cone => {
+                along >= -EPSILON && along <= size + EPSILON && across <= along / 2.0 + EPSILON
+            }
+            // The origin lies at the center of the cube's face
+            AreaShape::Cube => {
+                along >= -EPSILON && along <= size + EPSILON && across <= size / 2.0 + EPSILON
+            }
+            // Lines are one square wide
+            AreaShape::Line => {
+                along >= -EPSILON && along <= size + EPSILON && across <= 0.5 + EPSILON
+            }
+        }
+    }
+}
+
+impl Display for AreaOfEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.shape {
+            AreaShape::Sphere | AreaShape::Cylinder => {
+                write!(f, "{}-foot-radius {}", self.size, self.shape)
+            }
+            _ => write!(f, "{}-foot {}", self.size, self.shape),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CoverageRuleError(String);
+
+impl Display for CoverageRuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "'{}' is not a coverage rule, expected half or touch",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for CoverageRuleError {}
+
+/// When a grid square counts as inside an area
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoverageRule {
+    /// The area covers at least half of the square (DMG grid rule)
+    #[default]
+    Half,
+    /// The area touches any part of the square (template rule)
+    Touch,
+}
+
+impl FromStr for CoverageRule {
+    type Err = CoverageRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "half" => Ok(CoverageRule::Half),
+            "touch" => Ok(CoverageRule::Touch),
+            _ => Err(CoverageRuleError(s.to_string())),
+        }
+    }
+}
+
+impl Display for CoverageRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CoverageRule::Half => write!(f, "half"),
+            CoverageRule::Touch => write!(f, "touch"),
+        }
+    }
+}
+
+/// A point on the grid or the name of a token on the map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MapTarget {
+    /// Grid intersection, `[0, 0]` is the top-left corner of the top-left square
+    Point([f64; 2]),
+    Token(String),
+}
+
+/// A creature occupying `size` x `size` squares from the square `at`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapToken {
+    pub name: String,
+    pub at: [i32; 2],
+    #[serde(default = "MapToken::default_size")]
+    pub size: i32,
+}
+
+impl MapToken {
+    fn default_size() -> i32 {
+        1
+    }
+
+    pub fn occupies(&self, square: (i32, i32)) -> bool {
+        let size = self.size.max(1);
+        (self.at[0]..self.at[0] + size).contains(&square.0)
+            && (self.at[1]..self.at[1] + size).contains(&square.1)
+    }
+
+    pub fn center(&self) -> (f64, f64) {
+        let half = self.size.max(1) as f64 / 2.0;
+        (self.at[0] as f64 + half, self.at[1] as f64 + half)
+    }
+}
+
+/// Encounter map with the point of origin of an area and the target it points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AoeMap {
+    pub origin: MapTarget,
+    #[serde(default)]
+    pub target: Option<MapTarget>,
+    #[serde(default)]
+    pub tokens: Vec<MapToken>,
+}
+
+/// Squares and tokens covered by an area placed on a map
+#[derive(Debug, Clone, PartialEq)]
+pub struct AoeCoverage {
+    pub area: AreaOfEffect,
+    pub rule: CoverageRule,
+    /// Point of origin in squares
+    pub origin: (f64, f64),
+    /// Covered squares, sorted by row then column
+    pub squares: Vec<(i32, i32)>,
+    /// Tokens on the map and whether the area covers them
+    pub tokens: Vec<(MapToken, bool)>,
+}
+
+impl AoeCoverage {
+    pub fn is_covered(&self, square: (i32, i32)) -> bool {
+        self.squares
+            .binary_search_by_key(&(square.1, square.0), |&(x, y)| (y, x))
+            .is_ok()
+    }
+
+    pub fn affected(&self) -> Vec<&MapToken> {
+        self.tokens
+            .iter()
+            .filter(|(_, hit)| *hit)
+            .map(|(token, _)| token)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cone_width_equals_distance() {
+        let cone = AreaOfEffect {
+            shape: AreaShape::Cone,
+            size: 15,
+        };
+        let origin = (0.0, 0.0);
+        let east = (1.0, 0.0);
+        assert!(cone.contains(origin, east, (3.0, 1.5)));
+        assert!(!cone.contains(origin, east, (3.0, 1.6)));
+        assert!(!cone.contains(origin, east, (3.1, 0.0)));
+        assert!(!cone.contains(origin, east, (-0.5, 0.0)));
+    }
+
+    #[test]
+    fn test_area_shape_from_str() {
+        assert_eq!("Sphere".parse::<AreaShape>(), Ok(AreaShape::Sphere));
+        assert!("circle".parse::<AreaShape>().is_err());
+        let area = AreaOfEffect {
+            shape: AreaShape::Sphere,
+            size: 20,
+        };
+        assert_eq!(area.to_string(), "20-foot-radius sphere");
+    }
+}
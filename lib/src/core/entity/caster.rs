@@ -0,0 +1,186 @@
+use crate::core::entity::spell::Spell;
+use serde::{Deserialize, Serialize};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+pub const MAX_CHARACTER_LEVEL: u8 = 20;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AbilityError(String);
+
+impl Display for AbilityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "'{}' is not an ability, expected str, dex, con, int, wis or cha",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for AbilityError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ability {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl FromStr for Ability {
+    type Err = AbilityError;
+
+    /// Parses full names and the API's three letter indexes, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "str" | "strength" => Ok(Ability::Strength),
+            "dex" | "dexterity" => Ok(Ability::Dexterity),
+            "con" | "constitution" => Ok(Ability::Constitution),
+            "int" | "intelligence" => Ok(Ability::Intelligence),
+            "wis" | "wisdom" => Ok(Ability::Wisdom),
+            "cha" | "charisma" => Ok(Ability::Charisma),
+            _ => Err(AbilityError(s.to_string())),
+        }
+    }
+}
+
+impl Display for Ability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Ability::Strength => write!(f, "Strength"),
+            Ability::Dexterity => write!(f, "Dexterity"),
+            Ability::Constitution => write!(f, "Constitution"),
+            Ability::Intelligence => write!(f, "Intelligence"),
+            Ability::Wisdom => write!(f, "Wisdom"),
+            Ability::Charisma => write!(f, "Charisma"),
+        }
+    }
+}
+
+/// Ability modifier of an ability score, e.g. +3 for 16 and -1 for 9
+pub fn ability_modifier(score: u8) -> i16 {
+    (score as i16 - 10).div_euclid(2)
+}
+
+/// Proficiency bonus of a character level, +2 at level 1 up to +6 at level 17
+pub fn proficiency_bonus_for_level(level: u8) -> u8 {
+    2 + (level.clamp(1, MAX_CHARACTER_LEVEL) - 1) / 4
+}
+
+/// Spellcasting numbers of a character
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CasterProfile {
+    pub character: String,
+    /// Spellcasting ability, e.g. Intelligence for wizards
+    pub ability: Ability,
+    pub ability_score: u8,
+    pub proficiency_bonus: u8,
+    /// Bonus of magic items to the spell save DC, e.g. +1 from a Rod of the Pact Keeper
+    #[serde(default)]
+    pub save_dc_bonus: i16,
+    /// Bonus of magic items to spell attack rolls, e.g. +1 from a Wand of the War Mage
+    #[serde(default)]
+    pub attack_bonus: i16,
+}
+
+impl CasterProfile {
+    pub fn modifier(&self) -> i16 {
+        ability_modifier(self.ability_score)
+    }
+
+    /// 8 + proficiency bonus + spellcasting ability modifier + item bonuses
+    pub fn spell_save_dc(&self) -> i16 {
+        8 + self.proficiency_bonus as i16 + self.modifier() + self.save_dc_bonus
+    }
+
+    /// Proficiency bonus + spellcasting ability modifier + item bonuses
+    pub fn spell_attack_bonus(&self) -> i16 {
+        self.proficiency_bonus as i16 + self.modifier() + self.attack_bonus
+    }
+
+    /// Save DC and to-hit bonus of the profile for the spell, each only when the spell uses it
+    pub fn stats_for(&self, spell: &Spell) -> SpellCasterStats {
+        SpellCasterStats {
+            save_dc: spell.save.as_ref().map(|_| self.spell_save_dc()),
+            attack_bonus: spell
+                .attack_type
+                .as_ref()
+                .map(|_| self.spell_attack_bonus()),
+        }
+    }
+}
+
+/// Concrete numbers of a spell cast by a character
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpellCasterStats {
+    pub save_dc: Option<i16>,
+    pub attack_bonus: Option<i16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellSave;
+
+    fn wizard() -> CasterProfile {
+        CasterProfile {
+            character: "Elminster".to_string(),
+            ability: Ability::Intelligence,
+            ability_score: 17,
+            proficiency_bonus: proficiency_bonus_for_level(5),
+            save_dc_bonus: 1,
+            attack_bonus: 0,
+        }
+    }
+
+    #[test]
+    fn test_ability_modifier_and_proficiency() {
+        assert_eq!(ability_modifier(1), -5);
+        assert_eq!(ability_modifier(9), -1);
+        assert_eq!(ability_modifier(10), 0);
+        assert_eq!(ability_modifier(17), 3);
+        assert_eq!(ability_modifier(20), 5);
+        assert_eq!(proficiency_bonus_for_level(1), 2);
+        assert_eq!(proficiency_bonus_for_level(5), 3);
+        assert_eq!(proficiency_bonus_for_level(17), 6);
+        assert_eq!(proficiency_bonus_for_level(30), 6);
+        assert_eq!("DEX".parse::<Ability>(), Ok(Ability::Dexterity));
+        assert!("luck".parse::<Ability>().is_err());
+    }
+
+    #[test]
+    fn test_stats_for_spell() {
+        let profile = wizard();
+        let fireball = Spell {
+            save: Some(SpellSave {
+                ability: Ability::Dexterity,
+                success: Some("half".to_string()),
+            }),
+            ..Default::default()
+        };
+        let ray = Spell {
+            attack_type: Some("ranged".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            profile.stats_for(&fireball),
+            SpellCasterStats {
+                save_dc: Some(15),
+                attack_bonus: None
+            }
+        );
+        assert_eq!(
+            profile.stats_for(&ray),
+            SpellCasterStats {
+                save_dc: None,
+                attack_bonus: Some(6)
+            }
+        );
+        assert_eq!(profile.stats_for(&Spell::default()), Default::default());
+    }
+}
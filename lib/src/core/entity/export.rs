@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExportFormatError(String);
+
+impl Display for ExportFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "'{}' is not an export format, expected markdown, html, csv or json",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ExportFormatError {}
+
+/// File format a list of spells can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Markdown with YAML front matter
+    Markdown,
+    /// Standalone HTML page
+    Html,
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            _ => Err(ExportFormatError(s.to_string())),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ExportFormat::Markdown => write!(f, "markdown"),
+            ExportFormat::Html => write!(f, "html"),
+            ExportFormat::Csv => write!(f, "csv"),
+            ExportFormat::Json => write!(f, "json"),
+        }
+    }
+}
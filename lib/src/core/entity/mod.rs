@@ -1,2 +1,14 @@
+pub mod area_of_effect;
+pub mod caster;
 pub mod dice_set;
+pub mod export;
+pub mod reference;
+pub mod rich_text;
 pub mod spell;
+pub mod spell_annotation;
+pub mod spell_card;
+pub mod spell_cast;
+pub mod spell_comparison;
+pub mod spell_deck;
+pub mod spell_slots;
+pub mod spellbook;
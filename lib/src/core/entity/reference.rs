@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferenceKind {
+    Condition,
+    Spell,
+}
+
+impl Display for ReferenceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ReferenceKind::Condition => write!(f, "condition"),
+            ReferenceKind::Spell => write!(f, "spell"),
+        }
+    }
+}
+
+/// A condition or spell that can be mentioned in a spell description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    pub index: String,
+    pub name: String,
+    pub desc: Vec<String>,
+}
@@ -0,0 +1,201 @@
+/// Inline emphasis of a text span
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Emphasis {
+    pub bold: bool,
+    pub italic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub emphasis: Emphasis,
+}
+
+/// A block of a Markdown spell description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Paragraph(Vec<Span>),
+    ListItem(Vec<Span>),
+    /// Cells have their emphasis markers removed
+    Table {
+        header: Option<Vec<String>>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// Splits `text` at `*`, `**` and `***` emphasis markers
+///
+/// A marker surrounded by whitespace is kept as text, as in `2 * 3`.
+pub fn parse_inline(text: &str) -> Vec<Span> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut spans = Vec::new();
+    let mut emphasis = Emphasis::default();
+    let mut current = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '*' {
+            current.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && chars[i] == '*' {
+            i += 1;
+        }
+        let before = start.checked_sub(1).map(|j| chars[j]);
+        let after = chars.get(i).copied();
+        let is_boundary = |c: Option<char>| match c {
+            Some(c) => c.is_whitespace(),
+            None => true,
+        };
+        if is_boundary(before) && is_boundary(after) {
+            current.extend(&chars[start..i]);
+            continue;
+        }
+        if !current.is_empty() {
+            spans.push(Span {
+                text: std::mem::take(&mut current),
+                emphasis,
+            });
+        }
+        match i - start {
+            1 => emphasis.italic = !emphasis.italic,
+            2 => emphasis.bold = !emphasis.bold,
+            _ => {
+                emphasis.bold = !emphasis.bold;
+                emphasis.italic = !emphasis.italic;
+            }
+        }
+    }
+    if !current.is_empty() {
+        spans.push(Span {
+            text: current,
+            emphasis,
+        });
+    }
+    spans
+}
+
+fn plain(text: &str) -> String {
+    parse_inline(text)
+        .into_iter()
+        .map(|span| span.text)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+    line.split('|').map(plain).collect()
+}
+
+fn is_separator_row(line: &str) -> bool {
+    line.contains('-')
+        && line
+            .chars()
+            .all(|c| matches!(c, '|' | '-' | ':') || c.is_whitespace())
+}
+
+fn table_from_lines(lines: &[&str]) -> Block {
+    let has_header = lines.len() > 1 && is_separator_row(lines[1]);
+    let header = match has_header {
+        true => Some(table_cells(lines[0])),
+        false => None,
+    };
+    let rows = lines
+        .iter()
+        .skip(if has_header { 2 } else { 0 })
+        .filter(|line| !is_separator_row(line))
+        .map(|line| table_cells(line))
+        .collect();
+    Block::Table { header, rows }
+}
+
+/// Parses description paragraphs into paragraphs, list items and tables
+pub fn parse_description(desc: &[String]) -> Vec<Block> {
+    let lines = desc
+        .iter()
+        .flat_map(|paragraph| paragraph.lines())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with('|') {
+            let start = i;
+            while i < lines.len() && lines[i].starts_with('|') {
+                i += 1;
+            }
+            blocks.push(table_from_lines(&lines[start..i]));
+            continue;
+        }
+        match line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            Some(item) => blocks.push(Block::ListItem(parse_inline(item))),
+            None => blocks.push(Block::Paragraph(parse_inline(line))),
+        }
+        i += 1;
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, bold: bool, italic: bool) -> Span {
+        Span {
+            text: text.to_string(),
+            emphasis: Emphasis { bold, italic },
+        }
+    }
+
+    #[test]
+    fn test_parse_inline() {
+        assert_eq!(
+            parse_inline("***Damage.*** Roll *twice*, take **2 * 3**"),
+            vec![
+                span("Damage.", true, true),
+                span(" Roll ", false, false),
+                span("twice", false, true),
+                span(", take ", false, false),
+                span("2 * 3", true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_description_with_table() {
+        let desc = vec![
+            "Roll a d10 each turn.".to_string(),
+            "| d10 | Behavior |".to_string(),
+            "|---|---|".to_string(),
+            "| 1 | The creature **moves** in a random direction. |".to_string(),
+            "| 2-6 | The creature doesn't move. |".to_string(),
+            "- It can't take reactions.".to_string(),
+        ];
+        let blocks = parse_description(&desc);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(
+            blocks[1],
+            Block::Table {
+                header: Some(vec!["d10".to_string(), "Behavior".to_string()]),
+                rows: vec![
+                    vec![
+                        "1".to_string(),
+                        "The creature moves in a random direction.".to_string()
+                    ],
+                    vec!["2-6".to_string(), "The creature doesn't move.".to_string()],
+                ],
+            }
+        );
+        assert_eq!(
+            blocks[2],
+            Block::ListItem(vec![span("It can't take reactions.", false, false)])
+        );
+    }
+}
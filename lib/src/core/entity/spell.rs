@@ -1,8 +1,430 @@
-// use std::fmt::{Display, Error, Formatter};
-#[derive(Debug, Clone)]
+use crate::core::entity::area_of_effect::AreaOfEffect;
+use crate::core::entity::caster::Ability;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+pub const MAX_SPELL_LEVEL: u8 = 9;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SpellLevelError {
+    NotAWholeNumber(String),
+    OutOfRange(String),
+    InvalidRange(String),
+}
+
+impl Display for SpellLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SpellLevelError::NotAWholeNumber(value) => write!(
+                f,
+                "'{}' is not a spell level, expected a whole number from 0 (cantrip) to {}",
+                value, MAX_SPELL_LEVEL
+            ),
+            SpellLevelError::OutOfRange(value) => write!(
+                f,
+                "spell level '{}' is out of range, expected 0 (cantrip) to {}",
+                value, MAX_SPELL_LEVEL
+            ),
+            SpellLevelError::InvalidRange(value) => write!(
+                f,
+                "'{}' is not a valid level range, expected <MIN>..<MAX> with MIN <= MAX (e.g. 1..3)",
+                value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpellLevelError {}
+
+/// Level of a spell, where level 0 is a cantrip
+#[derive(
+    Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct SpellLevel(u8);
+
+impl SpellLevel {
+    pub const CANTRIP: SpellLevel = SpellLevel(0);
+    pub const MAX: SpellLevel = SpellLevel(MAX_SPELL_LEVEL);
+
+    pub fn new(level: u8) -> Result<Self, SpellLevelError> {
+        match level {
+            l if l <= MAX_SPELL_LEVEL => Ok(SpellLevel(l)),
+            l => Err(SpellLevelError::OutOfRange(l.to_string())),
+        }
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_cantrip(&self) -> bool {
+        *self == SpellLevel::CANTRIP
+    }
+}
+
+impl TryFrom<u8> for SpellLevel {
+    type Error = SpellLevelError;
+
+    fn try_from(level: u8) -> Result<Self, Self::Error> {
+        SpellLevel::new(level)
+    }
+}
+
+impl From<SpellLevel> for u8 {
+    fn from(level: SpellLevel) -> Self {
+        level.0
+    }
+}
+
+impl TryFrom<f64> for SpellLevel {
+    type Error = SpellLevelError;
+
+    fn try_from(level: f64) -> Result<Self, Self::Error> {
+        if level.fract() != 0.0 || level.is_nan() {
+            return Err(SpellLevelError::NotAWholeNumber(level.to_string()));
+        }
+        if level < 0.0 || level > MAX_SPELL_LEVEL as f64 {
+            return Err(SpellLevelError::OutOfRange(level.to_string()));
+        }
+        Ok(SpellLevel(level as u8))
+    }
+}
+
+impl FromStr for SpellLevel {
+    type Err = SpellLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("cantrip") {
+            return Ok(SpellLevel::CANTRIP);
+        }
+        match s.parse::<i64>() {
+            Ok(level) if (0..=MAX_SPELL_LEVEL as i64).contains(&level) => {
+                Ok(SpellLevel(level as u8))
+            }
+            Ok(_) => Err(SpellLevelError::OutOfRange(s.to_string())),
+            Err(_) => Err(SpellLevelError::NotAWholeNumber(s.to_string())),
+        }
+    }
+}
+
+impl Display for SpellLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Inclusive range of spell levels
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct SpellLevelRange {
+    pub min: SpellLevel,
+    pub max: SpellLevel,
+}
+
+impl SpellLevelRange {
+    pub fn new(min: SpellLevel, max: SpellLevel) -> Result<Self, SpellLevelError> {
+        match min <= max {
+            true => Ok(SpellLevelRange { min, max }),
+            false => Err(SpellLevelError::InvalidRange(format!("{}..{}", min, max))),
+        }
+    }
+
+    pub fn exact(level: SpellLevel) -> Self {
+        SpellLevelRange {
+            min: level,
+            max: level,
+        }
+    }
+
+    pub fn up_to(level: SpellLevel) -> Self {
+        SpellLevelRange {
+            min: SpellLevel::CANTRIP,
+            max: level,
+        }
+    }
+
+    pub fn cantrips() -> Self {
+        SpellLevelRange::exact(SpellLevel::CANTRIP)
+    }
+
+    pub fn contains(&self, level: SpellLevel) -> bool {
+        self.min <= level && level <= self.max
+    }
+}
+
+impl FromStr for SpellLevelRange {
+    type Err = SpellLevelError;
+
+    /// Parses `MIN..MAX`, `..MAX` or `MIN..` (inclusive); a single level is an exact range
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().split_once("..") {
+            Some((min, max)) => {
+                let min = match min.trim() {
+                    "" => SpellLevel::CANTRIP,
+                    min => min.parse::<SpellLevel>()?,
+                };
+                let max = match max.trim().trim_start_matches('=') {
+                    "" => SpellLevel::MAX,
+                    max => max.parse::<SpellLevel>()?,
+                };
+                SpellLevelRange::new(min, max)
+                    .map_err(|_| SpellLevelError::InvalidRange(s.to_string()))
+            }
+            None => Ok(SpellLevelRange::exact(s.parse::<SpellLevel>()?)),
+        }
+    }
+}
+
+impl Display for SpellLevelRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}..{}", self.min, self.max)
+    }
+}
+
+/// Damage dice of a spell, by slot level for leveled spells and by character level for cantrips
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpellDamage {
+    pub damage_type: Option<String>,
+    pub damage_at_slot_level: BTreeMap<SpellLevel, String>,
+    pub damage_at_character_level: BTreeMap<u8, String>,
+}
+
+/// Saving throw a spell asks its targets for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellSave {
+    pub ability: Ability,
+    /// Effect of a successful save, e.g. `half` or `none`
+    #[serde(default)]
+    pub success: Option<String>,
+}
+
+/// Where a spell comes from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpellSource {
+    #[default]
+    Srd,
+    Homebrew,
+}
+
+impl Display for SpellSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SpellSource::Srd => write!(f, "SRD"),
+            SpellSource::Homebrew => write!(f, "Homebrew"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Spell {
+    #[serde(default)]
+    pub index: String,
     pub name: String,
-    pub level: f64,
+    pub level: SpellLevel,
+    #[serde(default)]
     pub desc: Vec<String>,
+    #[serde(default)]
     pub classes: Vec<String>,
+    /// Subclasses (e.g. Life, Devotion) that always have the spell prepared
+    #[serde(default)]
+    pub subclasses: Vec<String>,
+    #[serde(default)]
+    pub school: Option<String>,
+    #[serde(default)]
+    pub range: Option<String>,
+    /// Components as `V`, `S` and `M`
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub duration: Option<String>,
+    #[serde(default)]
+    pub area_of_effect: Option<AreaOfEffect>,
+    #[serde(default)]
+    pub save: Option<SpellSave>,
+    /// `melee` or `ranged` for spells that need a spell attack roll
+    #[serde(default)]
+    pub attack_type: Option<String>,
+    #[serde(default)]
+    pub damage: Option<SpellDamage>,
+    #[serde(default)]
+    pub heal_at_slot_level: BTreeMap<SpellLevel, String>,
+    #[serde(default)]
+    pub source: SpellSource,
+}
+
+/// SRD subclasses with spell lists, as index, title and class index
+const SRD_SUBCLASSES: [(&str, &str, &str); 5] = [
+    ("devotion", "Oath of Devotion", "paladin"),
+    ("fiend", "The Fiend", "warlock"),
+    ("land", "Circle of the Land", "druid"),
+    ("life", "Life Domain", "cleric"),
+    ("lore", "College of Lore", "bard"),
+];
+
+impl Spell {
+    /// Subclasses of the spell matching any of `subclasses` by name, index or SRD title, like
+    /// `Life`, `life` or `Life Domain`
+    ///
+    /// With `classes`, SRD subclasses of other classes are left out.
+    pub fn matching_subclasses(&self, classes: &[String], subclasses: &[String]) -> Vec<String> {
+        let filters = subclasses
+            .iter()
+            .map(|subclass| subclass_index(subclass))
+            .collect::<Vec<String>>();
+        let classes = classes
+            .iter()
+            .map(|class| index_from_name(class))
+            .collect::<Vec<String>>();
+        self.subclasses
+            .iter()
+            .filter(|subclass| {
+                let index = subclass_index(subclass);
+                let in_class = match SRD_SUBCLASSES.iter().find(|(i, ..)| *i == index) {
+                    Some((_, _, class)) => classes.is_empty() || classes.iter().any(|c| c == class),
+                    None => true,
+                };
+                in_class && filters.contains(&index)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Index of a subclass from its name, index or SRD title, e.g. `Oath of Devotion` becomes
+/// `devotion`
+pub fn subclass_index(name: &str) -> String {
+    let index = index_from_name(name);
+    match SRD_SUBCLASSES
+        .iter()
+        .find(|(_, title, _)| index_from_name(title) == index)
+    {
+        Some((srd_index, ..)) => srd_index.to_string(),
+        None => index,
+    }
+}
+
+/// Builds a spell index from its name, e.g. `Acid Arrow` becomes `acid-arrow`
+pub fn index_from_name(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_level_from_str() {
+        assert_eq!("0".parse::<SpellLevel>(), Ok(SpellLevel::CANTRIP));
+        assert_eq!("cantrip".parse::<SpellLevel>(), Ok(SpellLevel::CANTRIP));
+        assert_eq!("9".parse::<SpellLevel>(), Ok(SpellLevel::MAX));
+        assert_eq!(
+            "2.5".parse::<SpellLevel>(),
+            Err(SpellLevelError::NotAWholeNumber("2.5".to_string()))
+        );
+        assert_eq!(
+            "10".parse::<SpellLevel>(),
+            Err(SpellLevelError::OutOfRange("10".to_string()))
+        );
+        assert_eq!(
+            "-1".parse::<SpellLevel>(),
+            Err(SpellLevelError::OutOfRange("-1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_spell_level_try_from_f64() {
+        assert_eq!(SpellLevel::try_from(3.0), Ok(SpellLevel(3)));
+        assert!(SpellLevel::try_from(2.5).is_err());
+        assert!(SpellLevel::try_from(-1.0).is_err());
+        assert!(SpellLevel::try_from(10.0).is_err());
+    }
+
+    #[test]
+    fn test_spell_level_range_from_str() {
+        let one = SpellLevel::new(1).unwrap();
+        let three = SpellLevel::new(3).unwrap();
+        assert_eq!(
+            "1..3".parse::<SpellLevelRange>(),
+            Ok(SpellLevelRange::new(one, three).unwrap())
+        );
+        assert_eq!(
+            "1..=3".parse::<SpellLevelRange>(),
+            Ok(SpellLevelRange::new(one, three).unwrap())
+        );
+        assert_eq!(
+            "..3".parse::<SpellLevelRange>(),
+            Ok(SpellLevelRange::up_to(three))
+        );
+        assert_eq!(
+            "3..".parse::<SpellLevelRange>(),
+            Ok(SpellLevelRange::new(three, SpellLevel::MAX).unwrap())
+        );
+        assert_eq!(
+            "3".parse::<SpellLevelRange>(),
+            Ok(SpellLevelRange::exact(three))
+        );
+        assert_eq!(
+            "3..1".parse::<SpellLevelRange>(),
+            Err(SpellLevelError::InvalidRange("3..1".to_string()))
+        );
+        assert!("1..2.5".parse::<SpellLevelRange>().is_err());
+    }
+
+    #[test]
+    fn test_spell_level_range_contains() {
+        let range = "1..3".parse::<SpellLevelRange>().unwrap();
+        assert!(!range.contains(SpellLevel::CANTRIP));
+        assert!(range.contains(SpellLevel::new(1).unwrap()));
+        assert!(range.contains(SpellLevel::new(3).unwrap()));
+        assert!(!range.contains(SpellLevel::new(4).unwrap()));
+        assert!(SpellLevelRange::cantrips().contains(SpellLevel::CANTRIP));
+    }
+
+    #[test]
+    fn test_matching_subclasses() {
+        let spell = Spell {
+            subclasses: vec!["Life".to_string(), "Devotion".to_string()],
+            ..Default::default()
+        };
+        let matching = |classes: &[&str], filter: &str| {
+            let classes = classes
+                .iter()
+                .map(|class| class.to_string())
+                .collect::<Vec<String>>();
+            spell.matching_subclasses(&classes, &[filter.to_string()])
+        };
+        assert_eq!(matching(&[], "life"), vec!["Life"]);
+        assert_eq!(matching(&["cleric"], "Life Domain"), vec!["Life"]);
+        assert_eq!(matching(&["Paladin"], "oath-of-devotion"), vec!["Devotion"]);
+        assert!(matching(&["wizard"], "life").is_empty());
+        assert!(matching(&[], "of").is_empty());
+        assert!(matching(&[], "domain").is_empty());
+        assert!(matching(&[], "land").is_empty());
+    }
+
+    #[test]
+    fn test_subclass_index() {
+        assert_eq!(subclass_index("Oath of Devotion"), "devotion");
+        assert_eq!(subclass_index(" life "), "life");
+        assert_eq!(subclass_index("Circle of Spores"), "circle-of-spores");
+    }
+
+    #[test]
+    fn test_index_from_name() {
+        assert_eq!(index_from_name("Acid Arrow"), "acid-arrow");
+        assert_eq!(
+            index_from_name("Tasha's Hideous Laughter"),
+            "tasha-s-hideous-laughter"
+        );
+        assert_eq!(index_from_name("  Fire   Bolt "), "fire-bolt");
+    }
 }
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+/// Notes, tags and favourite flag a user attached to a spell
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellAnnotation {
+    /// Index of the annotated spell
+    pub index: String,
+    #[serde(default)]
+    pub notes: Vec<String>,
+    /// Lowercase tags, e.g. `aoe` or `ritual`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favourite: bool,
+}
+
+impl SpellAnnotation {
+    pub fn new(index: String) -> Self {
+        SpellAnnotation {
+            index,
+            ..Default::default()
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.tags.is_empty() && !self.favourite
+    }
+
+    pub fn apply(&mut self, update: &SpellAnnotationUpdate) {
+        if update.clear_notes {
+            self.notes.clear();
+        }
+        self.notes.extend(update.notes.iter().cloned());
+        for tag in &update.tags {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && !self.has_tag(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        self.tags.retain(|tag| {
+            !update
+                .untags
+                .iter()
+                .any(|u| u.trim().eq_ignore_ascii_case(tag))
+        });
+        if let Some(favourite) = update.favourite {
+            self.favourite = favourite;
+        }
+    }
+}
+
+/// Changes to apply to a spell's annotation
+#[derive(Debug, Clone, Default)]
+pub struct SpellAnnotationUpdate {
+    pub notes: Vec<String>,
+    /// Removes the existing notes before adding `notes`
+    pub clear_notes: bool,
+    pub tags: Vec<String>,
+    pub untags: Vec<String>,
+    pub favourite: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_update() {
+        let mut annotation = SpellAnnotation::new("fireball".to_string());
+        annotation.apply(&SpellAnnotationUpdate {
+            notes: vec!["Mind the party".to_string()],
+            tags: vec!["AoE".to_string(), "aoe".to_string(), "fire".to_string()],
+            favourite: Some(true),
+            ..Default::default()
+        });
+        assert_eq!(annotation.tags, vec!["aoe", "fire"]);
+        assert!(annotation.has_tag("AOE"));
+        assert!(annotation.favourite);
+
+        annotation.apply(&SpellAnnotationUpdate {
+            clear_notes: true,
+            untags: vec!["Fire".to_string()],
+            favourite: Some(false),
+            ..Default::default()
+        });
+        assert!(annotation.notes.is_empty());
+        assert_eq!(annotation.tags, vec!["aoe"]);
+        assert!(!annotation.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::core::entity::spell::{SpellLevel, SpellSource};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// Poker card width (2.5in) in CSS pixels
+pub const CARD_WIDTH: f32 = 240.0;
+/// Poker card height (3.5in) in CSS pixels
+pub const CARD_HEIGHT: f32 = 336.0;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CardFormatError(String);
+
+impl Display for CardFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "'{}' is not a card format, expected svg or html", self.0)
+    }
+}
+
+impl std::error::Error for CardFormatError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardFormat {
+    /// A single SVG sheet with all cards
+    Svg,
+    /// Print-ready HTML with a page break after every sheet of cards
+    Html,
+}
+
+impl FromStr for CardFormat {
+    type Err = CardFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "svg" => Ok(CardFormat::Svg),
+            "html" | "htm" => Ok(CardFormat::Html),
+            _ => Err(CardFormatError(s.to_string())),
+        }
+    }
+}
+
+impl Display for CardFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            CardFormat::Svg => write!(f, "svg"),
+            CardFormat::Html => write!(f, "html"),
+        }
+    }
+}
+
+/// One card of a spell, long descriptions continue on further cards
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellCard {
+    pub index: String,
+    pub name: String,
+    pub level: SpellLevel,
+    pub classes: Vec<String>,
+    pub source: SpellSource,
+    /// Wrapped description lines, an empty line separates paragraphs
+    pub lines: Vec<String>,
+    pub font_size: f32,
+    /// Number of this card (starting at 1) out of `parts` cards of the spell
+    pub part: usize,
+    pub parts: usize,
+}
+
+impl SpellCard {
+    pub fn title(&self) -> String {
+        match self.part {
+            1 => self.name.clone(),
+            _ => format!("{} (cont.)", self.name),
+        }
+    }
+
+    pub fn subtitle(&self) -> String {
+        let level = match self.level.is_cantrip() {
+            true => "Cantrip".to_string(),
+            false => format!("Level {}", self.level),
+        };
+        match self.classes.is_empty() {
+            true => level,
+            false => format!("{} · {}", level, self.classes.join(", ")),
+        }
+    }
+
+    pub fn footer(&self) -> String {
+        match self.parts {
+            1 => self.source.to_string(),
+            _ => format!("{} · {}/{}", self.source, self.part, self.parts),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::core::entity::dice_set::DiceSet;
+use crate::core::entity::spell::SpellLevel;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CastEffect {
+    Damage(Option<String>),
+    Healing,
+}
+
+/// Rolled effect of casting a spell at a given slot or character level
+#[derive(Debug, Clone)]
+pub struct SpellCast {
+    pub spell_name: String,
+    pub slot: Option<SpellLevel>,
+    pub caster_level: Option<u8>,
+    pub effect: CastEffect,
+    pub expression: String,
+    pub dice_set: DiceSet,
+    pub bonus: i64,
+}
+
+impl SpellCast {
+    /// Rolled dice plus the bonus, at least 0
+    pub fn total(&self) -> i64 {
+        (self.dice_set.result as i64 + self.bonus).max(0)
+    }
+}
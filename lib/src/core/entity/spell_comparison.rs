@@ -0,0 +1,28 @@
+use crate::core::entity::spell::{Spell, SpellLevel};
+
+use std::collections::BTreeMap;
+
+/// A compared spell with its average damage (or healing) per slot level
+#[derive(Debug, Clone)]
+pub struct SpellComparisonColumn {
+    pub spell: Spell,
+    /// Averages for leveled spells, from the spell's level up to level 9
+    pub average_at_slot_level: BTreeMap<SpellLevel, f64>,
+    /// Average of a cantrip at the compared caster level
+    pub cantrip_average: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SpellComparison {
+    pub caster_level: u8,
+    pub modifier: i64,
+    pub columns: Vec<SpellComparisonColumn>,
+}
+
+impl SpellComparison {
+    pub fn has_cantrips(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| column.spell.level.is_cantrip())
+    }
+}
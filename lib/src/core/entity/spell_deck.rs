@@ -0,0 +1,146 @@
+use crate::core::entity::spell::{Spell, SpellLevel, SpellLevelError};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+/// Named deck remembering which spells were drawn from it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellDeck {
+    pub name: String,
+    /// Indexes of the drawn spells
+    #[serde(default)]
+    pub drawn: Vec<String>,
+}
+
+impl SpellDeck {
+    pub fn new(name: String) -> Self {
+        SpellDeck {
+            name,
+            drawn: vec![],
+        }
+    }
+
+    pub fn is_drawn(&self, spell: &Spell) -> bool {
+        self.drawn.contains(&spell.index)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SpellWeightingError {
+    UnknownKind(String),
+    InvalidEntry(String),
+    InvalidLevel(SpellLevelError),
+}
+
+impl Display for SpellWeightingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            SpellWeightingError::UnknownKind(value) => write!(
+                f,
+                "'{}' is not a weighting, expected level:<LEVEL>=<WEIGHT>,... or school:<SCHOOL>=<WEIGHT>,...",
+                value
+            ),
+            SpellWeightingError::InvalidEntry(value) => write!(
+                f,
+                "'{}' is not a weight, expected <KEY>=<WEIGHT> with a non-negative weight",
+                value
+            ),
+            SpellWeightingError::InvalidLevel(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SpellWeightingError {}
+
+/// Relative odds of drawing a spell, spells without a listed weight have weight 1
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SpellWeighting {
+    #[default]
+    Uniform,
+    ByLevel(BTreeMap<SpellLevel, f64>),
+    /// Weights by lowercase school name
+    BySchool(BTreeMap<String, f64>),
+}
+
+impl SpellWeighting {
+    pub fn weight(&self, spell: &Spell) -> f64 {
+        match self {
+            SpellWeighting::Uniform => 1.0,
+            SpellWeighting::ByLevel(weights) => *weights.get(&spell.level).unwrap_or(&1.0),
+            SpellWeighting::BySchool(weights) => spell
+                .school
+                .as_ref()
+                .and_then(|school| weights.get(&school.to_lowercase()))
+                .copied()
+                .unwrap_or(1.0),
+        }
+    }
+
+    fn parse_entries(value: &str) -> Result<Vec<(String, f64)>, SpellWeightingError> {
+        value
+            .split(',')
+            .map(|entry| match entry.split_once('=') {
+                Some((key, weight)) => match weight.trim().parse::<f64>() {
+                    Ok(weight) if weight >= 0.0 && weight.is_finite() => {
+                        Ok((key.trim().to_lowercase(), weight))
+                    }
+                    _ => Err(SpellWeightingError::InvalidEntry(entry.to_string())),
+                },
+                None => Err(SpellWeightingError::InvalidEntry(entry.to_string())),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for SpellWeighting {
+    type Err = SpellWeightingError;
+
+    /// Parses e.g. `level:1=5,2=3,3=1` or `school:evocation=2,necromancy=0`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().split_once(':') {
+            Some((kind, entries)) if kind.trim().eq_ignore_ascii_case("level") => {
+                let weights = Self::parse_entries(entries)?
+                    .into_iter()
+                    .map(|(level, weight)| {
+                        level
+                            .parse::<SpellLevel>()
+                            .map(|level| (level, weight))
+                            .map_err(SpellWeightingError::InvalidLevel)
+                    })
+                    .collect::<Result<BTreeMap<SpellLevel, f64>, SpellWeightingError>>()?;
+                Ok(SpellWeighting::ByLevel(weights))
+            }
+            Some((kind, entries)) if kind.trim().eq_ignore_ascii_case("school") => Ok(
+                SpellWeighting::BySchool(Self::parse_entries(entries)?.into_iter().collect()),
+            ),
+            _ => Err(SpellWeightingError::UnknownKind(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_weighting_from_str() {
+        let weighting = "level:0=0,1=5".parse::<SpellWeighting>().unwrap();
+        let spell = Spell {
+            level: SpellLevel::new(1).unwrap(),
+            school: Some("Evocation".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(weighting.weight(&spell), 5.0);
+        assert_eq!(weighting.weight(&Spell::default()), 0.0);
+
+        let weighting = "school:evocation=3".parse::<SpellWeighting>().unwrap();
+        assert_eq!(weighting.weight(&spell), 3.0);
+        assert_eq!(weighting.weight(&Spell::default()), 1.0);
+
+        assert!("level:1=-2".parse::<SpellWeighting>().is_err());
+        assert!("level:10=1".parse::<SpellWeighting>().is_err());
+        assert!("rarity:common=1".parse::<SpellWeighting>().is_err());
+    }
+}
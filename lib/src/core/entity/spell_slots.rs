@@ -0,0 +1,253 @@
+use crate::core::entity::spell::{SpellLevel, MAX_SPELL_LEVEL};
+use serde::{Deserialize, Serialize};
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+pub const MAX_CHARACTER_LEVEL: u8 = 20;
+
+/// Slots per spell level 1-9 for each caster level of the (multiclass) spellcaster table
+const SPELLCASTER_TABLE: [[u8; 9]; 20] = [
+    [2, 0, 0, 0, 0, 0, 0, 0, 0],
+    [3, 0, 0, 0, 0, 0, 0, 0, 0],
+    [4, 2, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 2, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 1, 0, 0, 0, 0, 0],
+    [4, 3, 3, 2, 0, 0, 0, 0, 0],
+    [4, 3, 3, 3, 1, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 2, 1, 1],
+];
+
+/// Pact Magic slot count and slot level for each warlock level
+const PACT_MAGIC_TABLE: [(u8, u8); 20] = [
+    (1, 1),
+    (2, 1),
+    (2, 2),
+    (2, 2),
+    (2, 3),
+    (2, 3),
+    (2, 4),
+    (2, 4),
+    (2, 5),
+    (2, 5),
+    (3, 5),
+    (3, 5),
+    (3, 5),
+    (3, 5),
+    (3, 5),
+    (3, 5),
+    (4, 5),
+    (4, 5),
+    (4, 5),
+    (4, 5),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasterType {
+    Full,
+    Half,
+    Third,
+    Pact,
+    NonCaster,
+}
+
+impl CasterType {
+    /// Caster type of a class, third casters are given by their subclass
+    pub fn from_class(class: &str) -> Option<Self> {
+        match class.trim().to_lowercase().replace(' ', "-").as_str() {
+            "bard" | "cleric" | "druid" | "sorcerer" | "wizard" => Some(CasterType::Full),
+            "paladin" | "ranger" => Some(CasterType::Half),
+            "eldritch-knight" | "arcane-trickster" => Some(CasterType::Third),
+            "warlock" => Some(CasterType::Pact),
+            "barbarian" | "fighter" | "monk" | "rogue" => Some(CasterType::NonCaster),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClassLevelError {
+    InvalidFormat(String),
+    InvalidLevel(String),
+}
+
+impl Display for ClassLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ClassLevelError::InvalidFormat(value) => {
+                write!(
+                    f,
+                    "'{}' is not a class level, expected <CLASS>:<LEVEL>",
+                    value
+                )
+            }
+            ClassLevelError::InvalidLevel(value) => write!(
+                f,
+                "'{}' is not a class level, expected 1 to {}",
+                value, MAX_CHARACTER_LEVEL
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ClassLevelError {}
+
+/// Levels in a single class, e.g. `wizard:5`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassLevel {
+    pub class: String,
+    pub level: u8,
+}
+
+impl FromStr for ClassLevel {
+    type Err = ClassLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((class, level)) if !class.trim().is_empty() => match level.trim().parse::<u8>() {
+                Ok(l) if (1..=MAX_CHARACTER_LEVEL).contains(&l) => Ok(ClassLevel {
+                    class: class.trim().to_lowercase(),
+                    level: l,
+                }),
+                _ => Err(ClassLevelError::InvalidLevel(s.to_string())),
+            },
+            _ => Err(ClassLevelError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+impl Display for ClassLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}", self.class, self.level)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PactSlots {
+    pub count: u8,
+    pub level: SpellLevel,
+}
+
+/// Maximum spell slots of a character
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpellSlots {
+    pub slots: [u8; MAX_SPELL_LEVEL as usize],
+    pub pact: Option<PactSlots>,
+}
+
+impl SpellSlots {
+    pub fn for_caster_level(caster_level: u8) -> Self {
+        let slots = match caster_level {
+            0 => [0; MAX_SPELL_LEVEL as usize],
+            l => SPELLCASTER_TABLE[(l.min(MAX_CHARACTER_LEVEL) - 1) as usize],
+        };
+        SpellSlots { slots, pact: None }
+    }
+
+    pub fn pact_for_warlock_level(warlock_level: u8) -> Option<PactSlots> {
+        match warlock_level {
+            0 => None,
+            l => {
+                let (count, level) = PACT_MAGIC_TABLE[(l.min(MAX_CHARACTER_LEVEL) - 1) as usize];
+                Some(PactSlots {
+                    count,
+                    level: SpellLevel::new(level).unwrap_or(SpellLevel::CANTRIP),
+                })
+            }
+        }
+    }
+
+    pub fn at_level(&self, level: SpellLevel) -> u8 {
+        match level.value() {
+            0 => 0,
+            l => self.slots[(l - 1) as usize],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(|slots| *slots == 0) && self.pact.is_none()
+    }
+}
+
+/// Class levels and expended slots of a character, persisted between sessions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CasterSlots {
+    pub character: String,
+    pub class_levels: Vec<ClassLevel>,
+    #[serde(default)]
+    pub expended: [u8; MAX_SPELL_LEVEL as usize],
+    #[serde(default)]
+    pub pact_expended: u8,
+}
+
+impl CasterSlots {
+    pub fn new(character: String, class_levels: Vec<ClassLevel>) -> Self {
+        CasterSlots {
+            character,
+            class_levels,
+            expended: [0; MAX_SPELL_LEVEL as usize],
+            pact_expended: 0,
+        }
+    }
+
+    pub fn expended_at_level(&self, level: SpellLevel) -> u8 {
+        match level.value() {
+            0 => 0,
+            l => self.expended[(l - 1) as usize],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rest {
+    Short,
+    Long,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_class_level_from_str() {
+        assert_eq!(
+            "Wizard:5".parse::<ClassLevel>(),
+            Ok(ClassLevel {
+                class: "wizard".to_string(),
+                level: 5
+            })
+        );
+        assert!(matches!(
+            "wizard".parse::<ClassLevel>(),
+            Err(ClassLevelError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            "wizard:21".parse::<ClassLevel>(),
+            Err(ClassLevelError::InvalidLevel(_))
+        ));
+        assert!(matches!(
+            "wizard:0".parse::<ClassLevel>(),
+            Err(ClassLevelError::InvalidLevel(_))
+        ));
+    }
+
+    #[test]
+    fn test_pact_slots() {
+        let pact = SpellSlots::pact_for_warlock_level(11).unwrap();
+        assert_eq!(pact.count, 3);
+        assert_eq!(pact.level, SpellLevel::new(5).unwrap());
+        assert_eq!(SpellSlots::pact_for_warlock_level(0), None);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::core::entity::spell::SpellLevel;
+use serde::{Deserialize, Serialize};
+
+/// Known and prepared spells of a single character
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Spellbook {
+    pub character: String,
+    pub class: String,
+    pub max_spell_level: SpellLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prepared: Option<usize>,
+    #[serde(default)]
+    pub known: Vec<SpellbookEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellbookEntry {
+    pub name: String,
+    pub level: SpellLevel,
+    #[serde(default)]
+    pub prepared: bool,
+}
+
+impl Spellbook {
+    pub fn new(
+        character: String,
+        class: String,
+        max_spell_level: SpellLevel,
+        max_prepared: Option<usize>,
+    ) -> Self {
+        Spellbook {
+            character,
+            class,
+            max_spell_level,
+            max_prepared,
+            known: vec![],
+        }
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&SpellbookEntry> {
+        self.known
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Prepared leveled spells, cantrips are always prepared and don't count
+    pub fn prepared_count(&self) -> usize {
+        self.known
+            .iter()
+            .filter(|entry| entry.prepared && !entry.level.is_cantrip())
+            .count()
+    }
+}
@@ -0,0 +1,258 @@
+use crate::core::entity::area_of_effect::{
+    AoeCoverage, AoeMap, AreaOfEffect, AreaShape, CoverageRule, MapTarget, MapToken,
+    FEET_PER_SQUARE,
+};
+use crate::datasources::common::local_file::{LocalFile, LocalFileError};
+
+use std::path::Path;
+
+/// Sample points per square side used to measure how much of a square an area covers
+const SAMPLES: usize = 8;
+
+#[derive(Debug)]
+pub enum AreaOfEffectError {
+    /// Name of the spell without an area of effect
+    NoAreaOfEffect(String),
+    /// Cones, cubes and lines need a target to point to
+    MissingTarget(AreaShape),
+    UnknownToken(String),
+    FileError(LocalFileError),
+}
+
+pub trait AreaOfEffectInterface {
+    fn load_map(&self, path: &Path) -> Result<AoeMap, AreaOfEffectError>;
+
+    /// Places `area` at the map's origin, pointing at its target, and collects the covered
+    /// squares and tokens
+    fn cover(
+        &self,
+        area: &AreaOfEffect,
+        map: &AoeMap,
+        rule: CoverageRule,
+    ) -> Result<AoeCoverage, AreaOfEffectError>;
+}
+
+pub struct AreaOfEffectImplementation {}
+
+impl Default for AreaOfEffectImplementation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AreaOfEffectImplementation {
+    pub fn new() -> Self {
+        AreaOfEffectImplementation {}
+    }
+
+    fn find_token<'a>(map: &'a AoeMap, name: &str) -> Result<&'a MapToken, AreaOfEffectError> {
+        map.tokens
+            .iter()
+            .find(|token| token.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| AreaOfEffectError::UnknownToken(name.to_string()))
+    }
+
+    fn resolve(map: &AoeMap, target: &MapTarget) -> Result<(f64, f64), AreaOfEffectError> {
+        match target {
+            MapTarget::Point([x, y]) => Ok((*x, *y)),
+            MapTarget::Token(name) => Ok(Self::find_token(map, name)?.center()),
+        }
+    }
+
+    /// Unit vector from `origin` to the map's target
+    fn direction(
+        area: &AreaOfEffect,
+        map: &AoeMap,
+        origin: (f64, f64),
+    ) -> Result<(f64, f64), AreaOfEffectError> {
+        if !area.shape.is_directed() {
+            return Ok((1.0, 0.0));
+        }
+        let target = match &map.target {
+            Some(target) => Self::resolve(map, target)?,
+            None => return Err(AreaOfEffectError::MissingTarget(area.shape)),
+        };
+        let (dx, dy) = (target.0 - origin.0, target.1 - origin.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        match length > f64::EPSILON {
+            true => Ok((dx / length, dy / length)),
+            false => Err(AreaOfEffectError::MissingTarget(area.shape)),
+        }
+    }
+
+    fn is_covered(
+        area: &AreaOfEffect,
+        origin: (f64, f64),
+        direction: (f64, f64),
+        square: (i32, i32),
+        rule: CoverageRule,
+    ) -> bool {
+        let step = 1.0 / SAMPLES as f64;
+        let covered = (0..SAMPLES * SAMPLES)
+            .filter(|i| {
+                let x = square.0 as f64 + (i % SAMPLES) as f64 * step + step / 2.0;
+                let y = square.1 as f64 + (i / SAMPLES) as f64 * step + step / 2.0;
+                area.contains(origin, direction, (x, y))
+            })
+            .count();
+        match rule {
+            CoverageRule::Half => covered * 2 >= SAMPLES * SAMPLES,
+            CoverageRule::Touch => covered > 0,
+        }
+    }
+}
+
+impl AreaOfEffectInterface for AreaOfEffectImplementation {
+    fn load_map(&self, path: &Path) -> Result<AoeMap, AreaOfEffectError> {
+        LocalFile::new(path)
+            .and_then(|file| file.read::<AoeMap>())
+            .map_err(AreaOfEffectError::FileError)
+    }
+
+    fn cover(
+        &self,
+        area: &AreaOfEffect,
+        map: &AoeMap,
+        rule: CoverageRule,
+    ) -> Result<AoeCoverage, AreaOfEffectError> {
+        let origin_token = match &map.origin {
+            MapTarget::Token(name) => Some(Self::find_token(map, name)?),
+            MapTarget::Point(_) => None,
+        };
+        let mut origin = Self::resolve(map, &map.origin)?;
+        let direction = Self::direction(area, map, origin)?;
+        // A directed area cast by a creature starts at the edge of its space
+        if let (Some(token), true) = (origin_token, area.shape.is_directed()) {
+            let half = token.size.max(1) as f64 / 2.0;
+            let exit = half / direction.0.abs().max(direction.1.abs());
+            origin = (origin.0 + direction.0 * exit, origin.1 + direction.1 * exit);
+        }
+
+        let reach = (area.size as f64 / FEET_PER_SQUARE * 1.5).ceil() as i32 + 1;
+        let (ox, oy) = (origin.0.floor() as i32, origin.1.floor() as i32);
+        let mut squares = Vec::new();
+        for y in oy - reach..=oy + reach {
+            for x in ox - reach..=ox + reach {
+                if Self::is_covered(area, origin, direction, (x, y), rule) {
+                    squares.push((x, y));
+                }
+            }
+        }
+        let tokens = map
+            .tokens
+            .iter()
+            .map(|token| {
+                let is_caster = area.shape.is_directed()
+                    && origin_token.is_some_and(|caster| caster.name == token.name);
+                let hit = !is_caster && squares.iter().any(|square| token.occupies(*square));
+                (token.clone(), hit)
+            })
+            .collect();
+
+        Ok(AoeCoverage {
+            area: *area,
+            rule,
+            origin,
+            squares,
+            tokens,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(name: &str, x: i32, y: i32) -> MapToken {
+        MapToken {
+            name: name.to_string(),
+            at: [x, y],
+            size: 1,
+        }
+    }
+
+    fn area(shape: AreaShape, size: u32) -> AreaOfEffect {
+        AreaOfEffect { shape, size }
+    }
+
+    fn affected(coverage: &AoeCoverage) -> Vec<String> {
+        coverage
+            .affected()
+            .into_iter()
+            .map(|token| token.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_cone_from_caster() {
+        let map = AoeMap {
+            origin: MapTarget::Token("Wizard".to_string()),
+            target: Some(MapTarget::Token("Goblin".to_string())),
+            tokens: vec![
+                token("Wizard", 0, 5),
+                token("Goblin", 3, 5),
+                token("Orc", 3, 6),
+                token("Ogre", 3, 7),
+                token("Rogue", 0, 6),
+            ],
+        };
+        let usecase = AreaOfEffectImplementation::new();
+        let coverage = usecase
+            .cover(&area(AreaShape::Cone, 15), &map, CoverageRule::Half)
+            .unwrap();
+        assert_eq!(coverage.origin, (1.0, 5.5));
+        assert!(coverage.is_covered((1, 5)));
+        assert!(!coverage.is_covered((2, 6)));
+        assert!(!coverage.is_covered((4, 5)));
+        assert_eq!(affected(&coverage), vec!["Goblin", "Orc"]);
+
+        let coverage = usecase
+            .cover(&area(AreaShape::Cone, 15), &map, CoverageRule::Touch)
+            .unwrap();
+        assert!(coverage.is_covered((2, 6)));
+        assert!(!coverage.is_covered((1, 6)));
+        assert_eq!(affected(&coverage), vec!["Goblin", "Orc"]);
+    }
+
+    #[test]
+    fn test_sphere_at_intersection() {
+        let map = AoeMap {
+            origin: MapTarget::Point([10.0, 10.0]),
+            target: None,
+            tokens: vec![token("Large", 12, 12), token("Far", 14, 10)],
+        };
+        let coverage = AreaOfEffectImplementation::new()
+            .cover(&area(AreaShape::Sphere, 20), &map, CoverageRule::Half)
+            .unwrap();
+        // Four squares to each side of the origin along the axes
+        assert!(coverage.is_covered((6, 9)));
+        assert!(coverage.is_covered((13, 10)));
+        assert!(!coverage.is_covered((14, 10)));
+        assert_eq!(affected(&coverage), vec!["Large"]);
+    }
+
+    #[test]
+    fn test_directed_area_needs_target() {
+        let map = AoeMap {
+            origin: MapTarget::Point([0.0, 0.0]),
+            target: None,
+            tokens: vec![],
+        };
+        let usecase = AreaOfEffectImplementation::new();
+        let err = usecase
+            .cover(&area(AreaShape::Line, 100), &map, CoverageRule::Half)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AreaOfEffectError::MissingTarget(AreaShape::Line)
+        ));
+        let map = AoeMap {
+            origin: MapTarget::Token("Nobody".to_string()),
+            ..map
+        };
+        let err = usecase
+            .cover(&area(AreaShape::Sphere, 10), &map, CoverageRule::Half)
+            .unwrap_err();
+        assert!(matches!(err, AreaOfEffectError::UnknownToken(_)));
+    }
+}
@@ -0,0 +1,452 @@
+use crate::core::entity::dice_set::DiceSet;
+use crate::core::entity::spell::{Spell, SpellLevel, MAX_SPELL_LEVEL};
+use crate::core::entity::spell_cast::{CastEffect, SpellCast};
+use crate::core::entity::spell_comparison::{SpellComparison, SpellComparisonColumn};
+use crate::core::entity::spell_slots::MAX_CHARACTER_LEVEL;
+use crate::core::usecase::dice::{DiceError, DiceInterface};
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CastError {
+    SlotBelowSpellLevel(SpellLevel, SpellLevel),
+    /// Cantrips scale with the caster level, not with a slot
+    SlotForCantrip(String),
+    InvalidCasterLevel(u8),
+    NoDamageOrHealing(String),
+    NoEntryForLevel(String, u8),
+    UnsupportedExpression(String),
+    Dice(DiceError),
+}
+
+pub trait CastInterface {
+    fn cast(
+        &self,
+        spell: &Spell,
+        slot: Option<SpellLevel>,
+        caster_level: Option<u8>,
+        modifier: i64,
+    ) -> Result<SpellCast, CastError>;
+
+    /// Average damage or healing of each spell at every slot level it can be cast with
+    fn compare(
+        &self,
+        spells: &[Spell],
+        caster_level: Option<u8>,
+        modifier: i64,
+    ) -> Result<SpellComparison, CastError>;
+}
+
+/// Entry of the highest level in `table` up to `level`
+fn expression_at_level<K: Ord + Copy>(table: &BTreeMap<K, String>, level: K) -> Option<String> {
+    table
+        .range(..=level)
+        .next_back()
+        .map(|(_, expression)| expression.clone())
+}
+
+/// Damage expression of a cantrip at `caster_level`, character level 1 by default
+pub fn resolve_cantrip(
+    spell: &Spell,
+    caster_level: Option<u8>,
+) -> Result<(CastEffect, u8, String), CastError> {
+    let caster_level = caster_level.unwrap_or(1);
+    if caster_level == 0 || caster_level > MAX_CHARACTER_LEVEL {
+        return Err(CastError::InvalidCasterLevel(caster_level));
+    }
+    let damage = match &spell.damage {
+        Some(damage) if !damage.damage_at_character_level.is_empty() => damage,
+        _ => return Err(CastError::NoDamageOrHealing(spell.name.clone())),
+    };
+    match expression_at_level(&damage.damage_at_character_level, caster_level) {
+        Some(expression) => Ok((
+            CastEffect::Damage(damage.damage_type.clone()),
+            caster_level,
+            expression,
+        )),
+        None => Err(CastError::NoEntryForLevel(spell.name.clone(), caster_level)),
+    }
+}
+
+/// Damage or else healing expression of a spell at `slot`, the spell's level by default, from
+/// the table entry of the highest level up to it
+pub fn resolve_slot(
+    spell: &Spell,
+    slot: Option<SpellLevel>,
+) -> Result<(CastEffect, SpellLevel, String), CastError> {
+    let slot = slot.unwrap_or(spell.level);
+    if slot < spell.level {
+        return Err(CastError::SlotBelowSpellLevel(spell.level, slot));
+    }
+    let (effect, table) = match &spell.damage {
+        Some(damage) if !damage.damage_at_slot_level.is_empty() => (
+            CastEffect::Damage(damage.damage_type.clone()),
+            &damage.damage_at_slot_level,
+        ),
+        _ if !spell.heal_at_slot_level.is_empty() => {
+            (CastEffect::Healing, &spell.heal_at_slot_level)
+        }
+        _ => return Err(CastError::NoDamageOrHealing(spell.name.clone())),
+    };
+    match expression_at_level(table, slot) {
+        Some(expression) => Ok((effect, slot, expression)),
+        None => Err(CastError::NoEntryForLevel(spell.name.clone(), slot.value())),
+    }
+}
+
+pub struct CastImplementation<D>
+where
+    D: DiceInterface,
+{
+    dice_usecase: D,
+}
+
+impl<D> CastImplementation<D>
+where
+    D: DiceInterface,
+{
+    pub fn new(dice_usecase: D) -> Self {
+        CastImplementation { dice_usecase }
+    }
+
+    /// Splits e.g. `1d8 + MOD` into dice strings and a flat bonus, the modifier may be negative
+    fn parse_expression(
+        &self,
+        expression: &str,
+        modifier: i64,
+    ) -> Result<(Vec<String>, i64), CastError> {
+        let normalized = expression
+            .split_whitespace()
+            .collect::<String>()
+            .to_lowercase();
+        let mut dice = Vec::new();
+        let mut bonus = 0;
+        for term in normalized.split('+') {
+            let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+            if term == "mod" {
+                bonus += modifier;
+                continue;
+            }
+            match term.split_once('d') {
+                Some((count, face)) if is_number(count) && is_number(face) => {
+                    dice.push(term.to_string())
+                }
+                None if is_number(term) => bonus += term.parse::<i64>().unwrap_or(0),
+                _ => return Err(CastError::UnsupportedExpression(expression.to_string())),
+            }
+        }
+        Ok((dice, bonus))
+    }
+
+    /// Expected value of an expression, `NdF` averages to `N * (F + 1) / 2`
+    fn average_expression(&self, expression: &str, modifier: i64) -> Result<f64, CastError> {
+        let (dice, bonus) = self.parse_expression(expression, modifier)?;
+        let average = dice
+            .iter()
+            .filter_map(|die| die.split_once('d'))
+            .map(|(count, face)| {
+                count.parse::<f64>().unwrap_or(0.0) * (face.parse::<f64>().unwrap_or(0.0) + 1.0)
+                    / 2.0
+            })
+            .sum::<f64>();
+        Ok(average + bonus as f64)
+    }
+
+    fn compare_spell(
+        &self,
+        spell: &Spell,
+        caster_level: u8,
+        modifier: i64,
+    ) -> Result<SpellComparisonColumn, CastError> {
+        let mut column = SpellComparisonColumn {
+            spell: spell.clone(),
+            average_at_slot_level: BTreeMap::new(),
+            cantrip_average: None,
+        };
+        let result = match spell.level.is_cantrip() {
+            true => resolve_cantrip(spell, Some(caster_level))
+                .and_then(|(_, _, expression)| self.average_expression(&expression, modifier))
+                .map(|average| column.cantrip_average = Some(average)),
+            false => (spell.level.value()..=MAX_SPELL_LEVEL)
+                .filter_map(|slot| SpellLevel::new(slot).ok())
+                .try_for_each(|slot| {
+                    let (_, slot, expression) = resolve_slot(spell, Some(slot))?;
+                    let average = self.average_expression(&expression, modifier)?;
+                    column.average_at_slot_level.insert(slot, average);
+                    Ok(())
+                }),
+        };
+        match result {
+            Ok(()) | Err(CastError::NoDamageOrHealing(_)) | Err(CastError::NoEntryForLevel(..)) => {
+                Ok(column)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn roll_expression(
+        &self,
+        expression: &str,
+        modifier: i64,
+    ) -> Result<(DiceSet, i64), CastError> {
+        let (dice, bonus) = self.parse_expression(expression, modifier)?;
+        let dice_set = match dice.is_empty() {
+            true => DiceSet::new(&[]),
+            false => self.dice_usecase.roll(dice).map_err(CastError::Dice)?,
+        };
+        Ok((dice_set, bonus))
+    }
+}
+
+impl<D> CastInterface for CastImplementation<D>
+where
+    D: DiceInterface,
+{
+    fn cast(
+        &self,
+        spell: &Spell,
+        slot: Option<SpellLevel>,
+        caster_level: Option<u8>,
+        modifier: i64,
+    ) -> Result<SpellCast, CastError> {
+        let (effect, slot, caster_level, expression) = match spell.level.is_cantrip() {
+            true if slot.is_some() => return Err(CastError::SlotForCantrip(spell.name.clone())),
+            true => {
+                let (effect, caster_level, expression) = resolve_cantrip(spell, caster_level)?;
+                (effect, None, Some(caster_level), expression)
+            }
+            false => {
+                let (effect, slot, expression) = resolve_slot(spell, slot)?;
+                (effect, Some(slot), None, expression)
+            }
+        };
+        let (dice_set, bonus) = self.roll_expression(&expression, modifier)?;
+        Ok(SpellCast {
+            spell_name: spell.name.clone(),
+            slot,
+            caster_level,
+            effect,
+            expression,
+            dice_set,
+            bonus,
+        })
+    }
+
+    fn compare(
+        &self,
+        spells: &[Spell],
+        caster_level: Option<u8>,
+        modifier: i64,
+    ) -> Result<SpellComparison, CastError> {
+        let caster_level = caster_level.unwrap_or(1);
+        if caster_level == 0 || caster_level > MAX_CHARACTER_LEVEL {
+            return Err(CastError::InvalidCasterLevel(caster_level));
+        }
+        let columns = spells
+            .iter()
+            .map(|spell| self.compare_spell(spell, caster_level, modifier))
+            .collect::<Result<Vec<SpellComparisonColumn>, CastError>>()?;
+        Ok(SpellComparison {
+            caster_level,
+            modifier,
+            columns,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellDamage;
+    use crate::core::usecase::dice::DiceImplementation;
+
+    fn level(level: u8) -> SpellLevel {
+        SpellLevel::new(level).unwrap()
+    }
+
+    fn spell(name: &str, spell_level: u8) -> Spell {
+        Spell {
+            name: name.to_string(),
+            level: level(spell_level),
+            ..Default::default()
+        }
+    }
+
+    fn fireball() -> Spell {
+        Spell {
+            damage: Some(SpellDamage {
+                damage_type: Some("Fire".to_string()),
+                damage_at_slot_level: (3..=9)
+                    .map(|slot| (level(slot), format!("{}d6", slot + 5)))
+                    .collect(),
+                damage_at_character_level: BTreeMap::new(),
+            }),
+            ..spell("Fireball", 3)
+        }
+    }
+
+    fn cure_wounds() -> Spell {
+        Spell {
+            heal_at_slot_level: (1..=9)
+                .map(|slot| (level(slot), format!("{}d8 + MOD", slot)))
+                .collect(),
+            ..spell("Cure Wounds", 1)
+        }
+    }
+
+    fn fire_bolt() -> Spell {
+        Spell {
+            damage: Some(SpellDamage {
+                damage_type: Some("Fire".to_string()),
+                damage_at_slot_level: BTreeMap::new(),
+                damage_at_character_level: [(1, "1d10"), (5, "2d10"), (11, "3d10"), (17, "4d10")]
+                    .into_iter()
+                    .map(|(level, expression)| (level, expression.to_string()))
+                    .collect(),
+            }),
+            ..spell("Fire Bolt", 0)
+        }
+    }
+
+    fn usecase() -> CastImplementation<DiceImplementation> {
+        CastImplementation::new(DiceImplementation::new())
+    }
+
+    #[test]
+    fn test_cast_upcast_damage() {
+        let cast = usecase()
+            .cast(&fireball(), Some(level(5)), None, 0)
+            .unwrap();
+        assert_eq!(cast.expression, "10d6");
+        assert_eq!(cast.slot, Some(level(5)));
+        assert_eq!(cast.effect, CastEffect::Damage(Some("Fire".to_string())));
+        assert!(cast.total() >= 10 && cast.total() <= 60);
+    }
+
+    #[test]
+    fn test_cast_defaults_to_spell_level() {
+        let cast = usecase().cast(&fireball(), None, None, 0).unwrap();
+        assert_eq!(cast.expression, "8d6");
+        assert_eq!(cast.slot, Some(level(3)));
+    }
+
+    #[test]
+    fn test_cast_slot_below_spell_level_fails() {
+        let err = usecase()
+            .cast(&fireball(), Some(level(2)), None, 0)
+            .unwrap_err();
+        assert_eq!(err, CastError::SlotBelowSpellLevel(level(3), level(2)));
+    }
+
+    #[test]
+    fn test_cast_healing_with_modifier() {
+        let cast = usecase()
+            .cast(&cure_wounds(), Some(level(2)), None, 3)
+            .unwrap();
+        assert_eq!(cast.effect, CastEffect::Healing);
+        assert_eq!(cast.expression, "2d8 + MOD");
+        assert_eq!(cast.bonus, 3);
+        assert!(cast.total() >= 5 && cast.total() <= 19);
+    }
+
+    #[test]
+    fn test_cast_cantrip_tier() {
+        let cast = usecase().cast(&fire_bolt(), None, Some(11), 0).unwrap();
+        assert_eq!(cast.expression, "3d10");
+        assert_eq!(cast.caster_level, Some(11));
+        let cast = usecase().cast(&fire_bolt(), None, None, 0).unwrap();
+        assert_eq!(cast.expression, "1d10");
+    }
+
+    #[test]
+    fn test_cast_with_negative_modifier() {
+        let cast = usecase().cast(&cure_wounds(), None, None, -1).unwrap();
+        assert_eq!(cast.bonus, -1);
+        assert!(cast.total() >= 0 && cast.total() <= 7);
+    }
+
+    #[test]
+    fn test_cast_cantrip_with_slot_fails() {
+        let err = usecase()
+            .cast(&fire_bolt(), Some(level(1)), None, 0)
+            .unwrap_err();
+        assert_eq!(err, CastError::SlotForCantrip("Fire Bolt".to_string()));
+    }
+
+    #[test]
+    fn test_cast_cantrip_invalid_caster_level_fails() {
+        let err = usecase().cast(&fire_bolt(), None, Some(21), 0).unwrap_err();
+        assert_eq!(err, CastError::InvalidCasterLevel(21));
+    }
+
+    #[test]
+    fn test_cast_without_effect_fails() {
+        let err = usecase()
+            .cast(&spell("Shield", 1), None, None, 0)
+            .unwrap_err();
+        assert_eq!(err, CastError::NoDamageOrHealing("Shield".to_string()));
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let usecase = usecase();
+        assert_eq!(
+            usecase.parse_expression("2d8 + 1d6", 0).unwrap(),
+            (vec!["2d8".to_string(), "1d6".to_string()], 0)
+        );
+        assert_eq!(usecase.parse_expression("70", 0).unwrap(), (vec![], 70));
+        assert_eq!(
+            usecase.parse_expression("1d4 + 1 + MOD", 2).unwrap(),
+            (vec!["1d4".to_string()], 3)
+        );
+        assert_eq!(
+            usecase.parse_expression("1d4 - 1", 0).unwrap_err(),
+            CastError::UnsupportedExpression("1d4 - 1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_average_expression() {
+        let usecase = usecase();
+        assert_eq!(usecase.average_expression("8d6", 0).unwrap(), 28.0);
+        assert_eq!(usecase.average_expression("1d8 + MOD", 3).unwrap(), 7.5);
+        assert_eq!(usecase.average_expression("70", 0).unwrap(), 70.0);
+        assert_eq!(usecase.average_expression("1d8 + MOD", -1).unwrap(), 3.5);
+    }
+
+    #[test]
+    fn test_resolve_slot_uses_highest_entry_below_slot() {
+        let mut spell = fireball();
+        if let Some(damage) = spell.damage.as_mut() {
+            damage
+                .damage_at_slot_level
+                .retain(|slot, _| slot.value() % 2 == 1);
+        }
+        let (effect, slot, expression) = resolve_slot(&spell, Some(level(4))).unwrap();
+        assert_eq!(effect, CastEffect::Damage(Some("Fire".to_string())));
+        assert_eq!(slot, level(4));
+        assert_eq!(expression, "8d6");
+    }
+
+    #[test]
+    fn test_compare() {
+        let spells = [fireball(), cure_wounds(), fire_bolt(), spell("Shield", 1)];
+        let comparison = usecase().compare(&spells, Some(5), 2).unwrap();
+        assert_eq!(comparison.caster_level, 5);
+        assert!(comparison.has_cantrips());
+        let fireball = &comparison.columns[0];
+        assert_eq!(fireball.average_at_slot_level.len(), 7);
+        assert_eq!(fireball.average_at_slot_level.get(&level(3)), Some(&28.0));
+        assert_eq!(fireball.average_at_slot_level.get(&level(9)), Some(&49.0));
+        let cure_wounds = &comparison.columns[1];
+        assert_eq!(cure_wounds.average_at_slot_level.get(&level(1)), Some(&6.5));
+        assert_eq!(comparison.columns[2].cantrip_average, Some(11.0));
+        assert!(comparison.columns[3].average_at_slot_level.is_empty());
+    }
+
+    #[test]
+    fn test_compare_invalid_caster_level_fails() {
+        let err = usecase().compare(&[fireball()], Some(0), 0).unwrap_err();
+        assert_eq!(err, CastError::InvalidCasterLevel(0));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::core::entity::caster::CasterProfile;
+use crate::datasources::common::local_file::LocalFileError;
+
+#[derive(Debug)]
+pub enum CasterDataSourceError {
+    FileError(LocalFileError),
+}
+
+pub trait CasterDataSourceInterface {
+    fn get_all_casters(&self) -> Result<Vec<CasterProfile>, CasterDataSourceError>;
+    fn save_all_casters(&self, casters: &[CasterProfile]) -> Result<(), CasterDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum CasterError {
+    UnknownCharacter(String),
+    DataSourceError(CasterDataSourceError),
+}
+
+pub trait CasterInterface {
+    /// Stores the profile, replacing any profile of the same character
+    fn set_caster(&mut self, profile: CasterProfile) -> Result<CasterProfile, CasterError>;
+
+    fn get_caster(&self, character: &str) -> Result<CasterProfile, CasterError>;
+}
+
+pub struct CasterImplementation<T>
+where
+    T: CasterDataSourceInterface,
+{
+    datasource: T,
+}
+
+impl<T> CasterImplementation<T>
+where
+    T: CasterDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        CasterImplementation { datasource }
+    }
+
+    fn load(&self) -> Result<Vec<CasterProfile>, CasterError> {
+        self.datasource
+            .get_all_casters()
+            .map_err(CasterError::DataSourceError)
+    }
+}
+
+impl<T> CasterInterface for CasterImplementation<T>
+where
+    T: CasterDataSourceInterface,
+{
+    fn set_caster(&mut self, profile: CasterProfile) -> Result<CasterProfile, CasterError> {
+        let mut casters = self.load()?;
+        casters.retain(|caster| {
+            !caster
+                .character
+                .eq_ignore_ascii_case(profile.character.trim())
+        });
+        casters.push(profile.clone());
+        self.datasource
+            .save_all_casters(&casters)
+            .map_err(CasterError::DataSourceError)?;
+        Ok(profile)
+    }
+
+    fn get_caster(&self, character: &str) -> Result<CasterProfile, CasterError> {
+        self.load()?
+            .into_iter()
+            .find(|caster| caster.character.eq_ignore_ascii_case(character.trim()))
+            .ok_or_else(|| CasterError::UnknownCharacter(character.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::caster::Ability;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockCasterDataSource {
+        casters: RefCell<Vec<CasterProfile>>,
+    }
+
+    impl CasterDataSourceInterface for MockCasterDataSource {
+        fn get_all_casters(&self) -> Result<Vec<CasterProfile>, CasterDataSourceError> {
+            Ok(self.casters.borrow().clone())
+        }
+
+        fn save_all_casters(&self, casters: &[CasterProfile]) -> Result<(), CasterDataSourceError> {
+            *self.casters.borrow_mut() = casters.to_vec();
+            Ok(())
+        }
+    }
+
+    fn profile(character: &str, ability_score: u8) -> CasterProfile {
+        CasterProfile {
+            character: character.to_string(),
+            ability: Ability::Wisdom,
+            ability_score,
+            proficiency_bonus: 2,
+            save_dc_bonus: 0,
+            attack_bonus: 0,
+        }
+    }
+
+    #[test]
+    fn test_set_and_get_caster() {
+        let mut usecase = CasterImplementation::new(MockCasterDataSource::default());
+        usecase.set_caster(profile("Jozan", 14)).unwrap();
+        usecase.set_caster(profile("jozan", 16)).unwrap();
+        let caster = usecase.get_caster("JOZAN").unwrap();
+        assert_eq!(caster.ability_score, 16);
+        assert_eq!(usecase.load().unwrap().len(), 1);
+        let err = usecase.get_caster("Mialee").unwrap_err();
+        assert!(matches!(err, CasterError::UnknownCharacter(_)));
+    }
+}
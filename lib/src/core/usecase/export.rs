@@ -0,0 +1,296 @@
+use crate::core::entity::export::ExportFormat;
+use crate::core::entity::spell::{Spell, SpellLevel};
+use crate::core::usecase::spell::SpellFilter;
+use serde::Serialize;
+
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    Io(std::io::Error),
+}
+
+pub trait ExportInterface {
+    /// Renders the spells, `filter` is the selection they came from and titles the export
+    fn export(
+        &self,
+        spells: &[Spell],
+        filter: &SpellFilter,
+        format: ExportFormat,
+    ) -> Result<String, ExportError>;
+
+    fn export_to_file(
+        &self,
+        spells: &[Spell],
+        filter: &SpellFilter,
+        format: ExportFormat,
+        path: &Path,
+    ) -> Result<(), ExportError>;
+}
+
+/// Escapes text for HTML and SVG (XML) output
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[derive(Debug, Serialize)]
+struct FrontMatter {
+    title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    classes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    levels: Option<String>,
+    spells: usize,
+}
+
+pub struct ExportImplementation {}
+
+impl ExportImplementation {
+    pub fn new() -> Self {
+        ExportImplementation {}
+    }
+
+    fn title(filter: &SpellFilter) -> String {
+        match filter.classes.is_empty() {
+            true => "Spells".to_string(),
+            false => format!("{} spells", filter.classes.join(", ")),
+        }
+    }
+
+    fn level_label(level: SpellLevel) -> String {
+        match level.is_cantrip() {
+            true => "Cantrip".to_string(),
+            false => level.to_string(),
+        }
+    }
+
+    fn to_markdown(spells: &[Spell], filter: &SpellFilter) -> Result<String, ExportError> {
+        let front_matter = FrontMatter {
+            title: Self::title(filter),
+            classes: filter.classes.to_vec(),
+            levels: filter.level.map(|level| level.to_string()),
+            spells: spells.len(),
+        };
+        let front_matter = serde_yaml::to_string(&front_matter).map_err(ExportError::Yaml)?;
+        let mut out = format!("---\n{}---\n\n# {}\n", front_matter, Self::title(filter));
+        for spell in spells {
+            out.push_str(&format!("\n## {}\n\n", spell.name));
+            out.push_str(&format!(
+                "- **Level:** {}\n",
+                Self::level_label(spell.level)
+            ));
+            if !spell.classes.is_empty() {
+                out.push_str(&format!("- **Classes:** {}\n", spell.classes.join(", ")));
+            }
+            out.push_str(&format!("- **Source:** {}\n", spell.source));
+            for paragraph in &spell.desc {
+                out.push_str(&format!("\n{}\n", paragraph));
+            }
+        }
+        Ok(out)
+    }
+
+    fn to_html(spells: &[Spell], filter: &SpellFilter) -> String {
+        let title = escape_html(&Self::title(filter));
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        out.push_str("<meta charset=\"utf-8\">\n");
+        out.push_str(&format!("<title>{}</title>\n", title));
+        out.push_str(
+            "<style>\n\
+             body { font-family: Georgia, serif; max-width: 48em; margin: 2em auto; padding: 0 1em; }\n\
+             .spell { border-top: 1px solid #999; }\n\
+             .meta { list-style: none; padding: 0; }\n\
+             </style>\n",
+        );
+        out.push_str("</head>\n<body>\n");
+        out.push_str(&format!("<h1>{}</h1>\n", title));
+        for spell in spells {
+            out.push_str(&format!(
+                "<section class=\"spell\" id=\"{}\">\n",
+                escape_html(&spell.index)
+            ));
+            out.push_str(&format!("<h2>{}</h2>\n", escape_html(&spell.name)));
+            out.push_str("<ul class=\"meta\">\n");
+            out.push_str(&format!(
+                "<li><strong>Level:</strong> {}</li>\n",
+                Self::level_label(spell.level)
+            ));
+            if !spell.classes.is_empty() {
+                out.push_str(&format!(
+                    "<li><strong>Classes:</strong> {}</li>\n",
+                    escape_html(&spell.classes.join(", "))
+                ));
+            }
+            out.push_str(&format!(
+                "<li><strong>Source:</strong> {}</li>\n",
+                spell.source
+            ));
+            out.push_str("</ul>\n");
+            for paragraph in &spell.desc {
+                out.push_str(&format!("<p>{}</p>\n", escape_html(paragraph)));
+            }
+            out.push_str("</section>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn escape_csv(value: &str) -> String {
+        match value.contains([',', '"', '\n', '\r']) {
+            true => format!("\"{}\"", value.replace('"', "\"\"")),
+            false => value.to_string(),
+        }
+    }
+
+    fn to_csv(spells: &[Spell]) -> String {
+        let mut out = String::from("index,name,level,classes,source,description\n");
+        for spell in spells {
+            let row = [
+                spell.index.clone(),
+                spell.name.clone(),
+                spell.level.to_string(),
+                spell.classes.join("; "),
+                spell.source.to_string(),
+                spell.desc.join("\n"),
+            ]
+            .iter()
+            .map(|value| Self::escape_csv(value))
+            .collect::<Vec<String>>()
+            .join(",");
+            out.push_str(&row);
+            out.push('\n');
+        }
+        out
+    }
+
+    fn to_json(spells: &[Spell]) -> Result<String, ExportError> {
+        serde_json::to_string_pretty(spells).map_err(ExportError::Json)
+    }
+}
+
+impl Default for ExportImplementation {
+    fn default() -> Self {
+        ExportImplementation::new()
+    }
+}
+
+impl ExportInterface for ExportImplementation {
+    fn export(
+        &self,
+        spells: &[Spell],
+        filter: &SpellFilter,
+        format: ExportFormat,
+    ) -> Result<String, ExportError> {
+        match format {
+            ExportFormat::Markdown => Self::to_markdown(spells, filter),
+            ExportFormat::Html => Ok(Self::to_html(spells, filter)),
+            ExportFormat::Csv => Ok(Self::to_csv(spells)),
+            ExportFormat::Json => Self::to_json(spells),
+        }
+    }
+
+    fn export_to_file(
+        &self,
+        spells: &[Spell],
+        filter: &SpellFilter,
+        format: ExportFormat,
+        path: &Path,
+    ) -> Result<(), ExportError> {
+        let content = self.export(spells, filter, format)?;
+        std::fs::write(path, content).map_err(ExportError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellLevelRange;
+
+    fn spells() -> Vec<Spell> {
+        vec![
+            Spell {
+                index: "fire-bolt".to_string(),
+                name: "Fire Bolt".to_string(),
+                level: SpellLevel::CANTRIP,
+                desc: vec!["You hurl a mote of fire.".to_string()],
+                classes: vec!["Sorcerer".to_string(), "Wizard".to_string()],
+                ..Default::default()
+            },
+            Spell {
+                index: "shield".to_string(),
+                name: "Shield".to_string(),
+                level: SpellLevel::new(1).unwrap(),
+                desc: vec!["An invisible barrier, \"+5 to AC\" <until your turn>.".to_string()],
+                classes: vec!["Wizard".to_string()],
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn filter() -> SpellFilter {
+        SpellFilter {
+            level: Some(SpellLevelRange::up_to(SpellLevel::new(1).unwrap())),
+            classes: vec!["Wizard".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_export_markdown() {
+        let out = ExportImplementation::new()
+            .export(&spells(), &filter(), ExportFormat::Markdown)
+            .unwrap();
+        assert!(out.starts_with(
+            "---\ntitle: Wizard spells\nclasses:\n- Wizard\nlevels: 0..1\nspells: 2\n---\n"
+        ));
+        assert!(out.contains("## Fire Bolt\n\n- **Level:** Cantrip\n"));
+        assert!(out.contains("- **Classes:** Sorcerer, Wizard\n- **Source:** SRD\n"));
+    }
+
+    #[test]
+    fn test_export_html_escapes() {
+        let out = ExportImplementation::new()
+            .export(&spells(), &filter(), ExportFormat::Html)
+            .unwrap();
+        assert!(out.starts_with("<!DOCTYPE html>"));
+        assert!(out.contains("<title>Wizard spells</title>"));
+        assert!(out.contains("<section class=\"spell\" id=\"shield\">"));
+        assert!(out.contains("&quot;+5 to AC&quot; &lt;until your turn&gt;."));
+    }
+
+    #[test]
+    fn test_export_csv_quotes() {
+        let out = ExportImplementation::new()
+            .export(&spells(), &filter(), ExportFormat::Csv)
+            .unwrap();
+        let lines = out.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "index,name,level,classes,source,description");
+        assert_eq!(
+            lines[1],
+            "fire-bolt,Fire Bolt,0,Sorcerer; Wizard,SRD,You hurl a mote of fire."
+        );
+        assert_eq!(
+            lines[2],
+            "shield,Shield,1,Wizard,SRD,\"An invisible barrier, \"\"+5 to AC\"\" <until your turn>.\""
+        );
+    }
+
+    #[test]
+    fn test_export_json_round_trip() {
+        let out = ExportImplementation::new()
+            .export(&spells(), &SpellFilter::default(), ExportFormat::Json)
+            .unwrap();
+        let parsed = serde_json::from_str::<Vec<Spell>>(&out).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[1].index, "shield");
+        assert_eq!(parsed[1].level, SpellLevel::new(1).unwrap());
+    }
+}
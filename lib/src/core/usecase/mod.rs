@@ -1,2 +1,13 @@
+pub mod area_of_effect;
+pub mod cast;
+pub mod caster;
 pub mod dice;
+pub mod export;
+pub mod reference;
 pub mod spell;
+pub mod spell_annotation;
+pub mod spell_card;
+pub mod spell_draw;
+pub mod spell_import;
+pub mod spell_slots;
+pub mod spellbook;
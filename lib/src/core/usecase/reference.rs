@@ -0,0 +1,202 @@
+use crate::core::entity::reference::{Reference, ReferenceKind};
+use crate::core::entity::spell::Spell;
+use crate::datasources::common::remote_datasource::APIError;
+use async_trait::async_trait;
+
+#[derive(Debug)]
+pub enum ConditionsDataSourceError {
+    GraphQLError(APIError),
+    /// Offline without persisted conditions
+    NotCached,
+}
+
+#[async_trait(?Send)]
+pub trait ConditionsDataSourceInterface {
+    async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError>;
+}
+
+/// Lets the settings pick the datasource at runtime
+#[async_trait(?Send)]
+impl<T> ConditionsDataSourceInterface for Box<T>
+where
+    T: ConditionsDataSourceInterface + ?Sized,
+{
+    async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+        (**self).get_all_conditions().await
+    }
+}
+
+#[derive(Debug)]
+pub enum ReferenceError {
+    DataSourceError(ConditionsDataSourceError),
+}
+
+#[async_trait(?Send)]
+pub trait ReferenceInterface {
+    /// Conditions and `spells` mentioned in the description of `spell`, in order of first mention
+    async fn find_references(
+        &mut self,
+        spell: &Spell,
+        spells: &[Spell],
+    ) -> Result<Vec<Reference>, ReferenceError>;
+}
+
+pub struct ReferenceImplementation<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    datasource: T,
+    conditions: Option<Vec<Reference>>,
+}
+
+impl<T> ReferenceImplementation<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        ReferenceImplementation {
+            datasource,
+            conditions: None,
+        }
+    }
+
+    async fn get_conditions(&mut self) -> Result<Vec<Reference>, ReferenceError> {
+        if let Some(conditions) = &self.conditions {
+            return Ok(conditions.clone());
+        }
+        let conditions = self
+            .datasource
+            .get_all_conditions()
+            .await
+            .map_err(ReferenceError::DataSourceError)?;
+        self.conditions = Some(conditions.clone());
+        Ok(conditions)
+    }
+
+    fn spell_reference(spell: &Spell) -> Reference {
+        Reference {
+            kind: ReferenceKind::Spell,
+            index: spell.index.clone(),
+            name: spell.name.clone(),
+            desc: spell.desc.clone(),
+        }
+    }
+
+    /// Position of the first whole-word mention of `reference` in the lowercase `text`
+    ///
+    /// Single-word spell names (shield, light, ...) are common words, so they only count when
+    /// followed by "spell", as in "the shield spell".
+    fn find_mention(text: &str, reference: &Reference) -> Option<usize> {
+        let name = reference.name.to_lowercase();
+        let needs_suffix =
+            reference.kind == ReferenceKind::Spell && !name.contains(char::is_whitespace);
+        text.match_indices(&name).map(|(i, _)| i).find(|&i| {
+            let end = i + name.len();
+            let before = text[..i].chars().next_back();
+            let after = text[end..].chars().next();
+            let is_word = !before.is_some_and(char::is_alphanumeric)
+                && !after.is_some_and(char::is_alphanumeric);
+            is_word && (!needs_suffix || text[end..].starts_with(" spell"))
+        })
+    }
+
+    fn link(spell: &Spell, candidates: Vec<Reference>) -> Vec<Reference> {
+        let text = spell.desc.join("\n").to_lowercase();
+        let mut mentions = candidates
+            .into_iter()
+            .filter(|reference| !reference.name.is_empty() && reference.index != spell.index)
+            .filter_map(|reference| {
+                Self::find_mention(&text, &reference).map(|position| (position, reference))
+            })
+            .collect::<Vec<(usize, Reference)>>();
+        mentions.sort_by_key(|(position, _)| *position);
+        mentions
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> ReferenceInterface for ReferenceImplementation<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    async fn find_references(
+        &mut self,
+        spell: &Spell,
+        spells: &[Spell],
+    ) -> Result<Vec<Reference>, ReferenceError> {
+        let mut candidates = self.get_conditions().await?;
+        candidates.extend(spells.iter().map(Self::spell_reference));
+        Ok(Self::link(spell, candidates))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    struct MockConditionsDataSource {}
+
+    #[async_trait(?Send)]
+    impl ConditionsDataSourceInterface for MockConditionsDataSource {
+        async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+            Ok(["Frightened", "Prone", "Invisible"]
+                .iter()
+                .map(|name| Reference {
+                    kind: ReferenceKind::Condition,
+                    index: name.to_lowercase(),
+                    name: name.to_string(),
+                    desc: vec![format!("{} description", name)],
+                })
+                .collect())
+        }
+    }
+
+    fn spell(name: &str, desc: &str) -> Spell {
+        Spell {
+            index: name.to_lowercase().replace(' ', "-"),
+            name: name.to_string(),
+            desc: vec![desc.to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn names(references: Vec<Reference>) -> Vec<String> {
+        references
+            .into_iter()
+            .map(|reference| reference.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_references_in_order_of_mention() {
+        let mut usecase = ReferenceImplementation::new(MockConditionsDataSource {});
+        let spells = vec![
+            spell("Shield", "An invisible barrier of magical force."),
+            spell("Dispel Magic", "Choose one creature."),
+            spell("Fear", "Each creature must succeed or become frightened."),
+        ];
+        let spell = spell(
+            "Phantasm",
+            "The target is frightened and falls prone. This works as the shield spell, \
+             dispel magic ends it. A shield does nothing; proneness is not a condition.",
+        );
+        let references = block_on(usecase.find_references(&spell, &spells)).unwrap();
+        assert_eq!(
+            names(references),
+            vec!["Frightened", "Prone", "Shield", "Dispel Magic"]
+        );
+    }
+
+    #[test]
+    fn test_spell_does_not_reference_itself() {
+        let mut usecase = ReferenceImplementation::new(MockConditionsDataSource {});
+        let spells = vec![spell("Fear", "You project an image, the fear spell ends.")];
+        let references = block_on(usecase.find_references(&spells[0], &spells)).unwrap();
+        assert!(references.is_empty());
+    }
+}
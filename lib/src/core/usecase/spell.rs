@@ -1,18 +1,62 @@
-use crate::core::entity::spell::Spell;
+use crate::core::entity::spell::{Spell, SpellLevelRange};
+use crate::datasources::common::local_file::LocalFileError;
 use crate::datasources::common::remote_datasource::APIError;
-use crate::datasources::queries::spells_query::spells_query::SpellsQuerySpells;
 use async_trait::async_trait;
 use rand::prelude::SliceRandom;
 
 #[derive(Debug)]
 pub enum SpellsDataSourceError {
     GraphQLError(APIError),
+    /// Boxed to keep the error small, it shares `APIError` with `GraphQLError`
+    RestError(Box<APIError>),
+    HomebrewError(LocalFileError),
+    FileError(LocalFileError),
     NoSpellsFound,
+    /// Offline without cached spells
+    NotCached,
+    #[cfg(feature = "sqlite")]
+    DatabaseError(Box<rusqlite::Error>),
 }
 
 #[async_trait(?Send)]
 pub trait SpellsDataSourceInterface {
-    async fn get_all_spells(&self) -> Result<Vec<SpellsQuerySpells>, SpellsDataSourceError>;
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError>;
+
+    /// Spells that may match the filter, datasources that can query by level or class narrow
+    /// them down before `SpellImplementation` applies the filter
+    async fn get_spells_matching(
+        &self,
+        _filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        self.get_all_spells().await
+    }
+
+    /// Refreshes spells that were served stale, called once the served spells have been used
+    async fn revalidate(&self) -> Result<(), SpellsDataSourceError> {
+        Ok(())
+    }
+}
+
+/// Lets the settings pick the datasource at runtime
+#[async_trait(?Send)]
+impl<T> SpellsDataSourceInterface for Box<T>
+where
+    T: SpellsDataSourceInterface + ?Sized,
+{
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        (**self).get_all_spells().await
+    }
+
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        (**self).get_spells_matching(filter).await
+    }
+
+    async fn revalidate(&self) -> Result<(), SpellsDataSourceError> {
+        (**self).revalidate().await
+    }
 }
 
 #[derive(Debug)]
@@ -27,23 +71,31 @@ pub enum SpellError {
     DataSourceError(SpellsDataSourceError),
     LocalDataSourceError(LocalSpellsDataSourceError),
 }
+
+/// Criteria a spell has to match, empty criteria match every spell
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpellFilter {
+    pub level: Option<SpellLevelRange>,
+    pub classes: Vec<String>,
+    /// Subclasses whose always-prepared spells are included alongside the class spells
+    pub subclasses: Vec<String>,
+    /// Name or index of a single spell, case insensitive
+    pub name: Option<String>,
+}
+
 #[async_trait(?Send)]
 pub trait SpellInterface {
-    async fn get_random_spell(
-        &mut self,
-        level: Option<f64>,
-        classes: Vec<String>,
-        exact_level: bool,
-    ) -> Result<Spell, SpellError>;
+    async fn get_random_spell(&mut self, filter: SpellFilter) -> Result<Spell, SpellError>;
 
     async fn get_spell_by_name(&mut self, name: String) -> Result<Spell, SpellError>;
 
     async fn get_all_spells_with_filters(
         &mut self,
-        level: Option<f64>,
-        classes: Vec<String>,
-        exact_level: bool,
+        filter: SpellFilter,
     ) -> Result<Vec<Spell>, SpellError>;
+
+    /// Refreshes persisted spells that were served stale
+    async fn revalidate(&mut self) -> Result<(), SpellError>;
 }
 
 pub struct SpellImplementation<T>
@@ -61,116 +113,90 @@ where
         SpellImplementation { datasource }
     }
 
-    async fn get_spells_from_datasource(&self) -> Result<Vec<SpellsQuerySpells>, SpellError> {
+    async fn get_spells_from_datasource(&self) -> Result<Vec<Spell>, SpellError> {
         match self.datasource.get_all_spells().await {
             Ok(spells) => Ok(spells),
             Err(err) => Err(SpellError::DataSourceError(err)),
         }
     }
 
-    async fn get_all_spells(&mut self) -> Result<Vec<SpellsQuerySpells>, SpellError> {
+    async fn get_all_spells(&mut self) -> Result<Vec<Spell>, SpellError> {
         match self.get_spells_from_datasource().await {
             Ok(spells) => Ok(spells),
             Err(err) => Err(err),
         }
     }
 
-    fn filter_spell_for_classes(&self, spell: &SpellsQuerySpells, classes: &[String]) -> bool {
-        if classes.is_empty() {
+    async fn get_spells_matching(
+        &mut self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellError> {
+        self.datasource
+            .get_spells_matching(filter)
+            .await
+            .map_err(SpellError::DataSourceError)
+    }
+
+    fn filter_spell_for_classes(
+        &self,
+        spell: &Spell,
+        classes: &[String],
+        subclasses: &[String],
+    ) -> bool {
+        if classes.is_empty() && subclasses.is_empty() {
             return true;
         }
-        spell.classes.iter().flatten().any(|spell_class| {
-            classes.iter().any(|filter_class| -> bool {
-                match spell_class {
-                    Some(spell_class) => {
-                        spell_class.name == Some(filter_class.to_string())
-                            || spell_class.index == Some(filter_class.to_string())
-                    }
-                    None => false,
-                }
-            })
-        })
+        let in_class = spell.classes.iter().any(|spell_class| {
+            classes
+                .iter()
+                .any(|filter_class| spell_class.eq_ignore_ascii_case(filter_class.trim()))
+        });
+        in_class || !spell.matching_subclasses(classes, subclasses).is_empty()
+    }
+
+    fn filter_spell_for_name(&self, spell: &Spell, name: &Option<String>) -> bool {
+        match name {
+            Some(name) => {
+                let name = name.to_lowercase().trim().to_string();
+                spell.name.to_lowercase().trim() == name
+                    || spell.index.to_lowercase().trim() == name
+            }
+            None => true,
+        }
+    }
+
+    fn filter_spell_for_level(&self, spell: &Spell, level: &Option<SpellLevelRange>) -> bool {
+        match level {
+            Some(level) => level.contains(spell.level),
+            None => true,
+        }
     }
 
     fn filter_spells(
         &mut self,
-        spells: Vec<SpellsQuerySpells>,
-        level: Option<f64>,
-        classes: Vec<String>,
-        exact_level: bool,
-    ) -> Result<Vec<SpellsQuerySpells>, SpellError> {
-        let mut filtered_spells = match spells
+        spells: Vec<Spell>,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellError> {
+        match spells
             .into_iter()
-            .filter(|spell| self.filter_spell_for_classes(spell, &classes))
-            .collect::<Vec<SpellsQuerySpells>>()
+            .filter(|spell| {
+                self.filter_spell_for_classes(spell, &filter.classes, &filter.subclasses)
+            })
+            .filter(|spell| self.filter_spell_for_level(spell, &filter.level))
+            .filter(|spell| self.filter_spell_for_name(spell, &filter.name))
+            .collect::<Vec<Spell>>()
         {
-            f if f.is_empty() => return Err(SpellError::NoSpellsFound),
-            f => f,
-        };
-        match level {
-            Some(level) => {
-                if exact_level {
-                    filtered_spells = filtered_spells
-                        .into_iter()
-                        .filter(|spell| spell.level == level)
-                        .collect::<Vec<SpellsQuerySpells>>();
-                } else {
-                    filtered_spells = filtered_spells
-                        .into_iter()
-                        .filter(|spell| spell.level <= level)
-                        .collect::<Vec<SpellsQuerySpells>>();
-                }
-                Ok(filtered_spells)
-            }
-            None => Ok(filtered_spells),
+            f if f.is_empty() => Err(SpellError::NoSpellsFound),
+            f => Ok(f),
         }
     }
 
-    fn get_random_spell(
-        &mut self,
-        spells: Vec<SpellsQuerySpells>,
-    ) -> Result<SpellsQuerySpells, SpellError> {
+    fn get_random_spell(&mut self, spells: Vec<Spell>) -> Result<Spell, SpellError> {
         match spells.choose(&mut rand::thread_rng()) {
             Some(spell) => Ok(spell.clone()),
             None => Err(SpellError::NoSpellsFound),
         }
     }
-
-    fn spell_from_spells_query_spells(&self, spell: &SpellsQuerySpells) -> Spell {
-        let name = match spell.name.clone() {
-            Some(name) => name,
-            None => match spell.index.clone() {
-                Some(index) => index,
-                None => "".to_string(),
-            },
-        };
-        let desc = match spell.desc.clone() {
-            Some(desc) => desc
-                .into_iter()
-                .map(|desc| desc.unwrap_or_default())
-                .collect::<Vec<String>>(),
-            None => vec![],
-        };
-        let classes = match spell.classes.clone() {
-            Some(classes) => classes
-                .into_iter()
-                .map(|class| match class {
-                    Some(class) => class
-                        .name
-                        .unwrap_or_else(|| class.index.unwrap_or_default()),
-                    None => "".to_string(),
-                })
-                .collect::<Vec<String>>(),
-            None => vec![],
-        };
-
-        Spell {
-            name,
-            level: spell.level,
-            desc,
-            classes,
-        }
-    }
 }
 
 #[async_trait(?Send)]
@@ -178,65 +204,169 @@ impl<T> SpellInterface for SpellImplementation<T>
 where
     T: SpellsDataSourceInterface + std::marker::Sync + std::marker::Send,
 {
-    async fn get_random_spell(
-        &mut self,
-        level: Option<f64>,
-        classes: Vec<String>,
-        exact_level: bool,
-    ) -> Result<Spell, SpellError> {
-        let spells = self.get_all_spells().await?;
-        let filtered_spells = self.filter_spells(spells, level, classes, exact_level)?;
-        match self.get_random_spell(filtered_spells) {
-            Ok(spell) => Ok(self.spell_from_spells_query_spells(&spell)),
-            Err(err) => Err(err),
-        }
+    async fn get_random_spell(&mut self, filter: SpellFilter) -> Result<Spell, SpellError> {
+        let spells = self.get_spells_matching(&filter).await?;
+        let filtered_spells = self.filter_spells(spells, &filter)?;
+        self.get_random_spell(filtered_spells)
     }
 
+    /// Asks the datasource for the name first, then looks through all spells in case the
+    /// datasource matches names differently
     async fn get_spell_by_name(&mut self, name: String) -> Result<Spell, SpellError> {
-        let name = name.to_lowercase().trim().to_string();
-        let spells = self.get_all_spells().await?;
-        let filtered_spells = spells
-            .into_iter()
-            .filter(|spell| {
-                *spell
-                    .clone()
-                    .name
-                    .as_ref()
-                    .unwrap_or(&"".to_string())
-                    .to_lowercase()
-                    .trim()
-                    == name
-                    || *spell
-                        .index
-                        .as_ref()
-                        .unwrap_or(&"".to_string())
-                        .to_lowercase()
-                        .trim()
-                        == name.clone()
-            })
-            .collect::<Vec<SpellsQuerySpells>>();
-        match filtered_spells {
-            f if f.is_empty() => Err(SpellError::NoSpellsFound),
-            _ => Ok(self.spell_from_spells_query_spells(&filtered_spells[0])),
+        let filter = SpellFilter {
+            name: Some(name),
+            ..Default::default()
+        };
+        let spells = self.get_spells_matching(&filter).await?;
+        match self.filter_spells(spells, &filter) {
+            Err(SpellError::NoSpellsFound) => {
+                let spells = self.get_all_spells().await?;
+                Ok(self.filter_spells(spells, &filter)?.remove(0))
+            }
+            spells => Ok(spells?.remove(0)),
         }
     }
 
     async fn get_all_spells_with_filters(
         &mut self,
-        level: Option<f64>,
-        classes: Vec<String>,
-        exact_level: bool,
+        filter: SpellFilter,
     ) -> Result<Vec<Spell>, SpellError> {
-        // let spells = self.get_all_spells().await?;
-        let spells = self.get_all_spells().await?;
-        // let spells = spells;
-        let filtered_spells = self.filter_spells(spells, level, classes, exact_level)?;
+        let spells = self.get_spells_matching(&filter).await?;
+        let filtered_spells = self.filter_spells(spells, &filter)?;
         match filtered_spells {
             f if f.is_empty() => Err(SpellError::NoSpellsFound),
-            _ => Ok(filtered_spells
-                .into_iter()
-                .map(|spell| self.spell_from_spells_query_spells(&spell))
-                .collect::<Vec<Spell>>()),
+            f => Ok(f),
+        }
+    }
+
+    async fn revalidate(&mut self) -> Result<(), SpellError> {
+        self.datasource
+            .revalidate()
+            .await
+            .map_err(SpellError::DataSourceError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::core::entity::spell::SpellLevel;
+
+    struct MockSpellsDataSource {
+        spells: Vec<Spell>,
+    }
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for MockSpellsDataSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            Ok(self.spells.clone())
+        }
+    }
+
+    fn spell(index: &str, level: u8, classes: &[&str]) -> Spell {
+        Spell {
+            index: index.to_string(),
+            name: index.replace('-', " "),
+            level: SpellLevel::new(level).unwrap(),
+            desc: vec![format!("{} description", index)],
+            classes: classes.iter().map(|class| class.to_string()).collect(),
+            ..Default::default()
         }
     }
+
+    fn usecase() -> SpellImplementation<MockSpellsDataSource> {
+        SpellImplementation::new(MockSpellsDataSource {
+            spells: vec![
+                spell("fire-bolt", 0, &["Sorcerer", "Wizard"]),
+                spell("cure-wounds", 1, &["Bard", "Cleric"]),
+                spell("shatter", 2, &["Bard", "Wizard"]),
+                Spell {
+                    subclasses: vec!["Fiend".to_string(), "Life".to_string()],
+                    ..spell("fireball", 3, &["Sorcerer", "Wizard"])
+                },
+            ],
+        })
+    }
+
+    fn indexes(spells: Vec<Spell>) -> Vec<String> {
+        spells.into_iter().map(|spell| spell.index).collect()
+    }
+
+    #[test]
+    fn test_filter_by_level_range() {
+        let mut usecase = usecase();
+        let filter = SpellFilter {
+            level: Some("1..2".parse().unwrap()),
+            ..Default::default()
+        };
+        let spells = block_on(usecase.get_all_spells_with_filters(filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["cure-wounds", "shatter"]);
+    }
+
+    #[test]
+    fn test_filter_cantrips_and_classes() {
+        let mut usecase = usecase();
+        let filter = SpellFilter {
+            level: Some(SpellLevelRange::cantrips()),
+            classes: vec!["wizard".to_string()],
+            ..Default::default()
+        };
+        let spells = block_on(usecase.get_all_spells_with_filters(filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["fire-bolt"]);
+    }
+
+    #[test]
+    fn test_filter_class_and_subclass() {
+        let mut usecase = usecase();
+        let filter = SpellFilter {
+            classes: vec!["cleric".to_string()],
+            subclasses: vec!["Life Domain".to_string()],
+            ..Default::default()
+        };
+        let spells = block_on(usecase.get_all_spells_with_filters(filter.clone())).unwrap();
+        assert_eq!(indexes(spells), vec!["cure-wounds", "fireball"]);
+
+        // The Fiend is a warlock subclass
+        let filter = SpellFilter {
+            subclasses: vec!["The Fiend".to_string()],
+            ..filter
+        };
+        let spells = block_on(usecase.get_all_spells_with_filters(filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["cure-wounds"]);
+    }
+
+    #[test]
+    fn test_filter_up_to_level() {
+        let mut usecase = usecase();
+        let filter = SpellFilter {
+            level: Some(SpellLevelRange::up_to(SpellLevel::new(1).unwrap())),
+            ..Default::default()
+        };
+        let spells = block_on(usecase.get_all_spells_with_filters(filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["fire-bolt", "cure-wounds"]);
+    }
+
+    #[test]
+    fn test_filter_no_match() {
+        let mut usecase = usecase();
+        let filter = SpellFilter {
+            level: Some(SpellLevelRange::exact(SpellLevel::MAX)),
+            ..Default::default()
+        };
+        let err = block_on(usecase.get_all_spells_with_filters(filter)).unwrap_err();
+        assert!(matches!(err, SpellError::NoSpellsFound));
+    }
+
+    #[test]
+    fn test_get_spell_by_name_or_index() {
+        let mut usecase = usecase();
+        let spell = block_on(usecase.get_spell_by_name("Cure Wounds".to_string())).unwrap();
+        assert_eq!(spell.index, "cure-wounds");
+        let spell = block_on(usecase.get_spell_by_name("fire-bolt".to_string())).unwrap();
+        assert_eq!(spell.index, "fire-bolt");
+        let err = block_on(usecase.get_spell_by_name("wish".to_string())).unwrap_err();
+        assert!(matches!(err, SpellError::NoSpellsFound));
+    }
 }
@@ -0,0 +1,231 @@
+use crate::core::entity::spell::Spell;
+use crate::core::entity::spell_annotation::{SpellAnnotation, SpellAnnotationUpdate};
+use crate::datasources::common::local_file::LocalFileError;
+
+#[derive(Debug)]
+pub enum SpellAnnotationDataSourceError {
+    FileError(LocalFileError),
+}
+
+pub trait SpellAnnotationDataSourceInterface {
+    fn get_all_annotations(&self) -> Result<Vec<SpellAnnotation>, SpellAnnotationDataSourceError>;
+    fn save_all_annotations(
+        &self,
+        annotations: &[SpellAnnotation],
+    ) -> Result<(), SpellAnnotationDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum SpellAnnotationError {
+    NoSpellsFound,
+    DataSourceError(SpellAnnotationDataSourceError),
+}
+
+/// Annotation criteria a spell has to match, empty criteria match every spell
+#[derive(Debug, Clone, Default)]
+pub struct SpellAnnotationFilter {
+    /// Tags the spell must all have
+    pub tags: Vec<String>,
+    pub favourite: bool,
+}
+
+impl SpellAnnotationFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && !self.favourite
+    }
+
+    fn matches(&self, annotation: Option<&SpellAnnotation>) -> bool {
+        match annotation {
+            Some(annotation) => {
+                (!self.favourite || annotation.favourite)
+                    && self.tags.iter().all(|tag| annotation.has_tag(tag))
+            }
+            None => self.is_empty(),
+        }
+    }
+}
+
+pub trait SpellAnnotationInterface {
+    fn get_annotations(&self) -> Result<Vec<SpellAnnotation>, SpellAnnotationError>;
+
+    /// Applies `update` to the annotation of the spell, dropping annotations left empty
+    fn annotate(
+        &mut self,
+        spell: &Spell,
+        update: &SpellAnnotationUpdate,
+    ) -> Result<SpellAnnotation, SpellAnnotationError>;
+
+    fn filter_spells(
+        &self,
+        spells: Vec<Spell>,
+        filter: &SpellAnnotationFilter,
+    ) -> Result<Vec<Spell>, SpellAnnotationError>;
+}
+
+pub struct SpellAnnotationImplementation<T>
+where
+    T: SpellAnnotationDataSourceInterface,
+{
+    datasource: T,
+}
+
+impl<T> SpellAnnotationImplementation<T>
+where
+    T: SpellAnnotationDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        SpellAnnotationImplementation { datasource }
+    }
+}
+
+impl<T> SpellAnnotationInterface for SpellAnnotationImplementation<T>
+where
+    T: SpellAnnotationDataSourceInterface,
+{
+    fn get_annotations(&self) -> Result<Vec<SpellAnnotation>, SpellAnnotationError> {
+        self.datasource
+            .get_all_annotations()
+            .map_err(SpellAnnotationError::DataSourceError)
+    }
+
+    fn annotate(
+        &mut self,
+        spell: &Spell,
+        update: &SpellAnnotationUpdate,
+    ) -> Result<SpellAnnotation, SpellAnnotationError> {
+        let mut annotations = self.get_annotations()?;
+        let mut annotation = match annotations.iter().position(|a| a.index == spell.index) {
+            Some(position) => annotations.remove(position),
+            None => SpellAnnotation::new(spell.index.clone()),
+        };
+        annotation.apply(update);
+        if !annotation.is_empty() {
+            annotations.push(annotation.clone());
+        }
+        // Sorted so that diffs of a shared annotation file stay small
+        annotations.sort_by(|a, b| a.index.cmp(&b.index));
+        self.datasource
+            .save_all_annotations(&annotations)
+            .map_err(SpellAnnotationError::DataSourceError)?;
+        Ok(annotation)
+    }
+
+    fn filter_spells(
+        &self,
+        spells: Vec<Spell>,
+        filter: &SpellAnnotationFilter,
+    ) -> Result<Vec<Spell>, SpellAnnotationError> {
+        if filter.is_empty() {
+            return Ok(spells);
+        }
+        let annotations = self.get_annotations()?;
+        match spells
+            .into_iter()
+            .filter(|spell| filter.matches(annotations.iter().find(|a| a.index == spell.index)))
+            .collect::<Vec<Spell>>()
+        {
+            f if f.is_empty() => Err(SpellAnnotationError::NoSpellsFound),
+            f => Ok(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockSpellAnnotationDataSource {
+        annotations: RefCell<Vec<SpellAnnotation>>,
+    }
+
+    impl SpellAnnotationDataSourceInterface for MockSpellAnnotationDataSource {
+        fn get_all_annotations(
+            &self,
+        ) -> Result<Vec<SpellAnnotation>, SpellAnnotationDataSourceError> {
+            Ok(self.annotations.borrow().clone())
+        }
+
+        fn save_all_annotations(
+            &self,
+            annotations: &[SpellAnnotation],
+        ) -> Result<(), SpellAnnotationDataSourceError> {
+            *self.annotations.borrow_mut() = annotations.to_vec();
+            Ok(())
+        }
+    }
+
+    fn spell(index: &str) -> Spell {
+        Spell {
+            index: index.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn tag(tags: &[&str], favourite: Option<bool>) -> SpellAnnotationUpdate {
+        SpellAnnotationUpdate {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            favourite,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_annotate_and_filter() {
+        let mut usecase =
+            SpellAnnotationImplementation::new(MockSpellAnnotationDataSource::default());
+        usecase
+            .annotate(&spell("shatter"), &tag(&["aoe"], None))
+            .unwrap();
+        usecase
+            .annotate(&spell("fireball"), &tag(&["aoe", "fire"], Some(true)))
+            .unwrap();
+        let indexes = usecase
+            .get_annotations()
+            .unwrap()
+            .into_iter()
+            .map(|a| a.index)
+            .collect::<Vec<String>>();
+        assert_eq!(indexes, vec!["fireball", "shatter"]);
+
+        let spells = vec![spell("fire-bolt"), spell("fireball"), spell("shatter")];
+        let filter = |tags: &[&str], favourite: bool| SpellAnnotationFilter {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            favourite,
+        };
+        let filtered = usecase
+            .filter_spells(spells.clone(), &filter(&["AoE"], false))
+            .unwrap();
+        assert_eq!(filtered.len(), 2);
+        let filtered = usecase
+            .filter_spells(spells.clone(), &filter(&["aoe"], true))
+            .unwrap();
+        assert_eq!(filtered[0].index, "fireball");
+        assert_eq!(
+            usecase
+                .filter_spells(spells.clone(), &SpellAnnotationFilter::default())
+                .unwrap()
+                .len(),
+            3
+        );
+        let err = usecase
+            .filter_spells(spells, &filter(&["ritual"], false))
+            .unwrap_err();
+        assert!(matches!(err, SpellAnnotationError::NoSpellsFound));
+    }
+
+    #[test]
+    fn test_empty_annotation_is_dropped() {
+        let mut usecase =
+            SpellAnnotationImplementation::new(MockSpellAnnotationDataSource::default());
+        usecase
+            .annotate(&spell("fireball"), &tag(&[], Some(true)))
+            .unwrap();
+        let annotation = usecase
+            .annotate(&spell("fireball"), &tag(&[], Some(false)))
+            .unwrap();
+        assert!(annotation.is_empty());
+        assert!(usecase.get_annotations().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::core::entity::spell::Spell;
+use crate::core::entity::spell_card::{CardFormat, SpellCard, CARD_HEIGHT, CARD_WIDTH};
+use crate::core::usecase::export::escape_html;
+
+use std::path::Path;
+
+const PADDING: f32 = 12.0;
+/// Space taken by the title and subtitle at the top of a card
+const HEADER_HEIGHT: f32 = 52.0;
+/// Space taken by the footer at the bottom of a card
+const FOOTER_HEIGHT: f32 = 20.0;
+const MAX_FONT_SIZE: f32 = 9.0;
+const MIN_FONT_SIZE: f32 = 6.0;
+const FONT_SIZE_STEP: f32 = 0.5;
+/// Average glyph width relative to the font size of a serif font
+const CHAR_WIDTH_RATIO: f32 = 0.5;
+const LINE_HEIGHT_RATIO: f32 = 1.25;
+/// CSS pixels per inch
+const PX_PER_INCH: f32 = 96.0;
+/// Letter paper
+const PAGE_WIDTH: f32 = 8.5 * PX_PER_INCH;
+const PAGE_HEIGHT: f32 = 11.0 * PX_PER_INCH;
+/// Leaves room for three rows of 3.5in cards on the 11in page
+const PAGE_MARGIN: f32 = 0.25 * PX_PER_INCH;
+const CARDS_PER_ROW: usize = ((PAGE_WIDTH - 2.0 * PAGE_MARGIN) / CARD_WIDTH) as usize;
+const ROWS_PER_PAGE: usize = ((PAGE_HEIGHT - 2.0 * PAGE_MARGIN) / CARD_HEIGHT) as usize;
+const CARDS_PER_PAGE: usize = CARDS_PER_ROW * ROWS_PER_PAGE;
+
+pub trait SpellCardInterface {
+    /// Lays out spells as cards, shrinking the text to fit and splitting it onto continuation cards
+    fn layout(&self, spells: &[Spell]) -> Vec<SpellCard>;
+
+    fn render(&self, spells: &[Spell], format: CardFormat) -> String;
+
+    fn render_to_file(
+        &self,
+        spells: &[Spell],
+        format: CardFormat,
+        path: &Path,
+    ) -> Result<(), std::io::Error>;
+}
+
+pub struct SpellCardImplementation {}
+
+impl SpellCardImplementation {
+    pub fn new() -> Self {
+        SpellCardImplementation {}
+    }
+
+    fn chars_per_line(font_size: f32) -> usize {
+        ((CARD_WIDTH - 2.0 * PADDING) / (font_size * CHAR_WIDTH_RATIO)) as usize
+    }
+
+    fn lines_per_card(font_size: f32) -> usize {
+        ((CARD_HEIGHT - HEADER_HEIGHT - FOOTER_HEIGHT - 2.0 * PADDING)
+            / (font_size * LINE_HEIGHT_RATIO)) as usize
+    }
+
+    fn wrap(paragraphs: &[String], width: usize) -> Vec<String> {
+        let mut lines = vec![];
+        for paragraph in paragraphs {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let mut word = word.to_string();
+                while word.chars().count() > width {
+                    if !line.is_empty() {
+                        lines.push(std::mem::take(&mut line));
+                    }
+                    let rest = word.chars().skip(width).collect::<String>();
+                    lines.push(word.chars().take(width).collect());
+                    word = rest;
+                }
+                if line.is_empty() {
+                    line = word;
+                } else if line.chars().count() + 1 + word.chars().count() <= width {
+                    line.push(' ');
+                    line.push_str(&word);
+                } else {
+                    lines.push(std::mem::replace(&mut line, word));
+                }
+            }
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        lines
+    }
+
+    /// Largest font size the description fits in on a single card, if any
+    fn fitting_font_size(desc: &[String]) -> Option<(f32, Vec<String>)> {
+        let mut font_size = MAX_FONT_SIZE;
+        while font_size >= MIN_FONT_SIZE {
+            let lines = Self::wrap(desc, Self::chars_per_line(font_size));
+            if lines.len() <= Self::lines_per_card(font_size) {
+                return Some((font_size, lines));
+            }
+            font_size -= FONT_SIZE_STEP;
+        }
+        None
+    }
+
+    fn split_lines(lines: Vec<String>, per_card: usize) -> Vec<Vec<String>> {
+        let mut chunks: Vec<Vec<String>> = vec![];
+        let mut chunk = vec![];
+        for line in lines {
+            if chunk.is_empty() && line.is_empty() {
+                continue;
+            }
+            chunk.push(line);
+            if chunk.len() == per_card {
+                chunks.push(std::mem::take(&mut chunk));
+            }
+        }
+        if !chunk.is_empty() || chunks.is_empty() {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    fn layout_spell(spell: &Spell) -> Vec<SpellCard> {
+        let (font_size, chunks) = match Self::fitting_font_size(&spell.desc) {
+            Some((font_size, lines)) => (font_size, vec![lines]),
+            None => {
+                let lines = Self::wrap(&spell.desc, Self::chars_per_line(MIN_FONT_SIZE));
+                (
+                    MIN_FONT_SIZE,
+                    Self::split_lines(lines, Self::lines_per_card(MIN_FONT_SIZE)),
+                )
+            }
+        };
+        let parts = chunks.len();
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, lines)| SpellCard {
+                index: spell.index.clone(),
+                name: spell.name.clone(),
+                level: spell.level,
+                classes: spell.classes.to_vec(),
+                source: spell.source,
+                lines,
+                font_size,
+                part: i + 1,
+                parts,
+            })
+            .collect()
+    }
+
+    fn render_svg_card(card: &SpellCard, x: f32, y: f32) -> String {
+        let mut out = format!("<g transform=\"translate({},{})\">\n", x, y);
+        out.push_str(&format!(
+            "<rect x=\"0.5\" y=\"0.5\" width=\"{}\" height=\"{}\" rx=\"10\" fill=\"white\" stroke=\"#333\"/>\n",
+            CARD_WIDTH - 1.0,
+            CARD_HEIGHT - 1.0
+        ));
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"13\" font-weight=\"bold\">{}</text>\n",
+            PADDING,
+            PADDING + 13.0,
+            escape_html(&card.title())
+        ));
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"8\" font-style=\"italic\">{}</text>\n",
+            PADDING,
+            PADDING + 28.0,
+            escape_html(&card.subtitle())
+        ));
+        out.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#999\"/>\n",
+            PADDING,
+            HEADER_HEIGHT,
+            CARD_WIDTH - PADDING,
+            HEADER_HEIGHT
+        ));
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\">\n",
+            PADDING,
+            HEADER_HEIGHT + PADDING,
+            card.font_size
+        ));
+        for line in &card.lines {
+            out.push_str(&format!(
+                "<tspan x=\"{}\" dy=\"{}\">{}</tspan>\n",
+                PADDING,
+                card.font_size * LINE_HEIGHT_RATIO,
+                escape_html(line)
+            ));
+        }
+        out.push_str("</text>\n");
+        out.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" font-size=\"7\" text-anchor=\"end\">{}</text>\n",
+            CARD_WIDTH - PADDING,
+            CARD_HEIGHT - PADDING,
+            escape_html(&card.footer())
+        ));
+        out.push_str("</g>\n");
+        out
+    }
+
+    fn render_svg(cards: &[SpellCard]) -> String {
+        let columns = cards.len().clamp(1, CARDS_PER_ROW);
+        let rows = cards.len().div_ceil(CARDS_PER_ROW);
+        let width = columns as f32 * CARD_WIDTH;
+        let height = rows.max(1) as f32 * CARD_HEIGHT;
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"Georgia, serif\">\n",
+            w = width,
+            h = height
+        );
+        for (i, card) in cards.iter().enumerate() {
+            let x = (i % CARDS_PER_ROW) as f32 * CARD_WIDTH;
+            let y = (i / CARDS_PER_ROW) as f32 * CARD_HEIGHT;
+            out.push_str(&Self::render_svg_card(card, x, y));
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    fn render_html_card(card: &SpellCard) -> String {
+        let mut out = String::from("<div class=\"card\">\n");
+        out.push_str(&format!("<h2>{}</h2>\n", escape_html(&card.title())));
+        out.push_str(&format!(
+            "<div class=\"meta\">{}</div>\n",
+            escape_html(&card.subtitle())
+        ));
+        out.push_str(&format!(
+            "<div class=\"desc\" style=\"font-size: {}px\">\n",
+            card.font_size
+        ));
+        for paragraph in card.lines.split(|line| line.is_empty()) {
+            if !paragraph.is_empty() {
+                out.push_str(&format!("<p>{}</p>\n", escape_html(&paragraph.join(" "))));
+            }
+        }
+        out.push_str("</div>\n");
+        out.push_str(&format!(
+            "<div class=\"footer\">{}</div>\n",
+            escape_html(&card.footer())
+        ));
+        out.push_str("</div>\n");
+        out
+    }
+
+    fn render_html(cards: &[SpellCard]) -> String {
+        let mut out = String::new();
+        out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+        out.push_str("<meta charset=\"utf-8\">\n<title>Spell cards</title>\n");
+        out.push_str(&format!(
+            "<style>\n\
+             @page {{ size: letter; margin: {page_margin}px; }}\n\
+             body {{ margin: 0; font-family: Georgia, serif; }}\n\
+             .page {{ display: flex; flex-wrap: wrap; width: {page_width}px; break-after: page; page-break-after: always; }}\n\
+             .page:last-child {{ break-after: auto; page-break-after: auto; }}\n\
+             .card {{ box-sizing: border-box; width: {width}px; height: {height}px; padding: {padding}px; border: 1px solid #333; border-radius: 10px; display: flex; flex-direction: column; overflow: hidden; }}\n\
+             .card h2 {{ font-size: 13px; margin: 0; }}\n\
+             .meta {{ font-size: 8px; font-style: italic; border-bottom: 1px solid #999; padding-bottom: 4px; margin-bottom: 4px; }}\n\
+             .desc {{ flex: 1; line-height: {line_height}; }}\n\
+             .desc p {{ margin: 0 0 0.5em 0; }}\n\
+             .footer {{ font-size: 7px; text-align: right; }}\n\
+             </style>\n",
+            page_margin = PAGE_MARGIN,
+            page_width = CARDS_PER_ROW as f32 * CARD_WIDTH,
+            width = CARD_WIDTH,
+            height = CARD_HEIGHT,
+            padding = PADDING,
+            line_height = LINE_HEIGHT_RATIO
+        ));
+        out.push_str("</head>\n<body>\n");
+        for page in cards.chunks(CARDS_PER_PAGE) {
+            out.push_str("<div class=\"page\">\n");
+            for card in page {
+                out.push_str(&Self::render_html_card(card));
+            }
+            out.push_str("</div>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+impl Default for SpellCardImplementation {
+    fn default() -> Self {
+        SpellCardImplementation::new()
+    }
+}
+
+impl SpellCardInterface for SpellCardImplementation {
+    fn layout(&self, spells: &[Spell]) -> Vec<SpellCard> {
+        spells.iter().flat_map(Self::layout_spell).collect()
+    }
+
+    fn render(&self, spells: &[Spell], format: CardFormat) -> String {
+        let cards = self.layout(spells);
+        match format {
+            CardFormat::Svg => Self::render_svg(&cards),
+            CardFormat::Html => Self::render_html(&cards),
+        }
+    }
+
+    fn render_to_file(
+        &self,
+        spells: &[Spell],
+        format: CardFormat,
+        path: &Path,
+    ) -> Result<(), std::io::Error> {
+        std::fs::write(path, self.render(spells, format))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellLevel;
+
+    fn spell(name: &str, words: usize) -> Spell {
+        Spell {
+            index: name.to_lowercase(),
+            name: name.to_string(),
+            level: SpellLevel::new(3).unwrap(),
+            desc: vec![vec!["word"; words].join(" ")],
+            classes: vec!["Wizard".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_wrap() {
+        let lines = SpellCardImplementation::wrap(
+            &["aaa bbb ccc".to_string(), "dddddddddd".to_string()],
+            7,
+        );
+        assert_eq!(lines, vec!["aaa bbb", "ccc", "", "ddddddd", "ddd"]);
+    }
+
+    #[test]
+    fn test_short_description_uses_largest_font() {
+        let cards = SpellCardImplementation::new().layout(&[spell("Shield", 20)]);
+        assert_eq!(cards.len(), 1);
+        assert_eq!(cards[0].font_size, MAX_FONT_SIZE);
+        assert_eq!(cards[0].footer(), "SRD");
+    }
+
+    #[test]
+    fn test_long_description_shrinks_font() {
+        let cards = SpellCardImplementation::new().layout(&[spell("Fireball", 250)]);
+        assert_eq!(cards.len(), 1);
+        assert!(cards[0].font_size < MAX_FONT_SIZE);
+        assert!(cards[0].font_size >= MIN_FONT_SIZE);
+    }
+
+    #[test]
+    fn test_overflow_continues_on_next_card() {
+        let cards = SpellCardImplementation::new().layout(&[spell("Wish", 1000)]);
+        assert!(cards.len() > 1);
+        assert!(cards.iter().all(|card| card.font_size == MIN_FONT_SIZE));
+        assert!(
+            cards
+                .iter()
+                .all(|card| card.lines.len()
+                    <= SpellCardImplementation::lines_per_card(MIN_FONT_SIZE))
+        );
+        assert_eq!(cards[0].title(), "Wish");
+        assert_eq!(cards[1].title(), "Wish (cont.)");
+        assert_eq!(cards[1].footer(), format!("SRD · 2/{}", cards.len()));
+        let words = cards
+            .iter()
+            .flat_map(|card| card.lines.iter())
+            .flat_map(|line| line.split_whitespace())
+            .count();
+        assert_eq!(words, 1000);
+    }
+
+    #[test]
+    fn test_render_html_pages() {
+        let spells = (0..10)
+            .map(|i| spell(&format!("Spell {}", i), 10))
+            .collect::<Vec<Spell>>();
+        let out = SpellCardImplementation::new().render(&spells, CardFormat::Html);
+        assert_eq!(out.matches("<div class=\"page\">").count(), 2);
+        assert_eq!(out.matches("<div class=\"card\">").count(), 10);
+        assert!(out.contains("@page { size: letter; margin: 24px; }"));
+    }
+
+    #[test]
+    fn test_cards_fit_the_printable_page() {
+        assert_eq!((CARDS_PER_ROW, ROWS_PER_PAGE), (3, 3));
+        assert!(CARDS_PER_ROW as f32 * CARD_WIDTH <= PAGE_WIDTH - 2.0 * PAGE_MARGIN);
+        assert!(ROWS_PER_PAGE as f32 * CARD_HEIGHT <= PAGE_HEIGHT - 2.0 * PAGE_MARGIN);
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let out =
+            SpellCardImplementation::new().render(&[spell("Fire & Ice", 10)], CardFormat::Svg);
+        assert!(out.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"240\""));
+        assert!(out.contains(">Fire &amp; Ice</text>"));
+        assert!(out.contains("Level 3 · Wizard"));
+    }
+}
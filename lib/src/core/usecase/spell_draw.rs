@@ -0,0 +1,246 @@
+use crate::core::entity::spell::Spell;
+use crate::core::entity::spell_deck::{SpellDeck, SpellWeighting};
+use crate::datasources::common::local_file::LocalFileError;
+use rand::prelude::SliceRandom;
+
+#[derive(Debug)]
+pub enum SpellDeckDataSourceError {
+    FileError(LocalFileError),
+}
+
+pub trait SpellDeckDataSourceInterface {
+    fn get_all_decks(&self) -> Result<Vec<SpellDeck>, SpellDeckDataSourceError>;
+    fn save_all_decks(&self, decks: &[SpellDeck]) -> Result<(), SpellDeckDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum SpellDrawError {
+    /// Requested and available number of spells
+    NotEnoughSpells(usize, usize),
+    UnknownDeck(String),
+    DataSourceError(SpellDeckDataSourceError),
+}
+
+pub trait SpellDrawInterface {
+    /// Draws `count` distinct spells, skipping and recording spells already drawn from `deck`
+    fn draw(
+        &mut self,
+        spells: &[Spell],
+        count: usize,
+        deck: Option<&str>,
+        weighting: &SpellWeighting,
+    ) -> Result<Vec<Spell>, SpellDrawError>;
+
+    /// Puts all drawn spells back into the deck
+    fn reset_deck(&mut self, deck: &str) -> Result<SpellDeck, SpellDrawError>;
+}
+
+pub struct SpellDrawImplementation<T>
+where
+    T: SpellDeckDataSourceInterface,
+{
+    datasource: T,
+}
+
+impl<T> SpellDrawImplementation<T>
+where
+    T: SpellDeckDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        SpellDrawImplementation { datasource }
+    }
+
+    fn load(&self) -> Result<Vec<SpellDeck>, SpellDrawError> {
+        self.datasource
+            .get_all_decks()
+            .map_err(SpellDrawError::DataSourceError)
+    }
+
+    fn save(&self, decks: &[SpellDeck]) -> Result<(), SpellDrawError> {
+        self.datasource
+            .save_all_decks(decks)
+            .map_err(SpellDrawError::DataSourceError)
+    }
+
+    fn choose(
+        spells: Vec<&Spell>,
+        count: usize,
+        weighting: &SpellWeighting,
+    ) -> Result<Vec<Spell>, SpellDrawError> {
+        let candidates = spells
+            .into_iter()
+            .filter(|spell| weighting.weight(spell) > 0.0)
+            .collect::<Vec<&Spell>>();
+        if candidates.len() < count {
+            return Err(SpellDrawError::NotEnoughSpells(count, candidates.len()));
+        }
+        let mut rng = rand::thread_rng();
+        let drawn = match weighting {
+            SpellWeighting::Uniform => candidates
+                .choose_multiple(&mut rng, count)
+                .map(|spell| (*spell).clone())
+                .collect(),
+            _ => candidates
+                .choose_multiple_weighted(&mut rng, count, |spell| weighting.weight(spell))
+                .map_err(|_| SpellDrawError::NotEnoughSpells(count, 0))?
+                .map(|spell| (*spell).clone())
+                .collect(),
+        };
+        Ok(drawn)
+    }
+}
+
+impl<T> SpellDrawInterface for SpellDrawImplementation<T>
+where
+    T: SpellDeckDataSourceInterface,
+{
+    fn draw(
+        &mut self,
+        spells: &[Spell],
+        count: usize,
+        deck: Option<&str>,
+        weighting: &SpellWeighting,
+    ) -> Result<Vec<Spell>, SpellDrawError> {
+        let name = match deck {
+            Some(name) => name.trim(),
+            None => return Self::choose(spells.iter().collect(), count, weighting),
+        };
+        let mut decks = self.load()?;
+        let position = match decks
+            .iter()
+            .position(|deck| deck.name.eq_ignore_ascii_case(name))
+        {
+            Some(position) => position,
+            None => {
+                decks.push(SpellDeck::new(name.to_string()));
+                decks.len() - 1
+            }
+        };
+        let deck = &mut decks[position];
+        let remaining = spells
+            .iter()
+            .filter(|spell| !deck.is_drawn(spell))
+            .collect::<Vec<&Spell>>();
+        let drawn = Self::choose(remaining, count, weighting)?;
+        deck.drawn
+            .extend(drawn.iter().map(|spell| spell.index.clone()));
+        self.save(&decks)?;
+        Ok(drawn)
+    }
+
+    fn reset_deck(&mut self, deck: &str) -> Result<SpellDeck, SpellDrawError> {
+        let mut decks = self.load()?;
+        let reset = match decks
+            .iter_mut()
+            .find(|d| d.name.eq_ignore_ascii_case(deck.trim()))
+        {
+            Some(d) => {
+                d.drawn.clear();
+                d.clone()
+            }
+            None => return Err(SpellDrawError::UnknownDeck(deck.to_string())),
+        };
+        self.save(&decks)?;
+        Ok(reset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellLevel;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockSpellDeckDataSource {
+        decks: RefCell<Vec<SpellDeck>>,
+    }
+
+    impl SpellDeckDataSourceInterface for MockSpellDeckDataSource {
+        fn get_all_decks(&self) -> Result<Vec<SpellDeck>, SpellDeckDataSourceError> {
+            Ok(self.decks.borrow().clone())
+        }
+
+        fn save_all_decks(&self, decks: &[SpellDeck]) -> Result<(), SpellDeckDataSourceError> {
+            *self.decks.borrow_mut() = decks.to_vec();
+            Ok(())
+        }
+    }
+
+    fn spells() -> Vec<Spell> {
+        (0..5)
+            .map(|level| Spell {
+                index: format!("spell-{}", level),
+                name: format!("Spell {}", level),
+                level: SpellLevel::new(level).unwrap(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn indexes(spells: &[Spell]) -> Vec<String> {
+        let mut indexes = spells
+            .iter()
+            .map(|spell| spell.index.clone())
+            .collect::<Vec<String>>();
+        indexes.sort();
+        indexes
+    }
+
+    #[test]
+    fn test_draw_distinct_spells() {
+        let mut usecase = SpellDrawImplementation::new(MockSpellDeckDataSource::default());
+        let drawn = usecase
+            .draw(&spells(), 5, None, &SpellWeighting::Uniform)
+            .unwrap();
+        assert_eq!(indexes(&drawn), indexes(&spells()));
+        let err = usecase
+            .draw(&spells(), 6, None, &SpellWeighting::Uniform)
+            .unwrap_err();
+        assert!(matches!(err, SpellDrawError::NotEnoughSpells(6, 5)));
+    }
+
+    #[test]
+    fn test_deck_excludes_drawn_spells() {
+        let mut usecase = SpellDrawImplementation::new(MockSpellDeckDataSource::default());
+        let first = usecase
+            .draw(&spells(), 3, Some("scrolls"), &SpellWeighting::Uniform)
+            .unwrap();
+        let second = usecase
+            .draw(&spells(), 2, Some("Scrolls"), &SpellWeighting::Uniform)
+            .unwrap();
+        let all = [first, second].concat();
+        assert_eq!(indexes(&all), indexes(&spells()));
+        let err = usecase
+            .draw(&spells(), 1, Some("scrolls"), &SpellWeighting::Uniform)
+            .unwrap_err();
+        assert!(matches!(err, SpellDrawError::NotEnoughSpells(1, 0)));
+
+        let deck = usecase.reset_deck("scrolls").unwrap();
+        assert!(deck.drawn.is_empty());
+        assert_eq!(
+            usecase
+                .draw(&spells(), 1, Some("scrolls"), &SpellWeighting::Uniform)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_weighted_draw_skips_zero_weights() {
+        let mut usecase = SpellDrawImplementation::new(MockSpellDeckDataSource::default());
+        let weighting = "level:0=0,1=0,2=0,3=10".parse::<SpellWeighting>().unwrap();
+        let drawn = usecase.draw(&spells(), 2, None, &weighting).unwrap();
+        assert_eq!(indexes(&drawn), vec!["spell-3", "spell-4"]);
+        let err = usecase.draw(&spells(), 3, None, &weighting).unwrap_err();
+        assert!(matches!(err, SpellDrawError::NotEnoughSpells(3, 2)));
+    }
+
+    #[test]
+    fn test_reset_unknown_deck_fails() {
+        let mut usecase = SpellDrawImplementation::new(MockSpellDeckDataSource::default());
+        let err = usecase.reset_deck("scrolls").unwrap_err();
+        assert!(matches!(err, SpellDrawError::UnknownDeck(_)));
+    }
+}
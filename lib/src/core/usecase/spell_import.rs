@@ -0,0 +1,119 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellsDataSourceError, SpellsDataSourceInterface};
+use async_trait::async_trait;
+
+/// Datasource that can replace its spells with spells read from another datasource
+pub trait SpellStoreInterface {
+    fn replace_all_spells(&self, spells: &[Spell]) -> Result<(), SpellsDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum SpellImportError {
+    /// The source returned no spells, the store is left untouched
+    NoSpellsFound,
+    DataSourceError(SpellsDataSourceError),
+}
+
+#[async_trait(?Send)]
+pub trait SpellImportInterface {
+    /// Replaces the stored spells with the spells of the source, returning how many were stored
+    async fn import_spells(&self) -> Result<usize, SpellImportError>;
+}
+
+pub struct SpellImportImplementation<S, T>
+where
+    S: SpellsDataSourceInterface,
+    T: SpellStoreInterface,
+{
+    source: S,
+    store: T,
+}
+
+impl<S, T> SpellImportImplementation<S, T>
+where
+    S: SpellsDataSourceInterface,
+    T: SpellStoreInterface,
+{
+    pub fn new(source: S, store: T) -> Self {
+        SpellImportImplementation { source, store }
+    }
+}
+
+#[async_trait(?Send)]
+impl<S, T> SpellImportInterface for SpellImportImplementation<S, T>
+where
+    S: SpellsDataSourceInterface,
+    T: SpellStoreInterface,
+{
+    async fn import_spells(&self) -> Result<usize, SpellImportError> {
+        let spells = self
+            .source
+            .get_all_spells()
+            .await
+            .map_err(SpellImportError::DataSourceError)?;
+        if spells.is_empty() {
+            return Err(SpellImportError::NoSpellsFound);
+        }
+        self.store
+            .replace_all_spells(&spells)
+            .map_err(SpellImportError::DataSourceError)?;
+        Ok(spells.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::cell::RefCell;
+
+    struct MockSource {
+        spells: Vec<Spell>,
+    }
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for MockSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            Ok(self.spells.clone())
+        }
+    }
+
+    #[derive(Default)]
+    struct MockStore {
+        spells: RefCell<Vec<String>>,
+    }
+
+    impl SpellStoreInterface for &MockStore {
+        fn replace_all_spells(&self, spells: &[Spell]) -> Result<(), SpellsDataSourceError> {
+            *self.spells.borrow_mut() = spells.iter().map(|spell| spell.index.clone()).collect();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_import_replaces_stored_spells() {
+        let store = MockStore {
+            spells: RefCell::new(vec!["old".to_string()]),
+        };
+        let source = MockSource {
+            spells: vec![
+                Spell {
+                    index: "fireball".to_string(),
+                    ..Default::default()
+                },
+                Spell {
+                    index: "shield".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+        let count = block_on(SpellImportImplementation::new(source, &store).import_spells());
+        assert_eq!(count.unwrap(), 2);
+        assert_eq!(*store.spells.borrow(), vec!["fireball", "shield"]);
+
+        let empty = MockSource { spells: vec![] };
+        let err = block_on(SpellImportImplementation::new(empty, &store).import_spells());
+        assert!(matches!(err, Err(SpellImportError::NoSpellsFound)));
+        assert_eq!(store.spells.borrow().len(), 2);
+    }
+}
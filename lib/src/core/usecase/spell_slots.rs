@@ -0,0 +1,370 @@
+use crate::core::entity::spell::SpellLevel;
+use crate::core::entity::spell_slots::{
+    CasterSlots, CasterType, ClassLevel, Rest, SpellSlots, MAX_CHARACTER_LEVEL,
+};
+use crate::datasources::common::local_file::LocalFileError;
+
+#[derive(Debug)]
+pub enum SpellSlotsDataSourceError {
+    FileError(LocalFileError),
+}
+
+pub trait SpellSlotsDataSourceInterface {
+    fn get_all_caster_slots(&self) -> Result<Vec<CasterSlots>, SpellSlotsDataSourceError>;
+    fn save_all_caster_slots(
+        &self,
+        caster_slots: &[CasterSlots],
+    ) -> Result<(), SpellSlotsDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum SpellSlotsError {
+    UnknownClass(String),
+    DuplicateClass(String),
+    CharacterLevelTooHigh(u8),
+    UnknownCharacter(String),
+    NoSlotRemaining(SpellLevel),
+    NoPactSlotRemaining,
+    /// Pact slots are all of the contained level
+    NotPactSlotLevel(SpellLevel),
+    DataSourceError(SpellSlotsDataSourceError),
+}
+
+pub trait SpellSlotsInterface {
+    fn calculate_slots(&self, class_levels: &[ClassLevel]) -> Result<SpellSlots, SpellSlotsError>;
+
+    fn set_class_levels(
+        &mut self,
+        character: String,
+        class_levels: Vec<ClassLevel>,
+    ) -> Result<CasterSlots, SpellSlotsError>;
+
+    fn get_caster_slots(&self, character: &str) -> Result<CasterSlots, SpellSlotsError>;
+
+    fn expend_slot(
+        &mut self,
+        character: &str,
+        level: SpellLevel,
+        pact: bool,
+    ) -> Result<CasterSlots, SpellSlotsError>;
+
+    fn rest(&mut self, character: &str, rest: Rest) -> Result<CasterSlots, SpellSlotsError>;
+}
+
+pub struct SpellSlotsImplementation<T>
+where
+    T: SpellSlotsDataSourceInterface,
+{
+    datasource: T,
+}
+
+impl<T> SpellSlotsImplementation<T>
+where
+    T: SpellSlotsDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        SpellSlotsImplementation { datasource }
+    }
+
+    fn load(&self) -> Result<Vec<CasterSlots>, SpellSlotsError> {
+        self.datasource
+            .get_all_caster_slots()
+            .map_err(SpellSlotsError::DataSourceError)
+    }
+
+    fn save(&self, caster_slots: &[CasterSlots]) -> Result<(), SpellSlotsError> {
+        self.datasource
+            .save_all_caster_slots(caster_slots)
+            .map_err(SpellSlotsError::DataSourceError)
+    }
+
+    /// Applies `update` to the character's slots and persists all characters
+    fn update_caster_slots<F>(
+        &mut self,
+        character: &str,
+        update: F,
+    ) -> Result<CasterSlots, SpellSlotsError>
+    where
+        F: FnOnce(&mut CasterSlots, &SpellSlots) -> Result<(), SpellSlotsError>,
+    {
+        let mut all_caster_slots = self.load()?;
+        let caster_slots = match all_caster_slots.iter_mut().find(|caster_slots| {
+            caster_slots
+                .character
+                .eq_ignore_ascii_case(character.trim())
+        }) {
+            Some(caster_slots) => caster_slots,
+            None => return Err(SpellSlotsError::UnknownCharacter(character.to_string())),
+        };
+        let maximum = self.calculate_slots(&caster_slots.class_levels)?;
+        update(caster_slots, &maximum)?;
+        let updated = caster_slots.clone();
+        self.save(&all_caster_slots)?;
+        Ok(updated)
+    }
+
+    fn caster_types(
+        &self,
+        class_levels: &[ClassLevel],
+    ) -> Result<Vec<(CasterType, u8)>, SpellSlotsError> {
+        let mut seen = Vec::new();
+        let mut total_level = 0_u8;
+        let mut caster_types = Vec::new();
+        for class_level in class_levels {
+            if seen.contains(&class_level.class) {
+                return Err(SpellSlotsError::DuplicateClass(class_level.class.clone()));
+            }
+            seen.push(class_level.class.clone());
+            total_level = total_level.saturating_add(class_level.level);
+            match CasterType::from_class(&class_level.class) {
+                Some(caster_type) => caster_types.push((caster_type, class_level.level)),
+                None => return Err(SpellSlotsError::UnknownClass(class_level.class.clone())),
+            }
+        }
+        if total_level > MAX_CHARACTER_LEVEL {
+            return Err(SpellSlotsError::CharacterLevelTooHigh(total_level));
+        }
+        Ok(caster_types)
+    }
+
+    /// Caster level on the spellcaster table; a single spellcasting class uses its own
+    /// progression (rounding up), several use the multiclass rules (rounding down)
+    fn caster_level(caster_types: &[(CasterType, u8)]) -> u8 {
+        let spellcasting = caster_types
+            .iter()
+            .filter(|(caster_type, _)| {
+                matches!(
+                    caster_type,
+                    CasterType::Full | CasterType::Half | CasterType::Third
+                )
+            })
+            .collect::<Vec<&(CasterType, u8)>>();
+        match spellcasting[..] {
+            [(CasterType::Half, level)] if *level >= 2 => level / 2 + level % 2,
+            [(CasterType::Third, level)] if *level >= 3 => level / 3 + u8::from(level % 3 > 0),
+            [(CasterType::Full, level)] => *level,
+            [_] => 0,
+            _ => spellcasting
+                .iter()
+                .map(|(caster_type, level)| match caster_type {
+                    CasterType::Full => *level,
+                    CasterType::Half => level / 2,
+                    CasterType::Third => level / 3,
+                    _ => 0,
+                })
+                .sum(),
+        }
+    }
+}
+
+impl<T> SpellSlotsInterface for SpellSlotsImplementation<T>
+where
+    T: SpellSlotsDataSourceInterface,
+{
+    fn calculate_slots(&self, class_levels: &[ClassLevel]) -> Result<SpellSlots, SpellSlotsError> {
+        let caster_types = self.caster_types(class_levels)?;
+        let mut slots = SpellSlots::for_caster_level(Self::caster_level(&caster_types));
+        let warlock_level = caster_types
+            .iter()
+            .filter(|(caster_type, _)| *caster_type == CasterType::Pact)
+            .map(|(_, level)| *level)
+            .sum();
+        slots.pact = SpellSlots::pact_for_warlock_level(warlock_level);
+        Ok(slots)
+    }
+
+    fn set_class_levels(
+        &mut self,
+        character: String,
+        class_levels: Vec<ClassLevel>,
+    ) -> Result<CasterSlots, SpellSlotsError> {
+        self.calculate_slots(&class_levels)?;
+        let mut all_caster_slots = self.load()?;
+        all_caster_slots.retain(|caster_slots| {
+            !caster_slots
+                .character
+                .eq_ignore_ascii_case(character.trim())
+        });
+        let caster_slots = CasterSlots::new(character, class_levels);
+        all_caster_slots.push(caster_slots.clone());
+        self.save(&all_caster_slots)?;
+        Ok(caster_slots)
+    }
+
+    fn get_caster_slots(&self, character: &str) -> Result<CasterSlots, SpellSlotsError> {
+        match self.load()?.into_iter().find(|caster_slots| {
+            caster_slots
+                .character
+                .eq_ignore_ascii_case(character.trim())
+        }) {
+            Some(caster_slots) => Ok(caster_slots),
+            None => Err(SpellSlotsError::UnknownCharacter(character.to_string())),
+        }
+    }
+
+    fn expend_slot(
+        &mut self,
+        character: &str,
+        level: SpellLevel,
+        pact: bool,
+    ) -> Result<CasterSlots, SpellSlotsError> {
+        self.update_caster_slots(character, |caster_slots, maximum| {
+            if pact {
+                return match maximum.pact {
+                    Some(pact_slots) if pact_slots.level != level => {
+                        Err(SpellSlotsError::NotPactSlotLevel(pact_slots.level))
+                    }
+                    Some(pact_slots) if caster_slots.pact_expended < pact_slots.count => {
+                        caster_slots.pact_expended += 1;
+                        Ok(())
+                    }
+                    _ => Err(SpellSlotsError::NoPactSlotRemaining),
+                };
+            }
+            if caster_slots.expended_at_level(level) >= maximum.at_level(level) {
+                return Err(SpellSlotsError::NoSlotRemaining(level));
+            }
+            caster_slots.expended[(level.value() - 1) as usize] += 1;
+            Ok(())
+        })
+    }
+
+    fn rest(&mut self, character: &str, rest: Rest) -> Result<CasterSlots, SpellSlotsError> {
+        self.update_caster_slots(character, |caster_slots, _| {
+            caster_slots.pact_expended = 0;
+            if rest == Rest::Long {
+                caster_slots.expended = Default::default();
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockSpellSlotsDataSource {
+        caster_slots: RefCell<Vec<CasterSlots>>,
+    }
+
+    impl SpellSlotsDataSourceInterface for MockSpellSlotsDataSource {
+        fn get_all_caster_slots(&self) -> Result<Vec<CasterSlots>, SpellSlotsDataSourceError> {
+            Ok(self.caster_slots.borrow().clone())
+        }
+
+        fn save_all_caster_slots(
+            &self,
+            caster_slots: &[CasterSlots],
+        ) -> Result<(), SpellSlotsDataSourceError> {
+            *self.caster_slots.borrow_mut() = caster_slots.to_vec();
+            Ok(())
+        }
+    }
+
+    fn usecase() -> SpellSlotsImplementation<MockSpellSlotsDataSource> {
+        SpellSlotsImplementation::new(MockSpellSlotsDataSource::default())
+    }
+
+    fn class_levels(class_levels: &[&str]) -> Vec<ClassLevel> {
+        class_levels
+            .iter()
+            .map(|class_level| class_level.parse().unwrap())
+            .collect()
+    }
+
+    fn slots(class_levels_str: &[&str]) -> [u8; 9] {
+        usecase()
+            .calculate_slots(&class_levels(class_levels_str))
+            .unwrap()
+            .slots
+    }
+
+    #[test]
+    fn test_single_class_slots() {
+        assert_eq!(slots(&["wizard:5"]), [4, 3, 2, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(slots(&["paladin:1"]), [0; 9]);
+        assert_eq!(slots(&["paladin:5"]), [4, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(slots(&["ranger:20"]), [4, 3, 3, 3, 2, 0, 0, 0, 0]);
+        assert_eq!(slots(&["eldritch-knight:3"]), [2, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(slots(&["arcane-trickster:7"]), [4, 2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(slots(&["fighter:5"]), [0; 9]);
+    }
+
+    #[test]
+    fn test_multiclass_slots() {
+        // caster level 3 + 2 + 1 = 6
+        assert_eq!(
+            slots(&["cleric:3", "paladin:4", "eldritch-knight:3"]),
+            [4, 3, 3, 0, 0, 0, 0, 0, 0]
+        );
+        // a single spellcasting class keeps its own progression
+        assert_eq!(
+            slots(&["paladin:5", "fighter:3"]),
+            [4, 2, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_pact_magic_is_separate() {
+        let slots = usecase()
+            .calculate_slots(&class_levels(&["sorcerer:3", "warlock:3"]))
+            .unwrap();
+        assert_eq!(slots.slots, [4, 2, 0, 0, 0, 0, 0, 0, 0]);
+        let pact = slots.pact.unwrap();
+        assert_eq!((pact.count, pact.level.value()), (2, 2));
+    }
+
+    #[test]
+    fn test_invalid_class_levels() {
+        let usecase = usecase();
+        assert!(matches!(
+            usecase.calculate_slots(&class_levels(&["necromancer:3"])),
+            Err(SpellSlotsError::UnknownClass(_))
+        ));
+        assert!(matches!(
+            usecase.calculate_slots(&class_levels(&["wizard:15", "cleric:6"])),
+            Err(SpellSlotsError::CharacterLevelTooHigh(21))
+        ));
+        assert!(matches!(
+            usecase.calculate_slots(&class_levels(&["wizard:1", "wizard:2"])),
+            Err(SpellSlotsError::DuplicateClass(_))
+        ));
+    }
+
+    #[test]
+    fn test_expend_and_rest() {
+        let mut usecase = usecase();
+        let first = SpellLevel::new(1).unwrap();
+        usecase
+            .set_class_levels(
+                "Fizban".to_string(),
+                class_levels(&["wizard:1", "warlock:1"]),
+            )
+            .unwrap();
+        usecase.expend_slot("fizban", first, false).unwrap();
+        usecase.expend_slot("fizban", first, false).unwrap();
+        assert!(matches!(
+            usecase.expend_slot("fizban", first, false),
+            Err(SpellSlotsError::NoSlotRemaining(_))
+        ));
+        assert!(matches!(
+            usecase.expend_slot("fizban", SpellLevel::new(2).unwrap(), true),
+            Err(SpellSlotsError::NotPactSlotLevel(level)) if level == first
+        ));
+        usecase.expend_slot("fizban", first, true).unwrap();
+        assert!(matches!(
+            usecase.expend_slot("fizban", first, true),
+            Err(SpellSlotsError::NoPactSlotRemaining)
+        ));
+
+        let caster_slots = usecase.rest("fizban", Rest::Short).unwrap();
+        assert_eq!(caster_slots.pact_expended, 0);
+        assert_eq!(caster_slots.expended_at_level(first), 2);
+
+        let caster_slots = usecase.rest("fizban", Rest::Long).unwrap();
+        assert_eq!(caster_slots.expended_at_level(first), 0);
+    }
+}
@@ -0,0 +1,425 @@
+use crate::core::entity::spell::{Spell, SpellLevel};
+use crate::core::entity::spellbook::{Spellbook, SpellbookEntry};
+use crate::datasources::common::local_file::LocalFileError;
+
+#[derive(Debug)]
+pub enum SpellbookDataSourceError {
+    FileError(LocalFileError),
+}
+
+pub trait SpellbookDataSourceInterface {
+    fn get_all_spellbooks(&self) -> Result<Vec<Spellbook>, SpellbookDataSourceError>;
+    fn save_all_spellbooks(&self, spellbooks: &[Spellbook])
+        -> Result<(), SpellbookDataSourceError>;
+}
+
+#[derive(Debug)]
+pub enum SpellbookError {
+    UnknownCharacter(String),
+    CharacterExists(String),
+    NotAClassSpell(String, String),
+    SpellLevelTooHigh(String, SpellLevel),
+    SpellAlreadyKnown(String),
+    SpellNotKnown(String),
+    CantripAlwaysPrepared(String),
+    PreparationLimitReached(usize),
+    DataSourceError(SpellbookDataSourceError),
+}
+
+pub trait SpellbookInterface {
+    fn create_spellbook(
+        &mut self,
+        character: String,
+        class: String,
+        max_spell_level: SpellLevel,
+        max_prepared: Option<usize>,
+    ) -> Result<Spellbook, SpellbookError>;
+
+    fn get_spellbook(&self, character: &str) -> Result<Spellbook, SpellbookError>;
+
+    fn get_all_spellbooks(&self) -> Result<Vec<Spellbook>, SpellbookError>;
+
+    /// Adds all spells or, if any of them is invalid, none
+    fn add_spells(
+        &mut self,
+        character: &str,
+        spells: &[Spell],
+    ) -> Result<Spellbook, SpellbookError>;
+
+    /// Removes all spells or, if any of them is not known, none
+    fn remove_spells(
+        &mut self,
+        character: &str,
+        names: &[String],
+    ) -> Result<Spellbook, SpellbookError>;
+
+    /// Prepares or unprepares all spells or, if any of them can't be, none
+    fn prepare_spells(
+        &mut self,
+        character: &str,
+        names: &[String],
+        prepared: bool,
+    ) -> Result<Spellbook, SpellbookError>;
+}
+
+pub struct SpellbookImplementation<T>
+where
+    T: SpellbookDataSourceInterface,
+{
+    datasource: T,
+}
+
+impl<T> SpellbookImplementation<T>
+where
+    T: SpellbookDataSourceInterface,
+{
+    pub fn new(datasource: T) -> Self {
+        SpellbookImplementation { datasource }
+    }
+
+    fn load(&self) -> Result<Vec<Spellbook>, SpellbookError> {
+        self.datasource
+            .get_all_spellbooks()
+            .map_err(SpellbookError::DataSourceError)
+    }
+
+    /// Applies `update` to the character's spellbook and persists all spellbooks, nothing is
+    /// persisted if `update` fails
+    fn update_spellbook<F>(
+        &mut self,
+        character: &str,
+        update: F,
+    ) -> Result<Spellbook, SpellbookError>
+    where
+        F: FnOnce(&mut Spellbook) -> Result<(), SpellbookError>,
+    {
+        let mut spellbooks = self.load()?;
+        let spellbook = match spellbooks
+            .iter_mut()
+            .find(|spellbook| spellbook.character.eq_ignore_ascii_case(character.trim()))
+        {
+            Some(spellbook) => spellbook,
+            None => return Err(SpellbookError::UnknownCharacter(character.to_string())),
+        };
+        update(spellbook)?;
+        let updated = spellbook.clone();
+        self.datasource
+            .save_all_spellbooks(&spellbooks)
+            .map_err(SpellbookError::DataSourceError)?;
+        Ok(updated)
+    }
+
+    fn validate_spell(spellbook: &Spellbook, spell: &Spell) -> Result<(), SpellbookError> {
+        if !spell
+            .classes
+            .iter()
+            .any(|class| class.eq_ignore_ascii_case(&spellbook.class))
+        {
+            return Err(SpellbookError::NotAClassSpell(
+                spell.name.clone(),
+                spellbook.class.clone(),
+            ));
+        }
+        if spell.level > spellbook.max_spell_level {
+            return Err(SpellbookError::SpellLevelTooHigh(
+                spell.name.clone(),
+                spell.level,
+            ));
+        }
+        if spellbook.entry(&spell.name).is_some() {
+            return Err(SpellbookError::SpellAlreadyKnown(spell.name.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl<T> SpellbookInterface for SpellbookImplementation<T>
+where
+    T: SpellbookDataSourceInterface,
+{
+    fn create_spellbook(
+        &mut self,
+        character: String,
+        class: String,
+        max_spell_level: SpellLevel,
+        max_prepared: Option<usize>,
+    ) -> Result<Spellbook, SpellbookError> {
+        let mut spellbooks = self.load()?;
+        if spellbooks
+            .iter()
+            .any(|spellbook| spellbook.character.eq_ignore_ascii_case(character.trim()))
+        {
+            return Err(SpellbookError::CharacterExists(character));
+        }
+        let spellbook = Spellbook::new(character, class, max_spell_level, max_prepared);
+        spellbooks.push(spellbook.clone());
+        self.datasource
+            .save_all_spellbooks(&spellbooks)
+            .map_err(SpellbookError::DataSourceError)?;
+        Ok(spellbook)
+    }
+
+    fn get_spellbook(&self, character: &str) -> Result<Spellbook, SpellbookError> {
+        match self
+            .load()?
+            .into_iter()
+            .find(|spellbook| spellbook.character.eq_ignore_ascii_case(character.trim()))
+        {
+            Some(spellbook) => Ok(spellbook),
+            None => Err(SpellbookError::UnknownCharacter(character.to_string())),
+        }
+    }
+
+    fn get_all_spellbooks(&self) -> Result<Vec<Spellbook>, SpellbookError> {
+        self.load()
+    }
+
+    fn add_spells(
+        &mut self,
+        character: &str,
+        spells: &[Spell],
+    ) -> Result<Spellbook, SpellbookError> {
+        self.update_spellbook(character, |spellbook| {
+            for spell in spells {
+                Self::validate_spell(spellbook, spell)?;
+                spellbook.known.push(SpellbookEntry {
+                    name: spell.name.clone(),
+                    level: spell.level,
+                    prepared: false,
+                });
+            }
+            spellbook
+                .known
+                .sort_by(|a, b| (a.level, &a.name).cmp(&(b.level, &b.name)));
+            Ok(())
+        })
+    }
+
+    fn remove_spells(
+        &mut self,
+        character: &str,
+        names: &[String],
+    ) -> Result<Spellbook, SpellbookError> {
+        self.update_spellbook(character, |spellbook| {
+            for name in names {
+                let count = spellbook.known.len();
+                spellbook
+                    .known
+                    .retain(|entry| !entry.name.eq_ignore_ascii_case(name.trim()));
+                if spellbook.known.len() == count {
+                    return Err(SpellbookError::SpellNotKnown(name.to_string()));
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn prepare_spells(
+        &mut self,
+        character: &str,
+        names: &[String],
+        prepared: bool,
+    ) -> Result<Spellbook, SpellbookError> {
+        self.update_spellbook(character, |spellbook| {
+            for name in names {
+                let prepared_count = spellbook.prepared_count();
+                let max_prepared = spellbook.max_prepared;
+                let entry = match spellbook
+                    .known
+                    .iter_mut()
+                    .find(|entry| entry.name.eq_ignore_ascii_case(name.trim()))
+                {
+                    Some(entry) => entry,
+                    None => return Err(SpellbookError::SpellNotKnown(name.to_string())),
+                };
+                if entry.level.is_cantrip() {
+                    return Err(SpellbookError::CantripAlwaysPrepared(entry.name.clone()));
+                }
+                if prepared && !entry.prepared {
+                    if let Some(max_prepared) = max_prepared {
+                        if prepared_count >= max_prepared {
+                            return Err(SpellbookError::PreparationLimitReached(max_prepared));
+                        }
+                    }
+                }
+                entry.prepared = prepared;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+
+    #[derive(Default)]
+    struct MockSpellbookDataSource {
+        spellbooks: RefCell<Vec<Spellbook>>,
+        saves: Cell<usize>,
+    }
+
+    impl SpellbookDataSourceInterface for MockSpellbookDataSource {
+        fn get_all_spellbooks(&self) -> Result<Vec<Spellbook>, SpellbookDataSourceError> {
+            Ok(self.spellbooks.borrow().clone())
+        }
+
+        fn save_all_spellbooks(
+            &self,
+            spellbooks: &[Spellbook],
+        ) -> Result<(), SpellbookDataSourceError> {
+            *self.spellbooks.borrow_mut() = spellbooks.to_vec();
+            self.saves.set(self.saves.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn spell(name: &str, level: u8, classes: &[&str]) -> Spell {
+        Spell {
+            name: name.to_string(),
+            level: SpellLevel::new(level).unwrap(),
+            classes: classes.iter().map(|class| class.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn usecase() -> SpellbookImplementation<MockSpellbookDataSource> {
+        let mut usecase = SpellbookImplementation::new(MockSpellbookDataSource::default());
+        usecase
+            .create_spellbook(
+                "Elminster".to_string(),
+                "Wizard".to_string(),
+                SpellLevel::new(2).unwrap(),
+                Some(1),
+            )
+            .unwrap();
+        usecase
+    }
+
+    #[test]
+    fn test_create_duplicate_character_fails() {
+        let mut usecase = usecase();
+        let err = usecase
+            .create_spellbook(
+                "elminster".to_string(),
+                "Wizard".to_string(),
+                SpellLevel::CANTRIP,
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::CharacterExists(_)));
+    }
+
+    #[test]
+    fn test_add_and_remove_spell() {
+        let mut usecase = usecase();
+        let spellbook = usecase
+            .add_spells("elminster", &[spell("Shield", 1, &["Sorcerer", "Wizard"])])
+            .unwrap();
+        assert_eq!(spellbook.known.len(), 1);
+        let spellbook = usecase
+            .remove_spells("Elminster", &names(&["shield"]))
+            .unwrap();
+        assert!(spellbook.known.is_empty());
+        let err = usecase
+            .remove_spells("Elminster", &names(&["shield"]))
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::SpellNotKnown(_)));
+    }
+
+    #[test]
+    fn test_add_validates_class_and_level() {
+        let mut usecase = usecase();
+        let err = usecase
+            .add_spells("Elminster", &[spell("Cure Wounds", 1, &["Cleric"])])
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::NotAClassSpell(_, _)));
+        let err = usecase
+            .add_spells("Elminster", &[spell("Fireball", 3, &["Wizard"])])
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::SpellLevelTooHigh(_, _)));
+        let err = usecase
+            .add_spells("Mordenkainen", &[spell("Shield", 1, &["Wizard"])])
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::UnknownCharacter(_)));
+    }
+
+    #[test]
+    fn test_prepare_enforces_limit() {
+        let mut usecase = usecase();
+        usecase
+            .add_spells(
+                "Elminster",
+                &[
+                    spell("Fire Bolt", 0, &["Wizard"]),
+                    spell("Shield", 1, &["Wizard"]),
+                    spell("Shatter", 2, &["Wizard"]),
+                ],
+            )
+            .unwrap();
+        let spellbook = usecase
+            .prepare_spells("Elminster", &names(&["Shield"]), true)
+            .unwrap();
+        assert_eq!(spellbook.prepared_count(), 1);
+        let err = usecase
+            .prepare_spells("Elminster", &names(&["Shatter"]), true)
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::PreparationLimitReached(1)));
+        let err = usecase
+            .prepare_spells("Elminster", &names(&["Fire Bolt"]), true)
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::CantripAlwaysPrepared(_)));
+        usecase
+            .prepare_spells("Elminster", &names(&["Shield"]), false)
+            .unwrap();
+        let spellbook = usecase
+            .prepare_spells("Elminster", &names(&["Shatter"]), true)
+            .unwrap();
+        assert!(spellbook.entry("shatter").unwrap().prepared);
+    }
+
+    #[test]
+    fn test_batch_saves_once_or_not_at_all() {
+        let mut usecase = usecase();
+        assert_eq!(usecase.datasource.saves.get(), 1);
+        let spellbook = usecase
+            .add_spells(
+                "Elminster",
+                &[
+                    spell("Shield", 1, &["Wizard"]),
+                    spell("Shatter", 2, &["Wizard"]),
+                ],
+            )
+            .unwrap();
+        assert_eq!(spellbook.known.len(), 2);
+        assert_eq!(usecase.datasource.saves.get(), 2);
+
+        let err = usecase
+            .add_spells(
+                "Elminster",
+                &[
+                    spell("Fire Bolt", 0, &["Wizard"]),
+                    spell("Cure Wounds", 1, &["Cleric"]),
+                ],
+            )
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::NotAClassSpell(_, _)));
+        let err = usecase
+            .prepare_spells("Elminster", &names(&["Shield", "Shatter"]), true)
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::PreparationLimitReached(1)));
+        let err = usecase
+            .remove_spells("Elminster", &names(&["Shield", "Wish"]))
+            .unwrap_err();
+        assert!(matches!(err, SpellbookError::SpellNotKnown(_)));
+        assert_eq!(usecase.datasource.saves.get(), 2);
+        let spellbook = usecase.get_spellbook("Elminster").unwrap();
+        assert_eq!(spellbook.known.len(), 2);
+        assert_eq!(spellbook.prepared_count(), 0);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::core::entity::caster::CasterProfile;
+use crate::core::usecase::caster::{CasterDataSourceError, CasterDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CasterFile {
+    #[serde(default)]
+    casters: Vec<CasterProfile>,
+}
+
+pub struct CasterFileDataSource {
+    file: LocalFile,
+}
+
+impl CasterFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+}
+
+impl CasterDataSourceInterface for CasterFileDataSource {
+    fn get_all_casters(&self) -> Result<Vec<CasterProfile>, CasterDataSourceError> {
+        match self.file.read_or_default::<CasterFile>() {
+            Ok(content) => Ok(content.casters),
+            Err(err) => Err(CasterDataSourceError::FileError(err)),
+        }
+    }
+
+    fn save_all_casters(&self, casters: &[CasterProfile]) -> Result<(), CasterDataSourceError> {
+        let content = CasterFile {
+            casters: casters.to_vec(),
+        };
+        self.file
+            .write(&content)
+            .map_err(CasterDataSourceError::FileError)
+    }
+}
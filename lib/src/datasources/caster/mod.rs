@@ -0,0 +1 @@
+pub mod caster_datasource;
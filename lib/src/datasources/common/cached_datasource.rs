@@ -0,0 +1,140 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellFilter, SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::datasources::common::ttl_cache::TtlCache;
+use async_trait::async_trait;
+
+/// Keeps the result of another datasource for the configured `cache_time`
+///
+/// `V` is the value the wrapped datasource returns, each datasource interface gets its own
+/// implementation for `CachedDataSource`. Errors are never cached. Besides the full result the
+/// result of the latest filtered query is kept.
+pub struct CachedDataSource<T, V> {
+    datasource: T,
+    cache: TtlCache<V>,
+    matching: TtlCache<(SpellFilter, V)>,
+}
+
+impl<T, V> CachedDataSource<T, V>
+where
+    V: Clone,
+{
+    /// Wraps `datasource`, caching its result for `cache_time` milliseconds
+    pub fn new(datasource: T, cache_time: u64) -> Self {
+        Self {
+            datasource,
+            cache: TtlCache::from_millis(cache_time),
+            matching: TtlCache::from_millis(cache_time),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> SpellsDataSourceInterface for CachedDataSource<T, Vec<Spell>>
+where
+    T: SpellsDataSourceInterface,
+{
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        if let Some(spells) = self.cache.get() {
+            log::debug!("Using {} cached spells", spells.len());
+            return Ok(spells);
+        }
+        let spells = self.datasource.get_all_spells().await?;
+        self.cache.insert(spells.clone());
+        Ok(spells)
+    }
+
+    /// Serves all cached spells when there are any, otherwise the spells of the same filter
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        if let Some(spells) = self.cache.get() {
+            return Ok(spells);
+        }
+        if let Some((cached_filter, spells)) = self.matching.get() {
+            if cached_filter == *filter {
+                log::debug!("Using {} cached matching spells", spells.len());
+                return Ok(spells);
+            }
+        }
+        let spells = self.datasource.get_spells_matching(filter).await?;
+        self.matching.insert((filter.clone(), spells.clone()));
+        Ok(spells)
+    }
+
+    async fn revalidate(&self) -> Result<(), SpellsDataSourceError> {
+        self.datasource.revalidate().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::cell::Cell;
+
+    #[derive(Default)]
+    struct CountingDataSource {
+        calls: Cell<usize>,
+        fail: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for CountingDataSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            self.calls.set(self.calls.get() + 1);
+            match self.fail {
+                true => Err(SpellsDataSourceError::NoSpellsFound),
+                false => Ok(vec![Spell {
+                    index: "fireball".to_string(),
+                    ..Default::default()
+                }]),
+            }
+        }
+    }
+
+    #[test]
+    fn test_spells_are_fetched_once_within_cache_time() {
+        let cached = CachedDataSource::new(CountingDataSource::default(), 60_000);
+        for _ in 0..3 {
+            let spells = block_on(cached.get_all_spells()).unwrap();
+            assert_eq!(spells[0].index, "fireball");
+        }
+        assert_eq!(cached.datasource.calls.get(), 1);
+
+        let uncached = CachedDataSource::new(CountingDataSource::default(), 0);
+        block_on(uncached.get_all_spells()).unwrap();
+        block_on(uncached.get_all_spells()).unwrap();
+        assert_eq!(uncached.datasource.calls.get(), 2);
+    }
+
+    #[test]
+    fn test_matching_spells_are_cached_per_filter() {
+        let cached = CachedDataSource::new(CountingDataSource::default(), 60_000);
+        let wizard = SpellFilter {
+            classes: vec!["wizard".to_string()],
+            ..Default::default()
+        };
+        block_on(cached.get_spells_matching(&wizard)).unwrap();
+        block_on(cached.get_spells_matching(&wizard)).unwrap();
+        assert_eq!(cached.datasource.calls.get(), 1);
+        block_on(cached.get_spells_matching(&SpellFilter::default())).unwrap();
+        assert_eq!(cached.datasource.calls.get(), 2);
+
+        block_on(cached.get_all_spells()).unwrap();
+        block_on(cached.get_spells_matching(&wizard)).unwrap();
+        assert_eq!(cached.datasource.calls.get(), 3);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let failing = CountingDataSource {
+            fail: true,
+            ..Default::default()
+        };
+        let cached = CachedDataSource::new(failing, 60_000);
+        assert!(block_on(cached.get_all_spells()).is_err());
+        assert!(block_on(cached.get_all_spells()).is_err());
+        assert_eq!(cached.datasource.calls.get(), 2);
+    }
+}
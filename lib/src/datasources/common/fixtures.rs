@@ -0,0 +1,136 @@
+use crate::datasources::common::local_file::{LocalFile, LocalFileError};
+use graphql_client::{QueryBody, Response};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FixtureMode {
+    /// Query the API and capture each response into the fixture folder
+    Record,
+    /// Answer queries from the fixture folder without the network
+    Replay,
+}
+
+/// Folder of recorded GraphQL responses, one JSON file per operation and variables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixtures {
+    pub mode: FixtureMode,
+    pub dir: PathBuf,
+}
+
+impl Fixtures {
+    pub fn new<P: Into<PathBuf>>(mode: FixtureMode, dir: P) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+        }
+    }
+
+    /// `SpellsQuery.json` for a query without variables, otherwise the operation name with a
+    /// hash of the variables like `SpellsQuery-1f0c2a9e4b7d3c85.json`
+    pub fn file_name(operation_name: &str, variables: &Value) -> String {
+        let variables = Self::without_nulls(variables);
+        match &variables {
+            Value::Null => format!("{}.json", operation_name),
+            Value::Object(map) if map.is_empty() => format!("{}.json", operation_name),
+            variables => format!(
+                "{}-{:016x}.json",
+                operation_name,
+                Self::fnv1a(variables.to_string().as_bytes())
+            ),
+        }
+    }
+
+    /// Fixture of the operation and variables in `dir`, falling back to the fixture of the
+    /// operation without variables
+    pub fn find(dir: &Path, operation_name: &str, variables: &Value) -> Option<PathBuf> {
+        [
+            Self::file_name(operation_name, variables),
+            Self::file_name(operation_name, &Value::Null),
+        ]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+    }
+
+    pub fn replay<V: Serialize, Data: DeserializeOwned>(
+        &self,
+        query: &QueryBody<V>,
+    ) -> Result<Response<Data>, LocalFileError> {
+        let variables = serde_json::to_value(&query.variables).map_err(LocalFileError::Json)?;
+        let path = match Self::find(&self.dir, query.operation_name, &variables) {
+            Some(path) => path,
+            None => {
+                let name = Self::file_name(query.operation_name, &variables);
+                log::error!("No fixture '{}' in '{}'", name, self.dir.display());
+                self.dir.join(name)
+            }
+        };
+        log::debug!("Replaying '{}'", path.display());
+        LocalFile::new(path)?.read()
+    }
+
+    pub fn record<V: Serialize, Data: DeserializeOwned>(
+        &self,
+        query: &QueryBody<V>,
+        response: Value,
+    ) -> Result<Response<Data>, LocalFileError> {
+        let variables = serde_json::to_value(&query.variables).map_err(LocalFileError::Json)?;
+        let path = self
+            .dir
+            .join(Self::file_name(query.operation_name, &variables));
+        log::info!("Recording '{}'", path.display());
+        LocalFile::new(path)?.write(&response)?;
+        serde_json::from_value(response).map_err(LocalFileError::Json)
+    }
+
+    fn without_nulls(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(_, value)| !value.is_null())
+                    .map(|(key, value)| (key.clone(), Self::without_nulls(value)))
+                    .collect(),
+            ),
+            value => value.clone(),
+        }
+    }
+
+    /// FNV-1a, stable across Rust versions unlike the standard hasher
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_file_name_ignores_null_variables() {
+        assert_eq!(
+            Fixtures::file_name("ConditionsQuery", &json!({})),
+            "ConditionsQuery.json"
+        );
+        assert_eq!(
+            Fixtures::file_name("SpellsQuery", &json!({"level": null})),
+            "SpellsQuery.json"
+        );
+        let name = Fixtures::file_name("SpellsQuery", &json!({"limit": 0, "class": null}));
+        assert_eq!(
+            name,
+            Fixtures::file_name("SpellsQuery", &json!({"limit": 0}))
+        );
+        assert!(name.starts_with("SpellsQuery-") && name.ends_with(".json"));
+        assert_ne!(
+            name,
+            Fixtures::file_name("SpellsQuery", &json!({"limit": 1}))
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum LocalFileError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Yaml(serde_yaml::Error),
+    UnknownFormat(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalFileFormat {
+    Json,
+    Yaml,
+}
+
+/// A YAML or JSON file (chosen by extension) holding user data
+#[derive(Debug, Clone)]
+pub struct LocalFile {
+    path: PathBuf,
+    format: LocalFileFormat,
+}
+
+impl LocalFile {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, LocalFileError> {
+        let path = path.as_ref().to_path_buf();
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => LocalFileFormat::Json,
+            Some("yml") | Some("yaml") => LocalFileFormat::Yaml,
+            _ => return Err(LocalFileError::UnknownFormat(path)),
+        };
+        Ok(Self { path, format })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> LocalFileFormat {
+        self.format
+    }
+
+    pub fn read<T: DeserializeOwned>(&self) -> Result<T, LocalFileError> {
+        let content = fs::read_to_string(&self.path).map_err(LocalFileError::Io)?;
+        match self.format {
+            LocalFileFormat::Json => serde_json::from_str(&content).map_err(LocalFileError::Json),
+            LocalFileFormat::Yaml => serde_yaml::from_str(&content).map_err(LocalFileError::Yaml),
+        }
+    }
+
+    /// Reads the file, treating a missing file as the default value
+    pub fn read_or_default<T: DeserializeOwned + Default>(&self) -> Result<T, LocalFileError> {
+        match self.read() {
+            Err(LocalFileError::Io(err)) if err.kind() == ErrorKind::NotFound => Ok(T::default()),
+            result => result,
+        }
+    }
+
+    /// Writes the file through a temporary sibling so a failed write keeps the old content
+    pub fn write<T: Serialize>(&self, value: &T) -> Result<(), LocalFileError> {
+        let content = match self.format {
+            LocalFileFormat::Json => {
+                serde_json::to_string_pretty(value).map_err(LocalFileError::Json)?
+            }
+            LocalFileFormat::Yaml => serde_yaml::to_string(value).map_err(LocalFileError::Yaml)?,
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(LocalFileError::Io)?;
+        }
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, content).map_err(LocalFileError::Io)?;
+        fs::rename(&tmp_path, &self.path).map_err(LocalFileError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            LocalFile::new("a/b.json").unwrap().format(),
+            LocalFileFormat::Json
+        );
+        assert_eq!(
+            LocalFile::new("a/b.yml").unwrap().format(),
+            LocalFileFormat::Yaml
+        );
+        assert!(LocalFile::new("a/b.txt").is_err());
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["nested/data.yml", "nested/data.json"] {
+            let file = LocalFile::new(dir.path().join(name)).unwrap();
+            let value = BTreeMap::from([("key".to_string(), vec![1, 2])]);
+            file.write(&value).unwrap();
+            assert_eq!(file.read::<BTreeMap<String, Vec<u8>>>().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_read_or_default_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = LocalFile::new(dir.path().join("missing.yml")).unwrap();
+        assert!(file.read::<Vec<String>>().is_err());
+        assert_eq!(
+            file.read_or_default::<Vec<String>>().unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
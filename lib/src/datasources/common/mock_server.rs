@@ -0,0 +1,206 @@
+use crate::datasources::common::fixtures::Fixtures;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// Response of the mock server for a path
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    pub fn ok(body: &str) -> Self {
+        Self::status(200, body)
+    }
+
+    pub fn status(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+enum MockRoutes {
+    Paths(Mutex<HashMap<String, Vec<MockResponse>>>),
+    /// GraphQL responses recorded into a fixture folder, for any path
+    GraphQLFixtures(PathBuf),
+}
+
+impl MockRoutes {
+    fn respond(&self, path: &str, body: &[u8]) -> MockResponse {
+        match self {
+            MockRoutes::Paths(routes) => {
+                let mut routes = routes.lock().unwrap_or_else(PoisonError::into_inner);
+                match routes.get_mut(path) {
+                    Some(responses) if responses.len() > 1 => responses.remove(0),
+                    Some(responses) => responses[0].clone(),
+                    None => MockResponse::status(404, "{\"error\":\"Not found\"}"),
+                }
+            }
+            MockRoutes::GraphQLFixtures(dir) => {
+                let query = serde_json::from_slice::<Value>(body).unwrap_or_default();
+                let operation_name = query["operationName"].as_str().unwrap_or_default();
+                match Fixtures::find(dir, operation_name, &query["variables"]) {
+                    Some(fixture) => match std::fs::read_to_string(&fixture) {
+                        Ok(body) => MockResponse::ok(&body),
+                        Err(err) => MockResponse::status(500, &format!("{:?}", err.to_string())),
+                    },
+                    None => MockResponse::ok(&format!(
+                        "{{\"data\":null,\"errors\":[{{\"message\":\"No fixture for {}\"}}]}}",
+                        operation_name
+                    )),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockStats {
+    requests: AtomicUsize,
+    active: AtomicUsize,
+    max_active: AtomicUsize,
+    user_agent: Mutex<Option<String>>,
+}
+
+/// HTTP server on a random local port answering fixed responses by path, 404 otherwise, or
+/// GraphQL queries from recorded fixtures
+///
+/// Each connection is handled on its own thread and answered after `delay`, so tests can
+/// observe how many requests a datasource keeps in flight. A path can answer a sequence of
+/// responses, the last one is repeated.
+pub struct MockServer {
+    url: String,
+    stats: Arc<MockStats>,
+}
+
+impl MockServer {
+    pub fn start(routes: HashMap<String, MockResponse>, delay: Duration) -> Self {
+        let routes = routes
+            .into_iter()
+            .map(|(path, response)| (path, vec![response]))
+            .collect();
+        Self::start_with_sequences(routes, delay)
+    }
+
+    pub fn start_with_sequences(
+        routes: HashMap<String, Vec<MockResponse>>,
+        delay: Duration,
+    ) -> Self {
+        Self::serve(MockRoutes::Paths(Mutex::new(routes)), delay)
+    }
+
+    /// Answers GraphQL queries with the fixtures recorded in `dir`, see `Fixtures::find`
+    pub fn graphql_fixtures<P: Into<PathBuf>>(dir: P) -> Self {
+        Self::serve(MockRoutes::GraphQLFixtures(dir.into()), Duration::ZERO)
+    }
+
+    fn serve(routes: MockRoutes, delay: Duration) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let stats = Arc::new(MockStats::default());
+        let routes = Arc::new(routes);
+        let server_stats = stats.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = routes.clone();
+                let stats = server_stats.clone();
+                thread::spawn(move || Self::handle(stream, &routes, &stats, delay));
+            }
+        });
+        Self { url, stats }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn requests(&self) -> usize {
+        self.stats.requests.load(Ordering::SeqCst)
+    }
+
+    /// User-Agent header of the latest request
+    pub fn user_agent(&self) -> Option<String> {
+        self.stats
+            .user_agent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Highest number of requests handled at the same time
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.stats.max_active.load(Ordering::SeqCst)
+    }
+
+    fn handle(stream: TcpStream, routes: &MockRoutes, stats: &MockStats, delay: Duration) {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+        let path = request_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or("/")
+            .to_string();
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).unwrap_or(0) == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+                if name.eq_ignore_ascii_case("user-agent") {
+                    *stats
+                        .user_agent
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner) = Some(value.trim().to_string());
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        let _ = reader.read_exact(&mut body);
+
+        stats.requests.fetch_add(1, Ordering::SeqCst);
+        let active = stats.active.fetch_add(1, Ordering::SeqCst) + 1;
+        stats.max_active.fetch_max(active, Ordering::SeqCst);
+        thread::sleep(delay);
+        let response = routes.respond(&path, &body);
+        stats.active.fetch_sub(1, Ordering::SeqCst);
+
+        let mut stream = reader.into_inner();
+        let headers = response
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}\r\n", name, value))
+            .collect::<String>();
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+            response.status,
+            response.body.len(),
+            headers,
+            response.body
+        );
+    }
+}
@@ -1 +1,7 @@
+pub mod cached_datasource;
+pub mod fixtures;
+pub mod local_file;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
 pub mod remote_datasource;
+pub mod ttl_cache;
@@ -1,27 +1,258 @@
+use crate::datasources::common::fixtures::{FixtureMode, Fixtures};
+use crate::datasources::common::local_file::LocalFileError;
+use futures_timer::Delay;
 use graphql_client::{Error as GraphQLError, GraphQLQuery, Response};
 use log;
+use rand::Rng;
 use reqwest;
-use reqwest::{Error as ReqwestError, StatusCode};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Error as ReqwestError, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Longest wait between two attempts, also for the `Retry-After` of a response
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
 
 #[derive(Debug)]
 pub enum APIError {
-    GraphQL(GraphQLError),
+    /// Every error of a GraphQL response, with their locations and paths
+    GraphQL(Vec<GraphQLError>),
     Reqwest(ReqwestError),
     ResponseHttp(StatusCode, String),
+    /// The response body is not the expected JSON
+    Json(serde_json::Error),
+    /// A fixture could not be recorded or replayed
+    Fixture(LocalFileError),
+}
+
+impl Display for APIError {
+    /// One line per GraphQL error, as `path:line:column: message`
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            APIError::GraphQL(errors) => {
+                let lines = errors
+                    .iter()
+                    .map(|error| format!("GraphQL error at {}", error))
+                    .collect::<Vec<String>>();
+                write!(f, "{}", lines.join("\n"))
+            }
+            APIError::Reqwest(err) => write!(f, "Request failed: {}", err),
+            APIError::ResponseHttp(status, reason) => {
+                write!(f, "API responded {} {}", status.as_u16(), reason)
+            }
+            APIError::Json(err) => write!(f, "Invalid response: {}", err),
+            APIError::Fixture(err) => write!(f, "Fixture failed: {:?}", err),
+        }
+    }
+}
+
+impl std::error::Error for APIError {}
+
+/// Data of a GraphQL response along with the errors the API reported for parts of it
+///
+/// Fields that failed are `null` in partial data, callers decide whether that is acceptable.
+#[derive(Debug)]
+pub struct GraphQLResponse<Data> {
+    pub data: Data,
+    pub errors: Vec<GraphQLError>,
+}
+
+impl<Data> GraphQLResponse<Data> {
+    pub fn is_partial(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// The data if the API reported no errors
+    pub fn complete(self) -> Result<Data, APIError> {
+        match self.is_partial() {
+            true => Err(APIError::GraphQL(self.errors)),
+            false => Ok(self.data),
+        }
+    }
+}
+
+/// Timeouts, retries, User-Agent and fixtures of the API clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientOptions {
+    pub connect_timeout: Duration,
+    /// Time for a whole request, from connecting until the body is read
+    pub timeout: Duration,
+    /// Retries after network errors, 429 and 5xx responses
+    pub retries: u32,
+    /// Delay before the first retry, doubled for each further retry
+    pub retry_backoff: Duration,
+    pub user_agent: String,
+    /// Records GraphQL responses to or replays them from fixture files
+    pub fixtures: Option<Fixtures>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(30),
+            retries: 3,
+            retry_backoff: Duration::from_millis(500),
+            user_agent: format!("dnd-rs/{}", env!("CARGO_PKG_VERSION")),
+            fixtures: None,
+        }
+    }
+}
+
+/// Status and body of a response, the body is read by `HttpClient::send` to retry failed reads
+struct HttpResponse {
+    status: StatusCode,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn json<De: DeserializeOwned>(&self) -> Result<De, APIError> {
+        serde_json::from_slice(&self.body).map_err(APIError::Json)
+    }
+}
+
+/// `reqwest::Client` shared by all requests of an API, so connections are reused
+#[derive(Debug)]
+struct HttpClient {
+    client: reqwest::Client,
+    options: ClientOptions,
+}
+
+impl HttpClient {
+    fn new(options: ClientOptions) -> Self {
+        Self {
+            client: Self::build_client(&options),
+            options,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_client(options: &ClientOptions) -> reqwest::Client {
+        reqwest::Client::builder()
+            .connect_timeout(options.connect_timeout)
+            .timeout(options.timeout)
+            .user_agent(options.user_agent.clone())
+            .build()
+            .unwrap_or_else(|err| {
+                log::error!("Falling back to the default client: {err}");
+                reqwest::Client::new()
+            })
+    }
+
+    /// The browser sets timeouts and the User-Agent of the web app
+    #[cfg(target_arch = "wasm32")]
+    fn build_client(_options: &ClientOptions) -> reqwest::Client {
+        reqwest::Client::new()
+    }
+
+    fn is_retryable_status(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+    }
+
+    /// Errors sending a request or reading its response that may pass on another attempt
+    fn is_retryable_error(err: &ReqwestError) -> bool {
+        err.is_timeout() || err.is_connect() || err.is_request() || err.is_body()
+    }
+
+    fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+        Self::parse_retry_after(response.headers().get(RETRY_AFTER)?.to_str().ok()?)
+    }
+
+    /// Delay of a `Retry-After` value in seconds or as HTTP date, at most `MAX_RETRY_DELAY`
+    ///
+    /// A date in the past is no delay.
+    fn parse_retry_after(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        let delay = match value.parse::<u64>() {
+            Ok(seconds) => Duration::from_secs(seconds),
+            Err(_) => {
+                let date = httpdate::parse_http_date(value)
+                    .ok()?
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .ok()?;
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default();
+                date.saturating_sub(now)
+            }
+        };
+        Some(delay.min(MAX_RETRY_DELAY))
+    }
+
+    /// Exponential backoff with jitter, between half and all of the doubled delay
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .options
+            .retry_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_RETRY_DELAY);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    /// Sends the request built by `request` and reads the response, retrying network errors,
+    /// failed reads, 429 and 5xx responses
+    ///
+    /// The response of the last attempt is returned whatever its status.
+    async fn send(
+        &self,
+        request: impl Fn(&reqwest::Client) -> RequestBuilder,
+    ) -> Result<HttpResponse, APIError> {
+        let mut attempt = 0;
+        loop {
+            let retry = attempt < self.options.retries;
+            let (retry_after, reason) = match request(&self.client).send().await {
+                Ok(response) if retry && Self::is_retryable_status(response.status()) => {
+                    (Self::retry_after(&response), response.status().to_string())
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    match response.bytes().await {
+                        Ok(body) => {
+                            return Ok(HttpResponse {
+                                status,
+                                body: body.to_vec(),
+                            })
+                        }
+                        Err(err) if retry && Self::is_retryable_error(&err) => {
+                            (None, err.to_string())
+                        }
+                        Err(err) => return Err(APIError::Reqwest(err)),
+                    }
+                }
+                Err(err) if retry && Self::is_retryable_error(&err) => (None, err.to_string()),
+                Err(err) => return Err(APIError::Reqwest(err)),
+            };
+            let delay = retry_after.unwrap_or_else(|| self.backoff(attempt));
+            log::warn!("Retrying in {:?}: {}", delay, reason);
+            Delay::new(delay).await;
+            attempt += 1;
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct GraphQLAPI {
     api_url: String,
+    client: HttpClient,
 }
 
 impl GraphQLAPI {
     pub fn new(api_url: String) -> Self {
+        Self::with_options(api_url, ClientOptions::default())
+    }
+
+    pub fn with_options(api_url: String, options: ClientOptions) -> Self {
         log::debug!("Routing API calls to '{}'", api_url);
 
-        Self { api_url }
+        Self {
+            api_url,
+            client: HttpClient::new(options),
+        }
     }
+    /// Data of a response without errors, partial data is an error
     pub async fn get_response_data<
         Ser: Serialize,
         De: for<'de> Deserialize<'de>,
@@ -30,59 +261,346 @@ impl GraphQLAPI {
         &self,
         variables: Ser,
     ) -> Result<Query::ResponseData, APIError> {
-        let client = reqwest::Client::new();
+        self.get_response::<Ser, De, Query>(variables)
+            .await?
+            .complete()
+    }
+
+    /// Data of a response with all reported errors, an error only if there is no data
+    pub async fn get_response<
+        Ser: Serialize,
+        De: for<'de> Deserialize<'de>,
+        Query: GraphQLQuery<Variables = Ser, ResponseData = De>,
+    >(
+        &self,
+        variables: Ser,
+    ) -> Result<GraphQLResponse<Query::ResponseData>, APIError> {
         let request_body = <Query>::build_query(variables);
 
         log::debug!("Query Body: {}", request_body.query);
 
-        let request_builder = client.post(&self.api_url).json(&request_body);
-        log::debug!("Built Request: {request_builder:?}");
+        let fixtures = self.client.options.fixtures.as_ref();
+        if let Some(fixtures) = fixtures.filter(|fixtures| fixtures.mode == FixtureMode::Replay) {
+            let response_body = fixtures.replay(&request_body).map_err(APIError::Fixture)?;
+            return Self::response_data(response_body);
+        }
 
-        let response = match request_builder.send().await {
+        let response = match self
+            .client
+            .send(|client| client.post(&self.api_url).json(&request_body))
+            .await
+        {
             Ok(res) => res,
             Err(err) => {
-                log::error!("{err}");
-                return Err(APIError::Reqwest(err));
+                log::error!("{:?}", err);
+                return Err(err);
+            }
+        };
+
+        log::debug!("Got response: {}", response.status);
+
+        let status = response.status;
+        let canonical_reason = status.canonical_reason().unwrap_or("Unknown").to_string();
+        if status != StatusCode::OK {
+            return Err(APIError::ResponseHttp(status, canonical_reason));
+        }
+        let response_body: Response<Query::ResponseData> = match fixtures {
+            Some(fixtures) => {
+                let response_body = response.json()?;
+                fixtures
+                    .record(&request_body, response_body)
+                    .map_err(APIError::Fixture)?
             }
+            None => response.json()?,
         };
+        Self::response_data(response_body)
+    }
+
+    fn response_data<Data>(
+        response_body: Response<Data>,
+    ) -> Result<GraphQLResponse<Data>, APIError> {
+        let errors = response_body.errors.unwrap_or_default();
+        match response_body.data {
+            Some(data) => Ok(GraphQLResponse { data, errors }),
+            None if errors.is_empty() => Err(APIError::GraphQL(vec![GraphQLError {
+                message: "No data and no errors in response".to_owned(),
+                extensions: None,
+                locations: None,
+                path: None,
+            }])),
+            None => Err(APIError::GraphQL(errors)),
+        }
+    }
+}
+
+/// Client of a JSON REST API, the base URL is prepended to the requested paths
+#[derive(Debug)]
+pub struct RestAPI {
+    base_url: String,
+    client: HttpClient,
+}
+
+impl RestAPI {
+    pub fn new(base_url: String) -> Self {
+        Self::with_options(base_url, ClientOptions::default())
+    }
+
+    pub fn with_options(base_url: String, options: ClientOptions) -> Self {
+        log::debug!("Routing REST calls to '{}'", base_url);
+
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: HttpClient::new(options),
+        }
+    }
 
-        log::debug!("Got response: {:?}", response);
+    pub async fn get_json<De: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+    ) -> Result<De, APIError> {
+        let url = format!("{}{}", self.base_url, path);
+        log::debug!("GET {}", url);
+
+        let response = match self.client.send(|client| client.get(&url)).await {
+            Ok(res) => res,
+            Err(err) => {
+                log::error!("{:?}", err);
+                return Err(err);
+            }
+        };
 
-        let status = response.status();
+        let status = response.status;
         let canonical_reason = status.canonical_reason().unwrap_or("Unknown").to_string();
         if status != StatusCode::OK {
             return Err(APIError::ResponseHttp(status, canonical_reason));
         }
-        let response_body: Response<Query::ResponseData> = match response.json().await {
-            Ok(response_body) => response_body,
-            Err(err) => return Err(APIError::Reqwest(err)),
-            // Err(err) => return Err(APIError::CustomError(format!("Error in response: {}", err))),
+        response.json::<De>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasources::common::mock_server::{MockResponse, MockServer};
+    use crate::datasources::queries::conditions_query::{conditions_query, ConditionsQuery};
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const CONDITIONS: &str =
+        r#"{"data":{"conditions":[{"index":"blinded","name":"Blinded","desc":[]}]}}"#;
+
+    fn options() -> ClientOptions {
+        ClientOptions {
+            retries: 2,
+            retry_backoff: Duration::from_millis(1),
+            user_agent: "dnd-test".to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn get_conditions(api: &GraphQLAPI) -> Result<conditions_query::ResponseData, APIError> {
+        api.get_response_data::<_, _, ConditionsQuery>(conditions_query::Variables {})
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_retries_unavailable_and_rate_limited_responses() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/graphql".to_string(),
+            vec![
+                MockResponse::status(503, "{}"),
+                MockResponse::status(429, "{}").header("Retry-After", "0"),
+                MockResponse::ok(CONDITIONS),
+            ],
+        );
+        let server = MockServer::start_with_sequences(routes, Duration::ZERO);
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), options());
+        let data = get_conditions(&api).await.unwrap();
+        assert_eq!(data.conditions[0].index.as_deref(), Some("blinded"));
+        assert_eq!(server.requests(), 3);
+        assert_eq!(server.user_agent().as_deref(), Some("dnd-test"));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_retries() {
+        let mut routes = HashMap::new();
+        routes.insert("/graphql".to_string(), MockResponse::status(502, "{}"));
+        routes.insert("/bad".to_string(), MockResponse::status(400, "{}"));
+        let server = MockServer::start(routes, Duration::ZERO);
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), options());
+        let err = get_conditions(&api).await.unwrap_err();
+        assert!(matches!(
+            err,
+            APIError::ResponseHttp(StatusCode::BAD_GATEWAY, _)
+        ));
+        assert_eq!(server.requests(), 3);
+
+        let api = GraphQLAPI::with_options(format!("{}/bad", server.url()), options());
+        let err = get_conditions(&api).await.unwrap_err();
+        assert!(matches!(
+            err,
+            APIError::ResponseHttp(StatusCode::BAD_REQUEST, _)
+        ));
+        assert_eq!(server.requests(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_retries_timeouts() {
+        let mut routes = HashMap::new();
+        routes.insert("/graphql".to_string(), MockResponse::ok(CONDITIONS));
+        let server = MockServer::start(routes, Duration::from_millis(300));
+        let options = ClientOptions {
+            timeout: Duration::from_millis(50),
+            retries: 1,
+            ..options()
         };
-        let data: Query::ResponseData = match response_body.data {
-            Some(data) => data,
-            None => {
-                return Err(APIError::GraphQL(
-                    // in case of multiple errors reported, pick first
-                    match response_body.errors {
-                        Some(errors) => match errors.first() {
-                            Some(error) => error.clone(),
-                            None => GraphQLError {
-                                message: "No error found in response".to_owned(),
-                                extensions: None,
-                                locations: None,
-                                path: None,
-                            },
-                        },
-                        None => GraphQLError {
-                            message: "No errors reported".to_owned(),
-                            extensions: None,
-                            locations: None,
-                            path: None,
-                        },
-                    },
-                ));
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), options);
+        let err = get_conditions(&api).await.unwrap_err();
+        assert!(matches!(err, APIError::Reqwest(err) if err.is_timeout()));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retries_truncated_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for (i, stream) in listener.incoming().flatten().enumerate() {
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                // The first response ends before its announced length
+                let length = CONDITIONS.len() + usize::from(i == 0) * 10;
+                let _ = write!(
+                    reader.into_inner(),
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    length,
+                    CONDITIONS
+                );
             }
+        });
+        let api = GraphQLAPI::with_options(url, options());
+        let data = get_conditions(&api).await.unwrap();
+        assert_eq!(data.conditions[0].index.as_deref(), Some("blinded"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(
+            HttpClient::parse_retry_after(" 5 "),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(HttpClient::parse_retry_after("3600"), Some(MAX_RETRY_DELAY));
+        assert_eq!(
+            HttpClient::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let in_a_minute =
+            httpdate::fmt_http_date(std::time::SystemTime::now() + Duration::from_secs(60));
+        assert_eq!(
+            HttpClient::parse_retry_after(&in_a_minute),
+            Some(MAX_RETRY_DELAY)
+        );
+        let soon = httpdate::fmt_http_date(std::time::SystemTime::now() + Duration::from_secs(10));
+        let delay = HttpClient::parse_retry_after(&soon).unwrap();
+        assert!(delay > Duration::from_secs(8) && delay <= Duration::from_secs(10));
+        assert_eq!(HttpClient::parse_retry_after("soon"), None);
+    }
+
+    const PARTIAL_CONDITIONS: &str = r#"{
+        "data": {"conditions": [{"index": "blinded", "name": null, "desc": []}]},
+        "errors": [
+            {"message": "Name unavailable", "locations": [{"line": 4, "column": 5}], "path": ["conditions", 0, "name"]},
+            {"message": "Rate limited field"}
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn test_partial_data_with_all_errors() {
+        let mut routes = HashMap::new();
+        routes.insert("/graphql".to_string(), MockResponse::ok(PARTIAL_CONDITIONS));
+        routes.insert(
+            "/failed".to_string(),
+            MockResponse::ok(r#"{"data": null, "errors": [{"message": "a"}, {"message": "b"}]}"#),
+        );
+        let server = MockServer::start(routes, Duration::ZERO);
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), options());
+
+        let response = api
+            .get_response::<_, _, ConditionsQuery>(conditions_query::Variables {})
+            .await
+            .unwrap();
+        assert!(response.is_partial());
+        assert_eq!(
+            response.data.conditions[0].index.as_deref(),
+            Some("blinded")
+        );
+        assert_eq!(response.errors.len(), 2);
+
+        let err = get_conditions(&api).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GraphQL error at conditions/0/name:4:5: Name unavailable\n\
+             GraphQL error at <query>:0:0: Rate limited field"
+        );
+
+        let api = GraphQLAPI::with_options(format!("{}/failed", server.url()), options());
+        let err = get_conditions(&api).await.unwrap_err();
+        assert!(matches!(err, APIError::GraphQL(errors) if errors.len() == 2));
+    }
+
+    #[tokio::test]
+    async fn test_records_and_replays_fixtures() {
+        let mut routes = HashMap::new();
+        routes.insert("/graphql".to_string(), MockResponse::ok(CONDITIONS));
+        let server = MockServer::start(routes, Duration::ZERO);
+        let dir = tempfile::tempdir().unwrap();
+
+        let record = ClientOptions {
+            fixtures: Some(Fixtures::new(FixtureMode::Record, dir.path())),
+            ..options()
+        };
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), record);
+        get_conditions(&api).await.unwrap();
+        assert!(dir.path().join("ConditionsQuery.json").is_file());
+
+        let replay = ClientOptions {
+            fixtures: Some(Fixtures::new(FixtureMode::Replay, dir.path())),
+            ..options()
         };
-        Ok(data)
+        let api = GraphQLAPI::with_options(format!("{}/graphql", server.url()), replay);
+        let data = get_conditions(&api).await.unwrap();
+        assert_eq!(data.conditions[0].index.as_deref(), Some("blinded"));
+        assert_eq!(server.requests(), 1);
+
+        let fixtures = MockServer::graphql_fixtures(dir.path());
+        let api = GraphQLAPI::with_options(format!("{}/graphql", fixtures.url()), options());
+        let data = get_conditions(&api).await.unwrap();
+        assert_eq!(data.conditions[0].name.as_deref(), Some("Blinded"));
+    }
+
+    #[test]
+    fn test_backoff_doubles_with_jitter() {
+        let client = HttpClient::new(ClientOptions {
+            retry_backoff: Duration::from_millis(100),
+            ..Default::default()
+        });
+        for attempt in 0..3 {
+            let full = Duration::from_millis(100 * 2u64.pow(attempt));
+            let delay = client.backoff(attempt);
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
+        }
+        assert!(client.backoff(20) <= MAX_RETRY_DELAY);
     }
 }
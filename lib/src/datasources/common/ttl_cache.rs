@@ -0,0 +1,69 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+// std's Instant panics on wasm32-unknown-unknown, where the ui runs
+use web_time::Instant;
+
+/// Single value kept for a time to live, a zero time to live disables caching
+///
+/// The value sits behind a mutex so that datasources can cache from `&self`.
+pub struct TtlCache<V> {
+    ttl: Duration,
+    entry: Mutex<Option<(Instant, V)>>,
+}
+
+impl<V> TtlCache<V>
+where
+    V: Clone,
+{
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Mutex::new(None),
+        }
+    }
+
+    pub fn from_millis(ttl: u64) -> Self {
+        Self::new(Duration::from_millis(ttl))
+    }
+
+    /// The cached value if it was inserted less than the time to live ago
+    pub fn get(&self) -> Option<V> {
+        let entry = self.entry.lock().unwrap_or_else(PoisonError::into_inner);
+        match &*entry {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&self, value: V) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entry = self.entry.lock().unwrap_or_else(PoisonError::into_inner);
+        *entry = Some((Instant::now(), value));
+    }
+
+    pub fn clear(&self) {
+        let mut entry = self.entry.lock().unwrap_or_else(PoisonError::into_inner);
+        *entry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_recent_and_expired() {
+        let cache = TtlCache::<Vec<String>>::from_millis(20);
+        assert_eq!(cache.get(), None);
+        cache.insert(vec!["fireball".to_string()]);
+        assert_eq!(cache.get(), Some(vec!["fireball".to_string()]));
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(cache.get(), None);
+
+        let disabled = TtlCache::<u8>::from_millis(0);
+        disabled.insert(1);
+        assert_eq!(disabled.get(), None);
+    }
+}
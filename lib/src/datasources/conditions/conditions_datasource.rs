@@ -0,0 +1,92 @@
+use crate::core::entity::reference::{Reference, ReferenceKind};
+use crate::core::entity::spell::index_from_name;
+use crate::core::usecase::reference::{ConditionsDataSourceError, ConditionsDataSourceInterface};
+use crate::datasources::common::remote_datasource::GraphQLAPI;
+use crate::datasources::queries::conditions_query::conditions_query::{
+    ConditionsQueryConditions, ResponseData, Variables,
+};
+use crate::datasources::queries::conditions_query::ConditionsQuery;
+use async_trait::async_trait;
+
+pub struct ConditionsGraphQLDataSource {
+    api: GraphQLAPI,
+}
+
+impl ConditionsGraphQLDataSource {
+    pub fn new(api: GraphQLAPI) -> Self {
+        Self { api }
+    }
+
+    /// Converts a queried condition, conditions without a name or index are skipped
+    fn reference_from_conditions_query_conditions(
+        condition: ConditionsQueryConditions,
+    ) -> Option<Reference> {
+        let name = condition.name.or_else(|| condition.index.clone())?;
+        let index = condition.index.unwrap_or_else(|| index_from_name(&name));
+        let desc = condition
+            .desc
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>();
+        Some(Reference {
+            kind: ReferenceKind::Condition,
+            index,
+            name,
+            desc,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl ConditionsDataSourceInterface for ConditionsGraphQLDataSource {
+    /// Partial data is used, conditions only add cross references to spells
+    async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+        let response = self
+            .api
+            .get_response::<Variables, ResponseData, ConditionsQuery>(Variables {})
+            .await
+            .map_err(ConditionsDataSourceError::GraphQLError)?;
+        for error in &response.errors {
+            log::warn!("Using partial conditions, GraphQL error at {}", error);
+        }
+        Ok(response
+            .data
+            .conditions
+            .into_iter()
+            .filter_map(Self::reference_from_conditions_query_conditions)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_from_condition() {
+        let condition = ConditionsQueryConditions {
+            index: None,
+            name: Some("Frightened".to_string()),
+            desc: Some(vec![
+                Some("- A frightened creature has disadvantage.".to_string()),
+                None,
+            ]),
+        };
+        let reference =
+            ConditionsGraphQLDataSource::reference_from_conditions_query_conditions(condition)
+                .unwrap();
+        assert_eq!(reference.index, "frightened");
+        assert_eq!(reference.kind, ReferenceKind::Condition);
+        assert_eq!(reference.desc.len(), 1);
+        let unnamed = ConditionsQueryConditions {
+            index: None,
+            name: None,
+            desc: None,
+        };
+        assert!(
+            ConditionsGraphQLDataSource::reference_from_conditions_query_conditions(unnamed)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod conditions_datasource;
+pub mod persistent_datasource;
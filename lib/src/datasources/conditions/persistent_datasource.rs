@@ -0,0 +1,234 @@
+use crate::core::entity::reference::Reference;
+use crate::core::usecase::reference::{ConditionsDataSourceError, ConditionsDataSourceInterface};
+use crate::datasources::common::local_file::{LocalFile, LocalFileError};
+use crate::datasources::spells::persistent_datasource::SpellCacheMetadata;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use std::io::ErrorKind;
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the cached conditions format, caches written with another version are refetched
+pub const CONDITIONS_CACHE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConditionsCacheFile {
+    metadata: SpellCacheMetadata,
+    conditions: Vec<Reference>,
+}
+
+/// Persists the conditions of another datasource to disk, next to the persisted spells
+///
+/// Online, a cache younger than `max_age` is served without fetching, an older one is refetched
+/// and still served when the fetch fails. Offline, only the cache is read. Without a file the
+/// conditions are passed through.
+pub struct PersistentConditionsDataSource<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    datasource: T,
+    file: Option<LocalFile>,
+    source: String,
+    max_age: Duration,
+    offline: bool,
+}
+
+impl<T> PersistentConditionsDataSource<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    pub fn new(
+        datasource: T,
+        file: Option<LocalFile>,
+        source: String,
+        max_age: Duration,
+        offline: bool,
+    ) -> Self {
+        Self {
+            datasource,
+            file,
+            source,
+            max_age,
+            offline,
+        }
+    }
+
+    /// The cache when it exists and matches the source and schema version
+    fn read_cache(&self) -> Option<ConditionsCacheFile> {
+        let file = self.file.as_ref()?;
+        let cache = match file.read::<ConditionsCacheFile>() {
+            Ok(cache) => cache,
+            Err(LocalFileError::Io(err)) if err.kind() == ErrorKind::NotFound => return None,
+            Err(err) => {
+                log::warn!(
+                    "Ignoring unreadable conditions cache '{}': {:?}",
+                    file.path().display(),
+                    err
+                );
+                return None;
+            }
+        };
+        match cache.metadata {
+            SpellCacheMetadata { schema_version, .. }
+                if schema_version != CONDITIONS_CACHE_SCHEMA_VERSION =>
+            {
+                log::info!(
+                    "Ignoring conditions cache of schema version {}",
+                    schema_version
+                );
+                None
+            }
+            SpellCacheMetadata { ref source, .. } if *source != self.source => {
+                log::info!("Ignoring conditions cache of '{}'", source);
+                None
+            }
+            _ => Some(cache),
+        }
+    }
+
+    fn write_cache(&self, conditions: &[Reference]) {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return,
+        };
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let cache = ConditionsCacheFile {
+            metadata: SpellCacheMetadata {
+                fetched_at,
+                source: self.source.clone(),
+                schema_version: CONDITIONS_CACHE_SCHEMA_VERSION,
+            },
+            conditions: conditions.to_vec(),
+        };
+        if let Err(err) = file.write(&cache) {
+            log::warn!(
+                "Could not write conditions cache '{}': {:?}",
+                file.path().display(),
+                err
+            );
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> ConditionsDataSourceInterface for PersistentConditionsDataSource<T>
+where
+    T: ConditionsDataSourceInterface,
+{
+    async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+        if self.file.is_none() && !self.offline {
+            return self.datasource.get_all_conditions().await;
+        }
+        let cache = self.read_cache();
+        if self.offline {
+            return match cache {
+                Some(cache) => Ok(cache.conditions),
+                None => Err(ConditionsDataSourceError::NotCached),
+            };
+        }
+        let cache = match cache {
+            Some(cache) if cache.metadata.age() < self.max_age => {
+                log::debug!("Using conditions cached at {}", cache.metadata.fetched_at);
+                return Ok(cache.conditions);
+            }
+            cache => cache,
+        };
+        match (self.datasource.get_all_conditions().await, cache) {
+            (Ok(conditions), _) => {
+                self.write_cache(&conditions);
+                Ok(conditions)
+            }
+            (Err(err), Some(cache)) => {
+                log::warn!("Serving stale conditions, the refetch failed: {:?}", err);
+                Ok(cache.conditions)
+            }
+            (Err(err), None) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::reference::ReferenceKind;
+    use futures::executor::block_on;
+    use std::cell::Cell;
+
+    struct MockConditionsDataSource {
+        calls: Cell<usize>,
+        online: bool,
+    }
+
+    #[async_trait(?Send)]
+    impl ConditionsDataSourceInterface for MockConditionsDataSource {
+        async fn get_all_conditions(&self) -> Result<Vec<Reference>, ConditionsDataSourceError> {
+            self.calls.set(self.calls.get() + 1);
+            if !self.online {
+                return Err(ConditionsDataSourceError::NotCached);
+            }
+            Ok(vec![Reference {
+                kind: ReferenceKind::Condition,
+                index: "frightened".to_string(),
+                name: "Frightened".to_string(),
+                desc: vec!["- A frightened creature has disadvantage.".to_string()],
+            }])
+        }
+    }
+
+    fn persistent(
+        dir: &tempfile::TempDir,
+        online: bool,
+        max_age: Duration,
+        offline: bool,
+    ) -> PersistentConditionsDataSource<MockConditionsDataSource> {
+        let file = LocalFile::new(dir.path().join("conditions.json")).ok();
+        PersistentConditionsDataSource::new(
+            MockConditionsDataSource {
+                calls: Cell::new(0),
+                online,
+            },
+            file,
+            "https://example.com/graphql".to_string(),
+            max_age,
+            offline,
+        )
+    }
+
+    #[test]
+    fn test_offline_serves_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let offline = persistent(&dir, true, Duration::ZERO, true);
+        let err = block_on(offline.get_all_conditions()).unwrap_err();
+        assert!(matches!(err, ConditionsDataSourceError::NotCached));
+
+        block_on(persistent(&dir, true, Duration::ZERO, false).get_all_conditions()).unwrap();
+
+        let conditions = block_on(offline.get_all_conditions()).unwrap();
+        assert_eq!(conditions[0].name, "Frightened");
+        assert_eq!(offline.datasource.calls.get(), 0);
+    }
+
+    #[test]
+    fn test_fresh_and_stale_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        block_on(persistent(&dir, true, Duration::ZERO, false).get_all_conditions()).unwrap();
+
+        let fresh = persistent(&dir, false, Duration::from_secs(3600), false);
+        block_on(fresh.get_all_conditions()).unwrap();
+        assert_eq!(fresh.datasource.calls.get(), 0);
+
+        // Stale conditions are refetched and still served when the refetch fails
+        let unreachable = persistent(&dir, false, Duration::ZERO, false);
+        let conditions = block_on(unreachable.get_all_conditions()).unwrap();
+        assert_eq!(conditions[0].index, "frightened");
+        assert_eq!(unreachable.datasource.calls.get(), 1);
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let uncached = persistent(&other_dir, false, Duration::ZERO, false);
+        assert!(block_on(uncached.get_all_conditions()).is_err());
+    }
+}
@@ -1,3 +1,9 @@
+pub mod caster;
 pub mod common;
+pub mod conditions;
 pub mod queries;
+pub mod spell_annotation;
+pub mod spell_deck;
+pub mod spell_slots;
+pub mod spellbook;
 pub mod spells;
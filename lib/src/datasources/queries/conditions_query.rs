@@ -0,0 +1,9 @@
+use graphql_client::GraphQLQuery;
+
+#[derive(GraphQLQuery, PartialEq, Eq)]
+#[graphql(
+    query_path = "../queries/spells/conditions_query.graphql",
+    schema_path = "../queries/spells/schema.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ConditionsQuery;
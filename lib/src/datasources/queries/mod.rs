@@ -1 +1,2 @@
+pub mod conditions_query;
 pub mod spells_query;
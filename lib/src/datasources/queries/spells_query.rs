@@ -1,32 +1,14 @@
-use self::spells_query::SpellsQuerySpells;
-use self::spells_query::SpellsQuerySpellsClasses;
-
 use graphql_client::GraphQLQuery;
 
+/// Levels a spell has one of
+pub type IntFilter = Vec<i64>;
+/// Indexes (e.g. `wizard`, `evocation`) a spell has one of
+pub type StringFilter = Vec<String>;
+
 #[derive(GraphQLQuery, PartialEq, Eq)]
 #[graphql(
     query_path = "../queries/spells/spells_query.graphql",
     schema_path = "../queries/spells/schema.graphql",
-    response_derives = "Debug"
+    response_derives = "Debug, Clone"
 )]
 pub struct SpellsQuery;
-
-impl Clone for SpellsQuerySpellsClasses {
-    fn clone(&self) -> Self {
-        Self {
-            index: self.index.clone(),
-            name: self.name.clone(),
-        }
-    }
-}
-impl Clone for SpellsQuerySpells {
-    fn clone(&self) -> Self {
-        Self {
-            name: self.name.clone(),
-            level: self.level,
-            desc: self.desc.clone(),
-            index: self.index.clone(),
-            classes: self.classes.clone(),
-        }
-    }
-}
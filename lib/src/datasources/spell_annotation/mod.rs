@@ -0,0 +1 @@
+pub mod spell_annotation_datasource;
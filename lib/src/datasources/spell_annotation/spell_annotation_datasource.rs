@@ -0,0 +1,43 @@
+use crate::core::entity::spell_annotation::SpellAnnotation;
+use crate::core::usecase::spell_annotation::{
+    SpellAnnotationDataSourceError, SpellAnnotationDataSourceInterface,
+};
+use crate::datasources::common::local_file::LocalFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpellAnnotationFile {
+    #[serde(default)]
+    annotations: Vec<SpellAnnotation>,
+}
+
+pub struct SpellAnnotationFileDataSource {
+    file: LocalFile,
+}
+
+impl SpellAnnotationFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+}
+
+impl SpellAnnotationDataSourceInterface for SpellAnnotationFileDataSource {
+    fn get_all_annotations(&self) -> Result<Vec<SpellAnnotation>, SpellAnnotationDataSourceError> {
+        match self.file.read_or_default::<SpellAnnotationFile>() {
+            Ok(content) => Ok(content.annotations),
+            Err(err) => Err(SpellAnnotationDataSourceError::FileError(err)),
+        }
+    }
+
+    fn save_all_annotations(
+        &self,
+        annotations: &[SpellAnnotation],
+    ) -> Result<(), SpellAnnotationDataSourceError> {
+        let content = SpellAnnotationFile {
+            annotations: annotations.to_vec(),
+        };
+        self.file
+            .write(&content)
+            .map_err(SpellAnnotationDataSourceError::FileError)
+    }
+}
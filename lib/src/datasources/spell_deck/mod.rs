@@ -0,0 +1 @@
+pub mod spell_deck_datasource;
@@ -0,0 +1,38 @@
+use crate::core::entity::spell_deck::SpellDeck;
+use crate::core::usecase::spell_draw::{SpellDeckDataSourceError, SpellDeckDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpellDeckFile {
+    #[serde(default)]
+    decks: Vec<SpellDeck>,
+}
+
+pub struct SpellDeckFileDataSource {
+    file: LocalFile,
+}
+
+impl SpellDeckFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+}
+
+impl SpellDeckDataSourceInterface for SpellDeckFileDataSource {
+    fn get_all_decks(&self) -> Result<Vec<SpellDeck>, SpellDeckDataSourceError> {
+        match self.file.read_or_default::<SpellDeckFile>() {
+            Ok(content) => Ok(content.decks),
+            Err(err) => Err(SpellDeckDataSourceError::FileError(err)),
+        }
+    }
+
+    fn save_all_decks(&self, decks: &[SpellDeck]) -> Result<(), SpellDeckDataSourceError> {
+        let content = SpellDeckFile {
+            decks: decks.to_vec(),
+        };
+        self.file
+            .write(&content)
+            .map_err(SpellDeckDataSourceError::FileError)
+    }
+}
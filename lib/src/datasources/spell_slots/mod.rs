@@ -0,0 +1 @@
+pub mod spell_slots_datasource;
@@ -0,0 +1,41 @@
+use crate::core::entity::spell_slots::CasterSlots;
+use crate::core::usecase::spell_slots::{SpellSlotsDataSourceError, SpellSlotsDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpellSlotsFile {
+    #[serde(default)]
+    characters: Vec<CasterSlots>,
+}
+
+pub struct SpellSlotsFileDataSource {
+    file: LocalFile,
+}
+
+impl SpellSlotsFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+}
+
+impl SpellSlotsDataSourceInterface for SpellSlotsFileDataSource {
+    fn get_all_caster_slots(&self) -> Result<Vec<CasterSlots>, SpellSlotsDataSourceError> {
+        match self.file.read_or_default::<SpellSlotsFile>() {
+            Ok(content) => Ok(content.characters),
+            Err(err) => Err(SpellSlotsDataSourceError::FileError(err)),
+        }
+    }
+
+    fn save_all_caster_slots(
+        &self,
+        caster_slots: &[CasterSlots],
+    ) -> Result<(), SpellSlotsDataSourceError> {
+        let content = SpellSlotsFile {
+            characters: caster_slots.to_vec(),
+        };
+        self.file
+            .write(&content)
+            .map_err(SpellSlotsDataSourceError::FileError)
+    }
+}
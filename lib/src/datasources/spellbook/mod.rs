@@ -0,0 +1 @@
+pub mod spellbook_datasource;
@@ -0,0 +1,65 @@
+use crate::core::entity::spellbook::Spellbook;
+use crate::core::usecase::spellbook::{SpellbookDataSourceError, SpellbookDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpellbookFile {
+    #[serde(default)]
+    spellbooks: Vec<Spellbook>,
+}
+
+pub struct SpellbookFileDataSource {
+    file: LocalFile,
+}
+
+impl SpellbookFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+}
+
+impl SpellbookDataSourceInterface for SpellbookFileDataSource {
+    fn get_all_spellbooks(&self) -> Result<Vec<Spellbook>, SpellbookDataSourceError> {
+        match self.file.read_or_default::<SpellbookFile>() {
+            Ok(content) => Ok(content.spellbooks),
+            Err(err) => Err(SpellbookDataSourceError::FileError(err)),
+        }
+    }
+
+    fn save_all_spellbooks(
+        &self,
+        spellbooks: &[Spellbook],
+    ) -> Result<(), SpellbookDataSourceError> {
+        let content = SpellbookFile {
+            spellbooks: spellbooks.to_vec(),
+        };
+        self.file
+            .write(&content)
+            .map_err(SpellbookDataSourceError::FileError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::SpellLevel;
+
+    #[test]
+    fn test_save_and_load_spellbooks() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = LocalFile::new(dir.path().join("spellbooks.yml")).unwrap();
+        let datasource = SpellbookFileDataSource::new(file);
+        assert!(datasource.get_all_spellbooks().unwrap().is_empty());
+        let spellbook = Spellbook::new(
+            "Elminster".to_string(),
+            "Wizard".to_string(),
+            SpellLevel::MAX,
+            Some(12),
+        );
+        datasource
+            .save_all_spellbooks(std::slice::from_ref(&spellbook))
+            .unwrap();
+        assert_eq!(datasource.get_all_spellbooks().unwrap(), vec![spellbook]);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use crate::datasources::spells::srd_spell::SrdSpell;
+use async_trait::async_trait;
+use std::path::Path;
+
+/// Spell file of the 5e-database dataset
+pub const SRD_SPELLS_FILE: &str = "5e-SRD-Spells.json";
+
+/// Reads spells from the `5e-SRD-Spells.json` file of a 5e-database checkout
+///
+/// See <https://github.com/5e-bits/5e-database>, the file holds the same data the GraphQL API
+/// serves.
+pub struct SpellsFileDataSource {
+    file: LocalFile,
+}
+
+impl SpellsFileDataSource {
+    pub fn new(file: LocalFile) -> Self {
+        Self { file }
+    }
+
+    /// Datasource for the spell file in the dataset folder `path`
+    pub fn from_folder<P: AsRef<Path>>(path: P) -> Result<Self, SpellsDataSourceError> {
+        LocalFile::new(path.as_ref().join(SRD_SPELLS_FILE))
+            .map(Self::new)
+            .map_err(SpellsDataSourceError::FileError)
+    }
+}
+
+#[async_trait(?Send)]
+impl SpellsDataSourceInterface for SpellsFileDataSource {
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let spells = self
+            .file
+            .read::<Vec<SrdSpell>>()
+            .map_err(SpellsDataSourceError::FileError)?;
+        log::debug!(
+            "Read {} spells from '{}'",
+            spells.len(),
+            self.file.path().display()
+        );
+        Ok(spells
+            .into_iter()
+            .filter_map(SrdSpell::into_spell)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::area_of_effect::AreaShape;
+    use crate::core::entity::caster::Ability;
+    use crate::core::entity::spell::SpellLevel;
+    use futures::executor::block_on;
+
+    const SPELLS: &str = r#"[
+      {
+        "index": "fireball",
+        "name": "Fireball",
+        "desc": ["A bright streak flashes from your pointing finger."],
+        "range": "150 feet",
+        "components": ["V", "S", "M"],
+        "duration": "Instantaneous",
+        "level": 3,
+        "damage": {
+          "damage_type": {"index": "fire", "name": "Fire", "url": "/api/damage-types/fire"},
+          "damage_at_slot_level": {"3": "8d6", "4": "9d6"}
+        },
+        "dc": {"dc_type": {"index": "dex", "name": "DEX"}, "dc_success": "half"},
+        "area_of_effect": {"type": "sphere", "size": 20},
+        "school": {"index": "evocation", "name": "Evocation"},
+        "classes": [{"index": "sorcerer", "name": "Sorcerer"}, {"index": "wizard", "name": "Wizard"}],
+        "subclasses": [{"index": "fiend", "name": "Fiend"}],
+        "url": "/api/spells/fireball"
+      },
+      {"index": "odd", "name": "Odd", "level": 12}
+    ]"#;
+
+    #[test]
+    fn test_get_all_spells_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(SRD_SPELLS_FILE), SPELLS).unwrap();
+        let datasource = SpellsFileDataSource::from_folder(dir.path()).unwrap();
+        let spells = block_on(datasource.get_all_spells()).unwrap();
+        assert_eq!(spells.len(), 1);
+        let fireball = &spells[0];
+        assert_eq!(fireball.name, "Fireball");
+        assert_eq!(fireball.level, SpellLevel::new(3).unwrap());
+        assert_eq!(fireball.classes, vec!["Sorcerer", "Wizard"]);
+        assert_eq!(fireball.school, Some("Evocation".to_string()));
+        assert_eq!(fireball.area_of_effect.unwrap().shape, AreaShape::Sphere);
+        assert_eq!(fireball.save.as_ref().unwrap().ability, Ability::Dexterity);
+        let damage = fireball.damage.as_ref().unwrap();
+        assert_eq!(damage.damage_type, Some("Fire".to_string()));
+        assert_eq!(
+            damage.damage_at_slot_level[&SpellLevel::new(4).unwrap()],
+            "9d6"
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let datasource = SpellsFileDataSource::from_folder(dir.path()).unwrap();
+        let err = block_on(datasource.get_all_spells()).unwrap_err();
+        assert!(matches!(err, SpellsDataSourceError::FileError(_)));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::core::entity::spell::{index_from_name, Spell, SpellSource};
+use crate::core::usecase::spell::{SpellFilter, SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::datasources::common::local_file::LocalFile;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+struct HomebrewFile {
+    #[serde(default)]
+    spells: Vec<Spell>,
+}
+
+/// Merges homebrew spell files on top of another spell datasource
+///
+/// A homebrew spell replaces the spell with the same index, other homebrew spells are appended.
+pub struct HomebrewSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    datasource: T,
+    paths: Vec<PathBuf>,
+}
+
+impl<T> HomebrewSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    pub fn new(datasource: T, paths: Vec<PathBuf>) -> Self {
+        Self { datasource, paths }
+    }
+
+    fn get_homebrew_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let mut spells = vec![];
+        for path in &self.paths {
+            let content = LocalFile::new(path)
+                .and_then(|file| file.read::<HomebrewFile>())
+                .map_err(SpellsDataSourceError::HomebrewError)?;
+            log::debug!(
+                "Loaded {} homebrew spells from '{}'",
+                content.spells.len(),
+                path.display()
+            );
+            spells.extend(content.spells.into_iter().map(|mut spell| {
+                if spell.index.is_empty() {
+                    spell.index = index_from_name(&spell.name);
+                }
+                spell.source = SpellSource::Homebrew;
+                spell
+            }));
+        }
+        Ok(spells)
+    }
+
+    fn merge_spells(mut spells: Vec<Spell>, homebrew: Vec<Spell>) -> Vec<Spell> {
+        for spell in homebrew {
+            match spells.iter_mut().find(|s| s.index == spell.index) {
+                Some(existing) => *existing = spell,
+                None => spells.push(spell),
+            }
+        }
+        spells
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> SpellsDataSourceInterface for HomebrewSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let homebrew = self.get_homebrew_spells()?;
+        let spells = self.datasource.get_all_spells().await?;
+        Ok(Self::merge_spells(spells, homebrew))
+    }
+
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let homebrew = self.get_homebrew_spells()?;
+        let spells = self.datasource.get_spells_matching(filter).await?;
+        Ok(Self::merge_spells(spells, homebrew))
+    }
+
+    async fn revalidate(&self) -> Result<(), SpellsDataSourceError> {
+        self.datasource.revalidate().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::core::entity::spell::SpellLevel;
+
+    struct MockSpellsDataSource;
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for MockSpellsDataSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            Ok(vec![
+                Spell {
+                    index: "fireball".to_string(),
+                    name: "Fireball".to_string(),
+                    level: SpellLevel::new(3).unwrap(),
+                    ..Default::default()
+                },
+                Spell {
+                    index: "shield".to_string(),
+                    name: "Shield".to_string(),
+                    level: SpellLevel::new(1).unwrap(),
+                    ..Default::default()
+                },
+            ])
+        }
+    }
+
+    #[test]
+    fn test_homebrew_overrides_and_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("homebrew.yml");
+        std::fs::write(
+            &path,
+            "spells:\n  - index: fireball\n    name: Fireball\n    level: 4\n  - name: Frost Lance\n    level: 2\n    classes: [Wizard]\n    damage:\n      damage_at_slot_level:\n        2: 3d8\n",
+        )
+        .unwrap();
+        let datasource = HomebrewSpellsDataSource::new(MockSpellsDataSource, vec![path]);
+        let spells = block_on(datasource.get_all_spells()).unwrap();
+        let summary = spells
+            .iter()
+            .map(|spell| (spell.index.as_str(), spell.level.value(), spell.source))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("fireball", 4, SpellSource::Homebrew),
+                ("shield", 1, SpellSource::Srd),
+                ("frost-lance", 2, SpellSource::Homebrew),
+            ]
+        );
+        let damage = spells[2].damage.clone().unwrap();
+        assert_eq!(
+            damage
+                .damage_at_slot_level
+                .get(&SpellLevel::new(2).unwrap()),
+            Some(&"3d8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_homebrew_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.yml");
+        let datasource = HomebrewSpellsDataSource::new(MockSpellsDataSource, vec![path]);
+        let err = block_on(datasource.get_all_spells()).unwrap_err();
+        assert!(matches!(err, SpellsDataSourceError::HomebrewError(_)));
+    }
+}
@@ -1 +1,8 @@
+pub mod file_datasource;
+pub mod homebrew_datasource;
+pub mod persistent_datasource;
+pub mod rest_datasource;
 pub mod spells_datasource;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_datasource;
+pub mod srd_spell;
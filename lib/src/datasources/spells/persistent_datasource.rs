@@ -0,0 +1,329 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellFilter, SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::datasources::common::local_file::{LocalFile, LocalFileError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use std::io::ErrorKind;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the cached spell format, caches written with another version are refetched
+pub const SPELL_CACHE_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpellCacheMetadata {
+    /// Seconds since the Unix epoch
+    pub fetched_at: u64,
+    /// URL the spells were fetched from
+    pub source: String,
+    pub schema_version: u32,
+}
+
+impl SpellCacheMetadata {
+    pub(crate) fn age(&self) -> Duration {
+        let fetched_at = UNIX_EPOCH + Duration::from_secs(self.fetched_at);
+        SystemTime::now()
+            .duration_since(fetched_at)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SpellCacheFile {
+    metadata: SpellCacheMetadata,
+    spells: Vec<Spell>,
+}
+
+/// Persists the spells of another datasource to disk
+///
+/// Online, a cache younger than `max_age` is served without fetching. An older one is served
+/// at once as well and refetched by `revalidate`, once the stale spells have been used. Without
+/// a cache, filtered queries are passed on and the full list is fetched by `revalidate`.
+/// Offline, only the cache is read. Without a file the spells are passed through.
+pub struct PersistentSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    datasource: T,
+    file: Option<LocalFile>,
+    source: String,
+    max_age: Duration,
+    offline: bool,
+    /// Whether stale or filtered spells were served and the cache needs a refetch
+    revalidate: AtomicBool,
+}
+
+impl<T> PersistentSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    pub fn new(
+        datasource: T,
+        file: Option<LocalFile>,
+        source: String,
+        max_age: Duration,
+        offline: bool,
+    ) -> Self {
+        Self {
+            datasource,
+            file,
+            source,
+            max_age,
+            offline,
+            revalidate: AtomicBool::new(false),
+        }
+    }
+
+    /// The cache when it exists and matches the source and schema version
+    fn read_cache(&self) -> Option<SpellCacheFile> {
+        let file = self.file.as_ref()?;
+        let cache = match file.read::<SpellCacheFile>() {
+            Ok(cache) => cache,
+            Err(LocalFileError::Io(err)) if err.kind() == ErrorKind::NotFound => return None,
+            Err(err) => {
+                log::warn!(
+                    "Ignoring unreadable spell cache '{}': {:?}",
+                    file.path().display(),
+                    err
+                );
+                return None;
+            }
+        };
+        match cache.metadata {
+            SpellCacheMetadata { schema_version, .. }
+                if schema_version != SPELL_CACHE_SCHEMA_VERSION =>
+            {
+                log::info!("Ignoring spell cache of schema version {}", schema_version);
+                None
+            }
+            SpellCacheMetadata { ref source, .. } if *source != self.source => {
+                log::info!("Ignoring spell cache of '{}'", source);
+                None
+            }
+            _ => Some(cache),
+        }
+    }
+
+    /// The cache when offline or fresh, or when stale with a refetch scheduled
+    fn cached_spells(&self) -> Result<Option<Vec<Spell>>, SpellsDataSourceError> {
+        let cache = self.read_cache();
+        if self.offline {
+            return match cache {
+                Some(cache) => Ok(Some(cache.spells)),
+                None => Err(SpellsDataSourceError::NotCached),
+            };
+        }
+        let cache = match cache {
+            Some(cache) => cache,
+            None => return Ok(None),
+        };
+        let age = cache.metadata.age();
+        match age < self.max_age {
+            true => log::debug!("Using spells cached at {}", cache.metadata.fetched_at),
+            false => {
+                log::info!("Serving spells cached {}s ago, revalidating", age.as_secs());
+                self.revalidate.store(true, Ordering::Relaxed);
+            }
+        }
+        Ok(Some(cache.spells))
+    }
+
+    fn write_cache(&self, spells: &[Spell]) {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return,
+        };
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let cache = SpellCacheFile {
+            metadata: SpellCacheMetadata {
+                fetched_at,
+                source: self.source.clone(),
+                schema_version: SPELL_CACHE_SCHEMA_VERSION,
+            },
+            spells: spells.to_vec(),
+        };
+        if let Err(err) = file.write(&cache) {
+            log::warn!(
+                "Could not write spell cache '{}': {:?}",
+                file.path().display(),
+                err
+            );
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<T> SpellsDataSourceInterface for PersistentSpellsDataSource<T>
+where
+    T: SpellsDataSourceInterface,
+{
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        if self.file.is_none() && !self.offline {
+            return self.datasource.get_all_spells().await;
+        }
+        if let Some(spells) = self.cached_spells()? {
+            return Ok(spells);
+        }
+        let spells = self.datasource.get_all_spells().await?;
+        self.write_cache(&spells);
+        Ok(spells)
+    }
+
+    /// Only spells fetched in full are persisted, so without a cache the filter is passed on
+    /// and the full list is left to `revalidate`
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        if self.file.is_none() && !self.offline {
+            return self.datasource.get_spells_matching(filter).await;
+        }
+        if let Some(spells) = self.cached_spells()? {
+            return Ok(spells);
+        }
+        let spells = self.datasource.get_spells_matching(filter).await?;
+        self.revalidate.store(true, Ordering::Relaxed);
+        Ok(spells)
+    }
+
+    async fn revalidate(&self) -> Result<(), SpellsDataSourceError> {
+        if !self.revalidate.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let spells = self.datasource.get_all_spells().await?;
+        self.write_cache(&spells);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::{SpellDamage, SpellLevel};
+    use futures::executor::block_on;
+    use std::cell::Cell;
+
+    struct MockSpellsDataSource {
+        calls: Cell<usize>,
+        online: bool,
+    }
+
+    impl MockSpellsDataSource {
+        fn new(online: bool) -> Self {
+            Self {
+                calls: Cell::new(0),
+                online,
+            }
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl SpellsDataSourceInterface for MockSpellsDataSource {
+        async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+            self.calls.set(self.calls.get() + 1);
+            if !self.online {
+                return Err(SpellsDataSourceError::NoSpellsFound);
+            }
+            let mut damage = SpellDamage::default();
+            damage
+                .damage_at_slot_level
+                .insert(SpellLevel::new(3).unwrap(), "8d6".to_string());
+            Ok(vec![Spell {
+                index: "fireball".to_string(),
+                name: "Fireball".to_string(),
+                level: SpellLevel::new(3).unwrap(),
+                damage: Some(damage),
+                ..Default::default()
+            }])
+        }
+    }
+
+    fn persistent(
+        dir: &tempfile::TempDir,
+        online: bool,
+        max_age: Duration,
+        offline: bool,
+    ) -> PersistentSpellsDataSource<MockSpellsDataSource> {
+        let file = LocalFile::new(dir.path().join("spells.json")).ok();
+        PersistentSpellsDataSource::new(
+            MockSpellsDataSource::new(online),
+            file,
+            "https://example.com/graphql".to_string(),
+            max_age,
+            offline,
+        )
+    }
+
+    #[test]
+    fn test_offline_serves_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let offline = persistent(&dir, true, Duration::ZERO, true);
+        let err = block_on(offline.get_all_spells()).unwrap_err();
+        assert!(matches!(err, SpellsDataSourceError::NotCached));
+
+        let online = persistent(&dir, true, Duration::ZERO, false);
+        block_on(online.get_all_spells()).unwrap();
+
+        let spells = block_on(offline.get_all_spells()).unwrap();
+        assert_eq!(spells[0].name, "Fireball");
+        assert_eq!(
+            spells[0].damage.as_ref().unwrap().damage_at_slot_level[&SpellLevel::new(3).unwrap()],
+            "8d6"
+        );
+        assert_eq!(offline.datasource.calls.get(), 0);
+    }
+
+    #[test]
+    fn test_fresh_and_stale_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        block_on(persistent(&dir, true, Duration::ZERO, false).get_all_spells()).unwrap();
+
+        let fresh = persistent(&dir, true, Duration::from_secs(3600), false);
+        block_on(fresh.get_all_spells()).unwrap();
+        assert_eq!(fresh.datasource.calls.get(), 0);
+
+        // Stale spells are served at once and refetched by revalidate, which may fail
+        let unreachable = persistent(&dir, false, Duration::ZERO, false);
+        let spells = block_on(unreachable.get_all_spells()).unwrap();
+        assert_eq!(spells[0].index, "fireball");
+        assert_eq!(unreachable.datasource.calls.get(), 0);
+        assert!(block_on(unreachable.revalidate()).is_err());
+        assert_eq!(unreachable.datasource.calls.get(), 1);
+
+        let stale = persistent(&dir, true, Duration::ZERO, false);
+        block_on(stale.get_all_spells()).unwrap();
+        block_on(stale.revalidate()).unwrap();
+        block_on(stale.revalidate()).unwrap();
+        assert_eq!(stale.datasource.calls.get(), 1);
+
+        let other_dir = tempfile::tempdir().unwrap();
+        let uncached = persistent(&other_dir, false, Duration::ZERO, false);
+        assert!(block_on(uncached.get_all_spells()).is_err());
+    }
+
+    #[test]
+    fn test_filter_passed_on_without_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let filter = SpellFilter {
+            classes: vec!["wizard".to_string()],
+            ..Default::default()
+        };
+        let uncached = persistent(&dir, true, Duration::from_secs(3600), false);
+        block_on(uncached.get_spells_matching(&filter)).unwrap();
+        assert_eq!(uncached.datasource.calls.get(), 1);
+        assert!(uncached.read_cache().is_none());
+
+        block_on(uncached.revalidate()).unwrap();
+        assert_eq!(uncached.datasource.calls.get(), 2);
+        assert!(uncached.read_cache().is_some());
+
+        block_on(uncached.get_spells_matching(&filter)).unwrap();
+        block_on(uncached.revalidate()).unwrap();
+        assert_eq!(uncached.datasource.calls.get(), 2);
+    }
+}
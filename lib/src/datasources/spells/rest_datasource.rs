@@ -0,0 +1,122 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::datasources::common::remote_datasource::RestAPI;
+use crate::datasources::spells::srd_spell::SrdSpell;
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct SpellListEntry {
+    index: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpellList {
+    results: Vec<SpellListEntry>,
+}
+
+/// Reads spells from the dnd5eapi REST endpoints, `/spells` and `/spells/{index}`
+///
+/// The list only holds names, so the details of each spell are fetched with at most
+/// `max_concurrent_requests` requests in flight.
+pub struct SpellsRestDataSource {
+    api: RestAPI,
+    max_concurrent_requests: usize,
+}
+
+impl SpellsRestDataSource {
+    pub fn new(api: RestAPI, max_concurrent_requests: usize) -> Self {
+        Self {
+            api,
+            max_concurrent_requests: max_concurrent_requests.max(1),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl SpellsDataSourceInterface for SpellsRestDataSource {
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let list = self
+            .api
+            .get_json::<SpellList>("/spells")
+            .await
+            .map_err(|err| SpellsDataSourceError::RestError(Box::new(err)))?;
+        log::debug!("Fetching details of {} spells", list.results.len());
+        let spells = stream::iter(list.results)
+            .map(|entry| async move {
+                self.api
+                    .get_json::<SrdSpell>(&format!("/spells/{}", entry.index))
+                    .await
+            })
+            .buffered(self.max_concurrent_requests)
+            .try_collect::<Vec<SrdSpell>>()
+            .await
+            .map_err(|err| SpellsDataSourceError::RestError(Box::new(err)))?;
+        Ok(spells
+            .into_iter()
+            .filter_map(SrdSpell::into_spell)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasources::common::mock_server::{MockResponse, MockServer};
+    use crate::datasources::common::remote_datasource::APIError;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn routes(count: usize) -> HashMap<String, MockResponse> {
+        let results = (0..count)
+            .map(|i| format!("{{\"index\":\"spell-{i}\",\"name\":\"Spell {i}\",\"level\":1,\"url\":\"/api/spells/spell-{i}\"}}"))
+            .collect::<Vec<String>>()
+            .join(",");
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/api/spells".to_string(),
+            MockResponse::ok(&format!("{{\"count\":{count},\"results\":[{results}]}}")),
+        );
+        for i in 0..count {
+            routes.insert(
+                format!("/api/spells/spell-{i}"),
+                MockResponse::ok(&format!(
+                    "{{\"index\":\"spell-{i}\",\"name\":\"Spell {i}\",\"level\":{},\"classes\":[{{\"index\":\"wizard\",\"name\":\"Wizard\"}}]}}",
+                    i % 10
+                )),
+            );
+        }
+        routes
+    }
+
+    #[tokio::test]
+    async fn test_get_all_spells_with_bounded_concurrency() {
+        let server = MockServer::start(routes(12), Duration::from_millis(20));
+        let api = RestAPI::new(format!("{}/api/", server.url()));
+        let datasource = SpellsRestDataSource::new(api, 4);
+        let spells = datasource.get_all_spells().await.unwrap();
+        assert_eq!(spells.len(), 12);
+        assert_eq!(spells[3].index, "spell-3");
+        assert_eq!(spells[3].classes, vec!["Wizard"]);
+        assert_eq!(server.requests(), 13);
+        assert!(server.max_concurrent_requests() > 1);
+        assert!(server.max_concurrent_requests() <= 4);
+    }
+
+    #[tokio::test]
+    async fn test_missing_detail_fails() {
+        let mut routes = routes(3);
+        routes.remove("/api/spells/spell-1");
+        let server = MockServer::start(routes, Duration::ZERO);
+        let api = RestAPI::new(format!("{}/api", server.url()));
+        let err = SpellsRestDataSource::new(api, 2)
+            .get_all_spells()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SpellsDataSourceError::RestError(err) if matches!(*err, APIError::ResponseHttp(status, _) if status.as_u16() == 404)
+        ));
+    }
+}
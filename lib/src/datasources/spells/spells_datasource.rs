@@ -1,11 +1,18 @@
-use crate::core::usecase::spell::{SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::core::entity::area_of_effect::{AreaOfEffect, AreaShape};
+use crate::core::entity::caster::Ability;
+use crate::core::entity::spell::{
+    index_from_name, subclass_index, Spell, SpellDamage, SpellLevel, SpellLevelRange, SpellSave,
+    SpellSource,
+};
+use crate::core::usecase::spell::{SpellFilter, SpellsDataSourceError, SpellsDataSourceInterface};
 use crate::datasources::common::remote_datasource::GraphQLAPI;
 use crate::datasources::queries::spells_query::spells_query::{
-    ResponseData, SpellsQuerySpells, Variables,
+    ResponseData, SpellsQuerySpells, SpellsQuerySpellsDamage, Variables,
 };
 use crate::datasources::queries::spells_query::SpellsQuery;
 // use crate::datasources::queries::spells_query;
 use async_trait::async_trait;
+use std::collections::BTreeMap;
 
 pub struct SpellsGraphQLDataSource {
     api: GraphQLAPI,
@@ -16,28 +23,247 @@ impl SpellsGraphQLDataSource {
         Self { api }
     }
 
-    fn make_variables(&self) -> Variables {
-        Variables { limit: Some(0) }
+    /// Variables of the queries for the filter, a limit of 0 is unlimited
+    ///
+    /// The API narrows down by level, class, subclass and name. Spells of the classes and the
+    /// always-prepared spells of the subclasses are both wanted, so they are queried separately.
+    /// Names are sent as words because the API matches them as a part of the spell's name.
+    fn make_variables(&self, filter: &SpellFilter) -> Vec<Variables> {
+        let level = filter
+            .level
+            .filter(|level| *level != SpellLevelRange::up_to(SpellLevel::MAX))
+            .map(|level| {
+                (level.min.value()..=level.max.value())
+                    .map(i64::from)
+                    .collect::<Vec<i64>>()
+            });
+        let name = filter
+            .name
+            .as_ref()
+            .map(|name| name.trim().replace('-', " "));
+        let indexes = |names: &[String], index: fn(&str) -> String| match names.is_empty() {
+            true => None,
+            false => Some(
+                names
+                    .iter()
+                    .map(|name| index(name))
+                    .collect::<Vec<String>>(),
+            ),
+        };
+        let variables = |class, subclass| Variables {
+            limit: Some(0),
+            level: level.clone(),
+            class,
+            subclass,
+            name: name.clone(),
+        };
+        match (
+            indexes(&filter.classes, index_from_name),
+            indexes(&filter.subclasses, subclass_index),
+        ) {
+            (None, None) => vec![variables(None, None)],
+            (class, None) => vec![variables(class, None)],
+            (None, subclass) => vec![variables(None, subclass)],
+            (class, subclass) => vec![variables(class, None), variables(None, subclass)],
+        }
     }
 
-    async fn get_all_raw_spells(&self) -> Result<ResponseData, SpellsDataSourceError> {
-        let variables = self.make_variables();
-        let response_data = self
+    /// Partial data is used, every GraphQL error is logged
+    async fn get_raw_spells(
+        &self,
+        variables: Variables,
+    ) -> Result<ResponseData, SpellsDataSourceError> {
+        let response = self
             .api
-            .get_response_data::<Variables, ResponseData, SpellsQuery>(variables)
-            .await;
-        match response_data {
-            Ok(data) => Ok(data),
-            Err(err) => Err(SpellsDataSourceError::GraphQLError(err)),
+            .get_response::<Variables, ResponseData, SpellsQuery>(variables)
+            .await
+            .map_err(SpellsDataSourceError::GraphQLError)?;
+        for error in &response.errors {
+            log::warn!("Using partial spells, GraphQL error at {}", error);
+        }
+        Ok(response.data)
+    }
+
+    fn slot_table_from_level_values(
+        level_values: Vec<(i64, String)>,
+    ) -> BTreeMap<SpellLevel, String> {
+        level_values
+            .into_iter()
+            .filter_map(
+                |(level, value)| match u8::try_from(level).map(SpellLevel::new) {
+                    Ok(Ok(level)) => Some((level, value)),
+                    _ => {
+                        log::warn!("Ignoring invalid slot level {}", level);
+                        None
+                    }
+                },
+            )
+            .collect::<BTreeMap<SpellLevel, String>>()
+    }
+
+    fn spell_damage_from_spells_query_damage(damage: SpellsQuerySpellsDamage) -> SpellDamage {
+        let damage_at_slot_level = damage
+            .damage_at_slot_level
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|level_value| (level_value.level, level_value.value))
+            .collect::<Vec<(i64, String)>>();
+        let damage_at_character_level = damage
+            .damage_at_character_level
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|level_value| match u8::try_from(level_value.level) {
+                Ok(level) => Some((level, level_value.value)),
+                Err(_) => None,
+            })
+            .collect::<BTreeMap<u8, String>>();
+        SpellDamage {
+            damage_type: damage
+                .damage_type
+                .and_then(|damage_type| damage_type.name.or(damage_type.index)),
+            damage_at_slot_level: Self::slot_table_from_level_values(damage_at_slot_level),
+            damage_at_character_level,
         }
     }
+
+    /// Converts a queried spell, spells with an invalid level are skipped
+    fn spell_from_spells_query_spells(spell: SpellsQuerySpells) -> Option<Spell> {
+        let level = match SpellLevel::try_from(spell.level) {
+            Ok(level) => level,
+            Err(err) => {
+                log::warn!("Skipping spell {:?}: {}", spell.index, err);
+                return None;
+            }
+        };
+        let name = match spell.name {
+            Some(name) => name,
+            None => spell.index.clone().unwrap_or_default(),
+        };
+        let index = match spell.index {
+            Some(index) => index,
+            None => index_from_name(&name),
+        };
+        let desc = spell
+            .desc
+            .unwrap_or_default()
+            .into_iter()
+            .map(|desc| desc.unwrap_or_default())
+            .collect::<Vec<String>>();
+        let classes = spell
+            .classes
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|class| {
+                class
+                    .name
+                    .unwrap_or_else(|| class.index.unwrap_or_default())
+            })
+            .collect::<Vec<String>>();
+        let subclasses = spell
+            .subclasses
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|subclass| {
+                subclass
+                    .name
+                    .unwrap_or_else(|| subclass.index.unwrap_or_default())
+            })
+            .collect::<Vec<String>>();
+        let school = spell.school.and_then(|school| school.name.or(school.index));
+        let components = spell
+            .components
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<String>>();
+        let area_of_effect = spell.area_of_effect.and_then(|area| {
+            match (area.type_.parse::<AreaShape>(), u32::try_from(area.size)) {
+                (Ok(shape), Ok(size)) => Some(AreaOfEffect { shape, size }),
+                _ => {
+                    log::warn!("Ignoring invalid area of effect {:?}", area);
+                    None
+                }
+            }
+        });
+        let save = spell.dc.and_then(|dc| {
+            let dc_type = dc.dc_type?;
+            match dc_type
+                .index
+                .or(dc_type.name)
+                .unwrap_or_default()
+                .parse::<Ability>()
+            {
+                Ok(ability) => Some(SpellSave {
+                    ability,
+                    success: dc.dc_success,
+                }),
+                Err(err) => {
+                    log::warn!("Ignoring spell save: {}", err);
+                    None
+                }
+            }
+        });
+        let damage = spell
+            .damage
+            .map(Self::spell_damage_from_spells_query_damage);
+        let heal_at_slot_level = Self::slot_table_from_level_values(
+            spell
+                .heal_at_slot_level
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|level_value| (level_value.level, level_value.value))
+                .collect::<Vec<(i64, String)>>(),
+        );
+
+        Some(Spell {
+            index,
+            name,
+            level,
+            desc,
+            classes,
+            subclasses,
+            school,
+            range: spell.range,
+            components,
+            duration: spell.duration,
+            area_of_effect,
+            save,
+            attack_type: spell.attack_type,
+            damage,
+            heal_at_slot_level,
+            source: SpellSource::Srd,
+        })
+    }
 }
 
 #[async_trait(?Send)]
 impl SpellsDataSourceInterface for SpellsGraphQLDataSource {
-    async fn get_all_spells(&self) -> Result<Vec<SpellsQuerySpells>, SpellsDataSourceError> {
-        let data = self.get_all_raw_spells().await;
-        let spells = data?.spells;
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        self.get_spells_matching(&SpellFilter::default()).await
+    }
+
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let mut spells: Vec<Spell> = vec![];
+        for variables in self.make_variables(filter) {
+            let data = self.get_raw_spells(variables).await?;
+            for spell in data
+                .spells
+                .into_iter()
+                .filter_map(Self::spell_from_spells_query_spells)
+            {
+                if !spells.iter().any(|known| known.index == spell.index) {
+                    spells.push(spell);
+                }
+            }
+        }
         Ok(spells)
     }
 }
@@ -45,8 +271,15 @@ impl SpellsDataSourceInterface for SpellsGraphQLDataSource {
 #[cfg(test)]
 mod tests {
     use futures::executor::block_on;
+    use std::collections::HashMap;
+    use std::time::Duration;
 
     use super::*;
+    use crate::datasources::common::mock_server::{MockResponse, MockServer};
+    use crate::datasources::queries::spells_query::spells_query::{
+        SpellsQuerySpellsAreaOfEffect, SpellsQuerySpellsDc, SpellsQuerySpellsDcDcType,
+        SpellsQuerySpellsSubclasses,
+    };
 
     fn data_source() -> SpellsGraphQLDataSource {
         let api = GraphQLAPI::new("".to_string());
@@ -56,14 +289,183 @@ mod tests {
     #[test]
     fn test_make_variables() {
         let data_source = data_source();
-        let variables = data_source.make_variables();
-        assert_eq!(variables.limit, Some(0));
+        let variables = data_source.make_variables(&SpellFilter::default());
+        assert_eq!(variables.len(), 1);
+        assert_eq!(variables[0].limit, Some(0));
+        assert_eq!(variables[0].level, None);
+        assert_eq!(variables[0].class, None);
+        assert_eq!(variables[0].subclass, None);
+        assert_eq!(variables[0].name, None);
+
+        let filter = SpellFilter {
+            level: Some("1..3".parse().unwrap()),
+            classes: vec!["Wizard".to_string(), " bard".to_string()],
+            ..Default::default()
+        };
+        let variables = data_source.make_variables(&filter);
+        assert_eq!(variables.len(), 1);
+        assert_eq!(variables[0].level, Some(vec![1, 2, 3]));
+        assert_eq!(
+            variables[0].class,
+            Some(vec!["wizard".to_string(), "bard".to_string()])
+        );
+
+        let filter = SpellFilter {
+            name: Some("cure-wounds".to_string()),
+            ..Default::default()
+        };
+        let variables = data_source.make_variables(&filter);
+        assert_eq!(variables[0].name, Some("cure wounds".to_string()));
+    }
+
+    #[test]
+    fn test_make_variables_for_class_and_subclass() {
+        let filter = SpellFilter {
+            level: Some("0..9".parse().unwrap()),
+            classes: vec!["Cleric".to_string()],
+            subclasses: vec!["Life".to_string()],
+            ..Default::default()
+        };
+        let variables = data_source().make_variables(&filter);
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].level, None);
+        assert_eq!(variables[0].class, Some(vec!["cleric".to_string()]));
+        assert_eq!(variables[0].subclass, None);
+        assert_eq!(variables[1].class, None);
+        assert_eq!(variables[1].subclass, Some(vec!["life".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_merges_class_and_subclass_spells() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/graphql".to_string(),
+            vec![
+                MockResponse::ok(r#"{"data":{"spells":[{"index":"bless","level":1}]}}"#),
+                MockResponse::ok(
+                    r#"{"data":{"spells":[{"index":"bless","level":1},{"index":"cure-wounds","level":1}]}}"#,
+                ),
+            ],
+        );
+        let server = MockServer::start_with_sequences(routes, Duration::ZERO);
+        let data_source =
+            SpellsGraphQLDataSource::new(GraphQLAPI::new(format!("{}/graphql", server.url())));
+        let filter = SpellFilter {
+            classes: vec!["cleric".to_string()],
+            subclasses: vec!["life".to_string()],
+            ..Default::default()
+        };
+        let spells = data_source.get_spells_matching(&filter).await.unwrap();
+        let indexes = spells
+            .iter()
+            .map(|spell| spell.index.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(indexes, vec!["bless", "cure-wounds"]);
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_keeps_partial_spells() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/graphql".to_string(),
+            vec![MockResponse::ok(
+                r#"{
+                    "data": {"spells": [
+                        {"index": "bless", "level": 1, "desc": null},
+                        {"index": "shield", "level": 1, "desc": ["An invisible barrier."]}
+                    ]},
+                    "errors": [
+                        {"message": "desc unavailable", "path": ["spells", 0, "desc"]},
+                        {"message": "rate limited", "locations": [{"line": 3, "column": 5}]}
+                    ]
+                }"#,
+            )],
+        );
+        let server = MockServer::start_with_sequences(routes, Duration::ZERO);
+        let data_source =
+            SpellsGraphQLDataSource::new(GraphQLAPI::new(format!("{}/graphql", server.url())));
+        let spells = data_source.get_all_spells().await.unwrap();
+        let indexes = spells
+            .iter()
+            .map(|spell| spell.index.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(indexes, vec!["bless", "shield"]);
+        assert!(spells[0].desc.is_empty());
+        assert_eq!(spells[1].desc, vec!["An invisible barrier."]);
     }
 
     #[test]
     fn test_get_from_invalid_url() {
         let data_source = data_source();
-        let err = block_on(data_source.get_all_raw_spells());
+        let variables = data_source
+            .make_variables(&SpellFilter::default())
+            .remove(0);
+        let err = block_on(data_source.get_raw_spells(variables));
         assert!(err.is_err());
     }
+
+    fn query_spell(index: &str, level: f64) -> SpellsQuerySpells {
+        SpellsQuerySpells {
+            name: None,
+            level,
+            desc: Some(vec![Some("A description".to_string())]),
+            index: Some(index.to_string()),
+            classes: None,
+            subclasses: Some(vec![Some(SpellsQuerySpellsSubclasses {
+                index: Some("lore".to_string()),
+                name: Some("Lore".to_string()),
+            })]),
+            school: None,
+            range: Some("Self".to_string()),
+            components: Some(vec![Some("V".to_string()), Some("S".to_string())]),
+            duration: None,
+            area_of_effect: Some(SpellsQuerySpellsAreaOfEffect {
+                type_: "Cone".to_string(),
+                size: 15,
+            }),
+            dc: Some(SpellsQuerySpellsDc {
+                dc_type: Some(SpellsQuerySpellsDcDcType {
+                    index: Some("dex".to_string()),
+                    name: Some("DEX".to_string()),
+                }),
+                dc_success: Some("half".to_string()),
+            }),
+            attack_type: None,
+            damage: None,
+            heal_at_slot_level: None,
+        }
+    }
+
+    #[test]
+    fn test_spell_from_spells_query_spells() {
+        let spell =
+            SpellsGraphQLDataSource::spell_from_spells_query_spells(query_spell("shield", 1.0))
+                .unwrap();
+        assert_eq!(spell.index, "shield");
+        assert_eq!(spell.name, "shield");
+        assert_eq!(spell.level, SpellLevel::new(1).unwrap());
+        assert_eq!(spell.source, SpellSource::Srd);
+        assert_eq!(spell.range, Some("Self".to_string()));
+        assert_eq!(spell.components, vec!["V", "S"]);
+        assert_eq!(spell.subclasses, vec!["Lore"]);
+        assert_eq!(
+            spell.area_of_effect,
+            Some(AreaOfEffect {
+                shape: AreaShape::Cone,
+                size: 15
+            })
+        );
+        assert_eq!(
+            spell.save,
+            Some(SpellSave {
+                ability: Ability::Dexterity,
+                success: Some("half".to_string())
+            })
+        );
+        assert!(
+            SpellsGraphQLDataSource::spell_from_spells_query_spells(query_spell("odd", 2.5))
+                .is_none()
+        );
+    }
 }
@@ -0,0 +1,243 @@
+use crate::core::entity::spell::Spell;
+use crate::core::usecase::spell::{SpellFilter, SpellsDataSourceError, SpellsDataSourceInterface};
+use crate::core::usecase::spell_import::SpellStoreInterface;
+use async_trait::async_trait;
+use rusqlite::types::{Type, Value};
+use rusqlite::{params, params_from_iter, Connection};
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+/// Spells are stored as JSON next to the columns that filters query
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS spells (
+    spell_index TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    school TEXT COLLATE NOCASE,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS spell_classes (
+    spell_index TEXT NOT NULL REFERENCES spells(spell_index) ON DELETE CASCADE,
+    class TEXT NOT NULL COLLATE NOCASE
+);
+CREATE INDEX IF NOT EXISTS spells_level ON spells(level);
+CREATE INDEX IF NOT EXISTS spells_school ON spells(school);
+CREATE INDEX IF NOT EXISTS spell_classes_class ON spell_classes(class, spell_index);
+";
+
+/// Reads spells imported with `dnd data import` from a SQLite database
+///
+/// The database is opened on first use, creating it with its schema when missing.
+pub struct SpellsSqliteDataSource {
+    path: PathBuf,
+    connection: Mutex<Option<Connection>>,
+}
+
+impl SpellsSqliteDataSource {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            connection: Mutex::new(None),
+        }
+    }
+
+    fn with_connection<R>(
+        &self,
+        query: impl FnOnce(&mut Connection) -> rusqlite::Result<R>,
+    ) -> Result<R, SpellsDataSourceError> {
+        let mut connection = self
+            .connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if connection.is_none() {
+            log::debug!("Opening spell database '{}'", self.path.display());
+            if let Some(parent) = self.path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let opened = Connection::open(&self.path).and_then(|connection| {
+                connection.execute_batch(SCHEMA)?;
+                Ok(connection)
+            });
+            *connection = Some(opened.map_err(Self::error)?);
+        }
+        match connection.as_mut() {
+            Some(connection) => query(connection).map_err(Self::error),
+            None => unreachable!("connection was just opened"),
+        }
+    }
+
+    fn error(err: rusqlite::Error) -> SpellsDataSourceError {
+        SpellsDataSourceError::DatabaseError(Box::new(err))
+    }
+
+    fn query_spells(
+        connection: &Connection,
+        sql: &str,
+        values: Vec<Value>,
+    ) -> rusqlite::Result<Vec<Spell>> {
+        let mut statement = connection.prepare(sql)?;
+        let rows = statement.query_map(params_from_iter(values), |row| {
+            let data: String = row.get(0)?;
+            serde_json::from_str::<Spell>(&data)
+                .map_err(|err| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, err.into()))
+        })?;
+        rows.collect()
+    }
+}
+
+#[async_trait(?Send)]
+impl SpellsDataSourceInterface for SpellsSqliteDataSource {
+    async fn get_all_spells(&self) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        self.with_connection(|connection| {
+            Self::query_spells(
+                connection,
+                "SELECT data FROM spells ORDER BY level, name",
+                vec![],
+            )
+        })
+    }
+
+    /// Filters by level and class in SQL, subclass filters only narrow down the level since
+    /// subclass titles are matched by `Spell::matching_subclasses`
+    async fn get_spells_matching(
+        &self,
+        filter: &SpellFilter,
+    ) -> Result<Vec<Spell>, SpellsDataSourceError> {
+        let mut sql = "SELECT data FROM spells WHERE 1 = 1".to_string();
+        let mut values = vec![];
+        if let Some(level) = &filter.level {
+            sql.push_str(" AND level BETWEEN ? AND ?");
+            values.push(Value::Integer(level.min.value().into()));
+            values.push(Value::Integer(level.max.value().into()));
+        }
+        if !filter.classes.is_empty() && filter.subclasses.is_empty() {
+            let placeholders = vec!["?"; filter.classes.len()].join(", ");
+            sql.push_str(&format!(
+                " AND spell_index IN (SELECT spell_index FROM spell_classes WHERE class IN ({}))",
+                placeholders
+            ));
+            values.extend(
+                filter
+                    .classes
+                    .iter()
+                    .map(|class| Value::Text(class.trim().to_string())),
+            );
+        }
+        sql.push_str(" ORDER BY level, name");
+        self.with_connection(|connection| Self::query_spells(connection, &sql, values))
+    }
+}
+
+impl SpellStoreInterface for SpellsSqliteDataSource {
+    fn replace_all_spells(&self, spells: &[Spell]) -> Result<(), SpellsDataSourceError> {
+        self.with_connection(|connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch("DELETE FROM spell_classes; DELETE FROM spells;")?;
+            {
+                let mut insert_spell = transaction.prepare(
+                    "INSERT OR REPLACE INTO spells (spell_index, name, level, school, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )?;
+                let mut insert_class = transaction
+                    .prepare("INSERT INTO spell_classes (spell_index, class) VALUES (?1, ?2)")?;
+                for spell in spells {
+                    let data = serde_json::to_string(spell)
+                        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(err.into()))?;
+                    insert_spell.execute(params![
+                        spell.index,
+                        spell.name,
+                        spell.level.value(),
+                        spell.school,
+                        data
+                    ])?;
+                    for class in &spell.classes {
+                        insert_class.execute(params![spell.index, class])?;
+                    }
+                }
+            }
+            transaction.commit()
+        })?;
+        log::debug!(
+            "Stored {} spells in '{}'",
+            spells.len(),
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::entity::spell::{SpellLevel, SpellLevelRange};
+    use futures::executor::block_on;
+
+    fn spell(index: &str, level: u8, school: &str, classes: &[&str]) -> Spell {
+        Spell {
+            index: index.to_string(),
+            name: index.replace('-', " "),
+            level: SpellLevel::new(level).unwrap(),
+            school: Some(school.to_string()),
+            classes: classes.iter().map(|class| class.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn indexes(spells: Vec<Spell>) -> Vec<String> {
+        spells.into_iter().map(|spell| spell.index).collect()
+    }
+
+    fn datasource(dir: &tempfile::TempDir) -> SpellsSqliteDataSource {
+        let datasource = SpellsSqliteDataSource::new(dir.path().join("db").join("spells.db"));
+        datasource
+            .replace_all_spells(&[
+                spell("fireball", 3, "Evocation", &["Sorcerer", "Wizard"]),
+                spell("cure-wounds", 1, "Evocation", &["Bard", "Cleric"]),
+                spell("shield", 1, "Abjuration", &["Sorcerer", "Wizard"]),
+                spell("fire-bolt", 0, "Evocation", &["Sorcerer", "Wizard"]),
+            ])
+            .unwrap();
+        datasource
+    }
+
+    #[test]
+    fn test_filters_run_in_sql() {
+        let dir = tempfile::tempdir().unwrap();
+        let datasource = datasource(&dir);
+        let spells = block_on(datasource.get_all_spells()).unwrap();
+        assert_eq!(
+            indexes(spells),
+            vec!["fire-bolt", "cure-wounds", "shield", "fireball"]
+        );
+        let filter = SpellFilter {
+            level: Some("1..3".parse::<SpellLevelRange>().unwrap()),
+            classes: vec!["wizard".to_string(), " bard ".to_string()],
+            ..Default::default()
+        };
+        let spells = block_on(datasource.get_spells_matching(&filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["cure-wounds", "shield", "fireball"]);
+        let filter = SpellFilter {
+            level: Some(SpellLevelRange::exact(SpellLevel::new(1).unwrap())),
+            classes: vec!["cleric".to_string()],
+            subclasses: vec!["life".to_string()],
+            ..Default::default()
+        };
+        let spells = block_on(datasource.get_spells_matching(&filter)).unwrap();
+        assert_eq!(indexes(spells), vec!["cure-wounds", "shield"]);
+    }
+
+    #[test]
+    fn test_replace_all_spells_keeps_spell_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let datasource = datasource(&dir);
+        let mut wish = spell("wish", 9, "Conjuration", &["Sorcerer", "Wizard"]);
+        wish.desc = vec!["Wish is the mightiest spell".to_string()];
+        datasource.replace_all_spells(&[wish]).unwrap();
+
+        let reopened = SpellsSqliteDataSource::new(dir.path().join("db").join("spells.db"));
+        let spells = block_on(reopened.get_all_spells()).unwrap();
+        assert_eq!(spells.len(), 1);
+        assert_eq!(spells[0].desc, vec!["Wish is the mightiest spell"]);
+        assert_eq!(spells[0].school.as_deref(), Some("Conjuration"));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::core::entity::area_of_effect::{AreaOfEffect, AreaShape};
+use crate::core::entity::caster::Ability;
+use crate::core::entity::spell::{
+    index_from_name, Spell, SpellDamage, SpellLevel, SpellSave, SpellSource,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Reference to another entry of the dataset, e.g. a class or school
+#[derive(Debug, Default, Deserialize)]
+struct SrdReference {
+    #[serde(default)]
+    index: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl SrdReference {
+    fn name(self) -> String {
+        self.name.or(self.index).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SrdDamage {
+    damage_type: Option<SrdReference>,
+    damage_at_slot_level: BTreeMap<String, String>,
+    damage_at_character_level: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SrdDc {
+    dc_type: SrdReference,
+    #[serde(default)]
+    dc_success: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SrdAreaOfEffect {
+    #[serde(rename = "type")]
+    type_: String,
+    size: u32,
+}
+
+/// Spell as stored in the 5e-database dataset and served by the REST API
+#[derive(Debug, Deserialize)]
+pub struct SrdSpell {
+    #[serde(default)]
+    index: Option<String>,
+    name: String,
+    level: i64,
+    #[serde(default)]
+    desc: Vec<String>,
+    #[serde(default)]
+    classes: Vec<SrdReference>,
+    #[serde(default)]
+    subclasses: Vec<SrdReference>,
+    #[serde(default)]
+    school: Option<SrdReference>,
+    #[serde(default)]
+    range: Option<String>,
+    #[serde(default)]
+    components: Vec<String>,
+    #[serde(default)]
+    duration: Option<String>,
+    #[serde(default)]
+    area_of_effect: Option<SrdAreaOfEffect>,
+    #[serde(default)]
+    dc: Option<SrdDc>,
+    #[serde(default)]
+    attack_type: Option<String>,
+    #[serde(default)]
+    damage: Option<SrdDamage>,
+    #[serde(default)]
+    heal_at_slot_level: BTreeMap<String, String>,
+}
+
+impl SrdSpell {
+    fn slot_table(values: BTreeMap<String, String>) -> BTreeMap<SpellLevel, String> {
+        values
+            .into_iter()
+            .filter_map(|(level, value)| match level.parse::<SpellLevel>() {
+                Ok(level) => Some((level, value)),
+                Err(err) => {
+                    log::warn!("Ignoring invalid slot level: {}", err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn spell_damage_from_srd_damage(damage: SrdDamage) -> SpellDamage {
+        SpellDamage {
+            damage_type: damage.damage_type.map(SrdReference::name),
+            damage_at_slot_level: Self::slot_table(damage.damage_at_slot_level),
+            damage_at_character_level: damage
+                .damage_at_character_level
+                .into_iter()
+                .filter_map(|(level, value)| Some((level.parse::<u8>().ok()?, value)))
+                .collect(),
+        }
+    }
+
+    /// Converts the dataset spell, `None` for spells with an invalid level
+    pub fn into_spell(self) -> Option<Spell> {
+        let level = match u8::try_from(self.level).map(SpellLevel::new) {
+            Ok(Ok(level)) => level,
+            _ => {
+                log::warn!(
+                    "Skipping spell {:?}: invalid level {}",
+                    self.name,
+                    self.level
+                );
+                return None;
+            }
+        };
+        let area_of_effect =
+            self.area_of_effect
+                .and_then(|area| match area.type_.parse::<AreaShape>() {
+                    Ok(shape) => Some(AreaOfEffect {
+                        shape,
+                        size: area.size,
+                    }),
+                    Err(err) => {
+                        log::warn!("Ignoring area of effect: {}", err);
+                        None
+                    }
+                });
+        let save = self.dc.and_then(|dc| {
+            match dc
+                .dc_type
+                .index
+                .as_deref()
+                .unwrap_or_default()
+                .parse::<Ability>()
+            {
+                Ok(ability) => Some(SpellSave {
+                    ability,
+                    success: dc.dc_success,
+                }),
+                Err(err) => {
+                    log::warn!("Ignoring spell save: {}", err);
+                    None
+                }
+            }
+        });
+
+        Some(Spell {
+            index: self.index.unwrap_or_else(|| index_from_name(&self.name)),
+            name: self.name,
+            level,
+            desc: self.desc,
+            classes: self.classes.into_iter().map(SrdReference::name).collect(),
+            subclasses: self
+                .subclasses
+                .into_iter()
+                .map(SrdReference::name)
+                .collect(),
+            school: self.school.map(SrdReference::name),
+            range: self.range,
+            components: self.components,
+            duration: self.duration,
+            area_of_effect,
+            save,
+            attack_type: self.attack_type,
+            damage: self.damage.map(Self::spell_damage_from_srd_damage),
+            heal_at_slot_level: Self::slot_table(self.heal_at_slot_level),
+            source: SpellSource::Srd,
+        })
+    }
+}
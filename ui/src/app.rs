@@ -1,3 +1,6 @@
+// `html!` expands child components into unit `let` bindings
+#![allow(clippy::let_unit_value)]
+
 use crate::components::spell_component::SpellComponent;
 use yew::prelude::*;
 
@@ -1,9 +1,14 @@
 use yew::prelude::*;
 
-use lib::config::handler::SettingsHandler;
-use lib::config::settings::{Settings, SpellApi, SpellDatasource, SpellSettings};
+use lib::config::handler::{SettingsHandler, SpellUsecase};
+use lib::config::registry::ConfigurationError;
+use lib::config::settings::{
+    CasterSettings, Settings, SpellAnnotationSettings, SpellApi, SpellCacheSettings,
+    SpellDatabaseSettings, SpellDatasource, SpellDeckSettings, SpellSettings, SpellSlotsSettings,
+    SpellbookSettings,
+};
 use lib::core::entity::spell::Spell;
-use lib::core::usecase::spell::SpellInterface;
+use lib::core::usecase::spell::{SpellFilter, SpellInterface};
 
 pub enum FetchState<T> {
     NotFetching,
@@ -13,7 +18,7 @@ pub enum FetchState<T> {
 }
 
 pub enum Msg {
-    SetSpellFetchState(FetchState<Spell>),
+    SetSpellFetchState(Box<FetchState<Spell>>),
     GetSpell,
     // GetError,
 }
@@ -28,28 +33,40 @@ impl SpellComponent {
             spell_settings: SpellSettings {
                 spell_api: SpellApi {
                     url: String::from("https://www.dnd5eapi.co/graphql"),
+                    ..Default::default()
                 },
                 spell_datasource: SpellDatasource {
                     remote_type: String::from("graphql"),
+                    path: None,
                     cache_time: 20000,
+                    max_concurrent_requests: 8,
                 },
+                homebrew: vec![],
             },
+            spell_cache_settings: SpellCacheSettings {
+                enabled: false,
+                ..Default::default()
+            },
+            spellbook_settings: SpellbookSettings::default(),
+            spell_slots_settings: SpellSlotsSettings::default(),
+            spell_deck_settings: SpellDeckSettings::default(),
+            spell_annotation_settings: SpellAnnotationSettings::default(),
+            caster_settings: CasterSettings::default(),
+            spell_database_settings: SpellDatabaseSettings::default(),
         }
     }
 
-    fn setup_spell_usecase() -> impl SpellInterface {
+    fn setup_spell_usecase() -> Result<SpellUsecase, ConfigurationError> {
         let settings = SpellComponent::get_settings();
         let handler = SettingsHandler::new(settings);
         handler.setup_spell_usecase()
     }
 
     async fn get_random_spell() -> Result<Spell, String> {
-        let level: Option<f64> = None;
-        let classes: Vec<String> = vec![];
-        let exact_level = false;
+        let filter = SpellFilter::default();
 
-        let mut usecase = SpellComponent::setup_spell_usecase();
-        let res = usecase.get_random_spell(level, classes, exact_level).await;
+        let mut usecase = SpellComponent::setup_spell_usecase().map_err(|err| err.to_string())?;
+        let res = usecase.get_random_spell(filter).await;
 
         match res {
             Ok(spell) => Ok(spell),
@@ -71,18 +88,18 @@ impl Component for SpellComponent {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::SetSpellFetchState(fetch_state) => {
-                self.spell = fetch_state;
+                self.spell = *fetch_state;
                 true
             }
             Msg::GetSpell => {
                 ctx.link().send_future(async {
                     match SpellComponent::get_random_spell().await {
-                        Ok(s) => Msg::SetSpellFetchState(FetchState::Success(s)),
-                        Err(err) => Msg::SetSpellFetchState(FetchState::Failed(err)),
+                        Ok(s) => Msg::SetSpellFetchState(Box::new(FetchState::Success(s))),
+                        Err(err) => Msg::SetSpellFetchState(Box::new(FetchState::Failed(err))),
                     }
                 });
                 ctx.link()
-                    .send_message(Msg::SetSpellFetchState(FetchState::Fetching));
+                    .send_message(Msg::SetSpellFetchState(Box::new(FetchState::Fetching)));
                 false
             }
         }